@@ -0,0 +1,47 @@
+//! 比较按名字读写与通过FieldHandle读写的耗时
+//! 运行: cargo bench --bench field_access
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use bitflags::Layout;
+
+const ROUNDS: u32 = 1_000_000;
+
+fn measure<F: FnMut(u32) -> u32>(name: &str, mut f: F) -> Duration {
+    let begin = Instant::now();
+    let mut acc = 0u32;
+    for i in 0..ROUNDS {
+        acc = acc.wrapping_add(f(black_box(i)));
+    }
+    black_box(acc);
+    let elapsed = begin.elapsed();
+    println!("{:<24}{:>10.2} ns/iter", name, elapsed.as_nanos() as f64 / ROUNDS as f64);
+    elapsed
+}
+
+fn main() {
+    let mut layout: Layout<u32> = Layout::new();
+    layout.insert("mode".into(), 0, 3).unwrap();
+    layout.insert("ready".into(), 3, 1).unwrap();
+    layout.insert_signed("offset".into(), 8, 12).unwrap();
+    layout.insert("channel".into(), 24, 8).unwrap();
+
+    let by_key = measure("get/set by key", |raw| {
+        let mut value = layout.value(raw);
+        let mode = value.get_value("mode").unwrap();
+        value.set_value("channel", mode).unwrap();
+        value.get_value("offset").unwrap() ^ value.get_data()
+    });
+
+    let mode = layout.field("mode").unwrap();
+    let channel = layout.field("channel").unwrap();
+    let offset = layout.field("offset").unwrap();
+    let by_handle = measure("get/set by handle", |raw| {
+        let mut value = layout.value(raw);
+        let m = value.get(mode);
+        value.set(channel, m).unwrap();
+        value.get(offset) ^ value.get_data()
+    });
+
+    println!("speedup: {:.1}x", by_key.as_secs_f64() / by_handle.as_secs_f64());
+}
@@ -0,0 +1,77 @@
+use std::fmt;
+
+/**
+ * 位域的访问权限,由RegisterBank在读写时执行
+ * ReadOnly(RO): 只读,写入被拒绝或忽略
+ * WriteOnly(WO): 只写,读出为0
+ * ReadWrite(RW): 可读可写
+ * WriteOneToClear(W1C): 写1清零,写0不变
+ * WriteOneToSet(W1S): 写1置位,写0不变
+ * ReadToClear(RC): 只读,读出后清零
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Access {
+    ReadOnly,
+    WriteOnly,
+    #[default]
+    ReadWrite,
+    WriteOneToClear,
+    WriteOneToSet,
+    ReadToClear,
+}
+
+impl Access {
+    /**
+     * @description: 由缩写解析访问权限,不区分大小写
+     * @param {&str} name   缩写,RO、WO、RW、W1C、W1S或RC
+     * @return {Option}     缩写有效时返回访问权限
+     */
+    pub fn from_name(name: &str) -> Option<Access> {
+        match name.to_ascii_uppercase().as_str() {
+            "RO" => Some(Access::ReadOnly),
+            "WO" => Some(Access::WriteOnly),
+            "RW" => Some(Access::ReadWrite),
+            "W1C" => Some(Access::WriteOneToClear),
+            "W1S" => Some(Access::WriteOneToSet),
+            "RC" => Some(Access::ReadToClear),
+            _ => None,
+        }
+    }
+    /**
+     * @description: 获取访问权限的缩写
+     * @param {*} self  自己
+     * @return {&str}   缩写
+     */
+    pub fn name(self) -> &'static str {
+        match self {
+            Access::ReadOnly => "RO",
+            Access::WriteOnly => "WO",
+            Access::ReadWrite => "RW",
+            Access::WriteOneToClear => "W1C",
+            Access::WriteOneToSet => "W1S",
+            Access::ReadToClear => "RC",
+        }
+    }
+    /**
+     * @description: 软件能否读出位域的值
+     * @param {*} self  自己
+     * @return {bool}   可读时返回true
+     */
+    pub fn is_readable(self) -> bool {
+        self != Access::WriteOnly
+    }
+    /**
+     * @description: 软件写入是否会改变位域
+     * @param {*} self  自己
+     * @return {bool}   可写时返回true
+     */
+    pub fn is_writable(self) -> bool {
+        !matches!(self, Access::ReadOnly | Access::ReadToClear)
+    }
+}
+
+impl fmt::Display for Access {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
@@ -0,0 +1,305 @@
+use crate::access::Access;
+use crate::bitfield::{BitStorage, Bitfield};
+use crate::error::BitfieldError;
+use crate::layout::{BitfieldValue, Layout};
+
+/**
+ * 寄存器组中的一个寄存器:名字、地址偏移、复位值以及保存当前值的位域处理结构体
+ */
+#[derive(Debug)]
+struct Register<T> {
+    name: String,
+    offset: u64,
+    reset: T,
+    bitfield: Bitfield<T>,
+}
+
+/**
+ * 按地址偏移组织的一组同宽度寄存器,读写时执行每个位域的访问权限
+ * 位域通过"寄存器.位域"形式的路径访问,寄存器按地址偏移排序
+ */
+#[derive(Debug)]
+pub struct RegisterBank<T> {
+    registers: Vec<Register<T>>,
+}
+
+/**
+ * 一个寄存器内按访问权限划分的位掩码,别名位域不参与划分,不属于任何位域的位按可读可写处理
+ */
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct AccessMasks {
+    write_only: u128,
+    read_to_clear: u128,
+    write_one_to_clear: u128,
+    write_one_to_set: u128,
+    writable: u128,
+}
+
+impl AccessMasks {
+    /**
+     * @description: 按位域的访问权限计算掩码
+     * @param {&Layout} layout  位域布局
+     * @return {AccessMasks}    掩码
+     */
+    pub(crate) fn new<T: BitStorage>(layout: &Layout<T>) -> AccessMasks {
+        let mut masks = AccessMasks { writable: T::mask(T::BITS), ..AccessMasks::default() };
+        for (_, value) in layout.fields().filter(|(_, value)| !value.is_alias()) {
+            let bits = T::mask(value.length()) << value.offset();
+            masks.writable &= !bits;
+            match value.access() {
+                Access::ReadOnly => {}
+                Access::WriteOnly => {
+                    masks.write_only |= bits;
+                    masks.writable |= bits;
+                }
+                Access::ReadWrite => masks.writable |= bits,
+                Access::WriteOneToClear => masks.write_one_to_clear |= bits,
+                Access::WriteOneToSet => masks.write_one_to_set |= bits,
+                Access::ReadToClear => masks.read_to_clear |= bits,
+            }
+        }
+        masks
+    }
+    /**
+     * @description: 按访问权限计算软件读出的值
+     * @param {*} self  掩码
+     * @param {u128} current    寄存器当前值
+     * @return {(u128, u128)}   (读出的值, 读后寄存器的值)
+     */
+    pub(crate) fn read(self, current: u128) -> (u128, u128) {
+        (current & !self.write_only, current & !self.read_to_clear)
+    }
+    /**
+     * @description: 按访问权限计算软件写入后的寄存器值
+     * @param {*} self  掩码
+     * @param {u128} current    寄存器当前值
+     * @param {u128} value  写入的值
+     * @return {u128}   写入后寄存器的值
+     */
+    pub(crate) fn write(self, current: u128, value: u128) -> u128 {
+        let next = (current & !self.writable) | (value & self.writable);
+        (next & !(value & self.write_one_to_clear)) | (value & self.write_one_to_set)
+    }
+}
+
+/**
+ * @description: 把"寄存器.位域"形式的路径拆分为寄存器名字和位域名字
+ * @param {&str} path   路径
+ * @return {Result} 成功，返回(寄存器名字, 位域名字),路径中没有'.'时，返回失败原因
+ */
+pub(crate) fn split_path(path: &str) -> Result<(&str, &str), BitfieldError> {
+    path.split_once('.').ok_or_else(|| BitfieldError::KeyNotFound { key: path.to_string() })
+}
+
+impl<T: BitStorage> RegisterBank<T> {
+    /**
+     * @description: 创建空的寄存器组
+     * @return {RegisterBank}   寄存器组
+     */
+    pub fn new() -> RegisterBank<T> {
+        RegisterBank { registers: Vec::new() }
+    }
+    /**
+     * @description: 增加一个寄存器,寄存器的初始值为复位值
+     * @param {*} mut self  可变的自己
+     * @param {String} name 寄存器名字,不能包含'.'
+     * @param {u64} offset  地址偏移
+     * @param {Layout} layout   寄存器的位域布局
+     * @param {T} reset 复位值
+     * @return {Result} 成功，无信息，名字或地址偏移已被占用时，返回失败原因
+     */
+    pub fn add(&mut self, name: String, offset: u64, layout: Layout<T>, reset: T) -> Result<(), BitfieldError> {
+        if name.contains('.') || self.registers.iter().any(|register| register.name == name) {
+            return Err(BitfieldError::DuplicateKey { key: name });
+        }
+        if let Some(register) = self.registers.iter().find(|register| register.offset == offset) {
+            return Err(BitfieldError::AddressInUse { offset, key: register.name.clone() });
+        }
+        let index = self.registers.partition_point(|register| register.offset < offset);
+        self.registers.insert(index, Register { name, offset, reset, bitfield: Bitfield::from_layout(layout, reset) });
+        Ok(())
+    }
+    /**
+     * @description: 把全部寄存器恢复为复位值
+     * @param {*} mut self  可变的自己
+     * @return {无} 无
+     */
+    pub fn reset(&mut self) {
+        for register in &mut self.registers {
+            register.bitfield.set_data(register.reset);
+        }
+    }
+    /**
+     * @description: 获取寄存器个数
+     * @param {*} self  不可变的自己
+     * @return {usize}  寄存器个数
+     */
+    pub fn len(&self) -> usize {
+        self.registers.len()
+    }
+    /**
+     * @description: 寄存器组中是否没有任何寄存器
+     * @param {*} self  不可变的自己
+     * @return {bool}   没有寄存器时返回true
+     */
+    pub fn is_empty(&self) -> bool {
+        self.registers.is_empty()
+    }
+    /**
+     * @description: 按地址偏移顺序遍历寄存器
+     * @param {*} self  不可变的自己
+     * @return {Iterator}   (寄存器名字, 地址偏移, 位域处理结构体)
+     */
+    pub fn registers(&self) -> impl Iterator<Item = (&str, u64, &Bitfield<T>)> {
+        self.registers.iter().map(|register| (register.name.as_str(), register.offset, &register.bitfield))
+    }
+    /**
+     * @description: 按名字查找寄存器的序号
+     * @param {*} self  不可变的自己
+     * @param {&str} name   寄存器名字
+     * @return {Result} 成功，返回序号,失败时，返回失败原因
+     */
+    fn index(&self, name: &str) -> Result<usize, BitfieldError> {
+        self.registers
+            .iter()
+            .position(|register| register.name == name)
+            .ok_or_else(|| BitfieldError::KeyNotFound { key: name.to_string() })
+    }
+    /**
+     * @description: 按地址偏移查找寄存器的序号
+     * @param {*} self  不可变的自己
+     * @param {u64} offset  地址偏移
+     * @return {Result} 成功，返回序号,失败时，返回失败原因
+     */
+    fn index_at(&self, offset: u64) -> Result<usize, BitfieldError> {
+        self.registers
+            .binary_search_by_key(&offset, |register| register.offset)
+            .map_err(|_| BitfieldError::AddressNotFound { offset })
+    }
+    /**
+     * @description: 获取寄存器,直接读写返回的结构体不经过访问权限检查
+     * @param {*} self  不可变的自己
+     * @param {&str} name   寄存器名字
+     * @return {Result} 成功，返回位域处理结构体,失败时，返回失败原因
+     */
+    pub fn register(&self, name: &str) -> Result<&Bitfield<T>, BitfieldError> {
+        Ok(&self.registers[self.index(name)?].bitfield)
+    }
+    /**
+     * @description: 获取可修改的寄存器,用于模拟硬件一侧的修改,不经过访问权限检查
+     * @param {*} mut self  可变的自己
+     * @param {&str} name   寄存器名字
+     * @return {Result} 成功，返回位域处理结构体,失败时，返回失败原因
+     */
+    pub fn register_mut(&mut self, name: &str) -> Result<&mut Bitfield<T>, BitfieldError> {
+        let index = self.index(name)?;
+        Ok(&mut self.registers[index].bitfield)
+    }
+    /**
+     * @description: 获取寄存器的地址偏移
+     * @param {*} self  不可变的自己
+     * @param {&str} name   寄存器名字
+     * @return {Result} 成功，返回地址偏移,失败时，返回失败原因
+     */
+    pub fn offset(&self, name: &str) -> Result<u64, BitfieldError> {
+        Ok(self.registers[self.index(name)?].offset)
+    }
+    /**
+     * @description: 获取地址偏移处寄存器的名字
+     * @param {*} self  不可变的自己
+     * @param {u64} offset  地址偏移
+     * @return {Result} 成功，返回寄存器名字,失败时，返回失败原因
+     */
+    pub fn name_at(&self, offset: u64) -> Result<&str, BitfieldError> {
+        Ok(&self.registers[self.index_at(offset)?].name)
+    }
+    /**
+     * @description: 按"寄存器.位域"路径获取位域设置信息
+     * @param {*} self  不可变的自己
+     * @param {&str} path   路径
+     * @return {Result} 成功，返回位域信息,失败时，返回失败原因
+     */
+    pub fn get_param(&self, path: &str) -> Result<&BitfieldValue, BitfieldError> {
+        let (register, field) = split_path(path)?;
+        self.register(register)?.get_param(field)
+    }
+    /**
+     * @description: 按访问权限读取整个寄存器,只写位域读出为0,读清零位域在读出后清零
+     * @param {*} mut self  可变的自己
+     * @param {u64} offset  地址偏移
+     * @return {Result} 成功，返回读出的值,失败时，返回失败原因
+     */
+    pub fn read(&mut self, offset: u64) -> Result<T, BitfieldError> {
+        let index = self.index_at(offset)?;
+        let register = &mut self.registers[index].bitfield;
+        let (value, next) = AccessMasks::new(register.layout()).read(register.get_data().to_raw());
+        register.set_data(T::from_raw(next));
+        Ok(T::from_raw(value))
+    }
+    /**
+     * @description: 按访问权限写入整个寄存器,只读和读清零位域保持不变,写1清零和写1置位位域只响应写1的位
+     * @param {*} mut self  可变的自己
+     * @param {u64} offset  地址偏移
+     * @param {T} value     写入的值
+     * @return {Result} 成功，无信息，失败时，返回失败原因
+     */
+    pub fn write(&mut self, offset: u64, value: T) -> Result<(), BitfieldError> {
+        let index = self.index_at(offset)?;
+        let register = &mut self.registers[index].bitfield;
+        let next = AccessMasks::new(register.layout()).write(register.get_data().to_raw(), value.to_raw());
+        register.set_data(T::from_raw(next));
+        Ok(())
+    }
+    /**
+     * @description: 按"寄存器.位域"路径读取位域,只写位域拒绝读取,读清零位域在读出后清零
+     * @param {*} mut self  可变的自己
+     * @param {&str} path   路径
+     * @return {Result} 成功，返回位域的值,失败时，返回失败原因
+     */
+    pub fn get_value(&mut self, path: &str) -> Result<T, BitfieldError> {
+        let (register, field) = split_path(path)?;
+        let index = self.index(register)?;
+        let bitfield = &mut self.registers[index].bitfield;
+        let access = bitfield.get_param(field)?.access();
+        if !access.is_readable() {
+            return Err(BitfieldError::AccessDenied { path: path.to_string(), access });
+        }
+        let value = bitfield.get_value(field)?;
+        if access == Access::ReadToClear {
+            let handle = bitfield.field(field)?;
+            bitfield.set(handle, T::from_raw(0))?;
+        }
+        Ok(value)
+    }
+    /**
+     * @description: 按"寄存器.位域"路径写入位域,只读和读清零位域拒绝写入,写1清零和写1置位位域只响应写1的位
+     * @param {*} mut self  可变的自己
+     * @param {&str} path   路径
+     * @param {T} value     要写入的值
+     * @return {Result} 成功，无信息，失败时，返回失败原因
+     */
+    pub fn set_value(&mut self, path: &str, value: T) -> Result<(), BitfieldError> {
+        let (register, field) = split_path(path)?;
+        let index = self.index(register)?;
+        let bitfield = &mut self.registers[index].bitfield;
+        let access = bitfield.get_param(field)?.access();
+        if !access.is_writable() {
+            return Err(BitfieldError::AccessDenied { path: path.to_string(), access });
+        }
+        let current = bitfield.get_data().to_raw();
+        let bits = bitfield.layout().set_value(T::from_raw(0), field, value)?.to_raw();
+        let next = match access {
+            Access::WriteOneToClear => current & !bits,
+            Access::WriteOneToSet => current | bits,
+            _ => bitfield.layout().set_value(T::from_raw(current), field, value)?.to_raw(),
+        };
+        bitfield.set_data(T::from_raw(next));
+        Ok(())
+    }
+}
+
+impl<T: BitStorage> Default for RegisterBank<T> {
+    fn default() -> Self {
+        RegisterBank::new()
+    }
+}
@@ -1,1382 +1,521 @@
-
-use std::collections::HashMap;
-use std::mem;
-
-/**
- * 基数结构体,原始数据存放位置
- */
-#[derive(Debug)]
-struct BitfieldBase<T> {
-    bitfielddata: T,
-}
-
-/**
- * 位域数据存放结构体
- */
-#[derive(Debug)]
-pub struct BitfieldValue {
-    start : u8 ,
-    length : u8 ,
-}
-
-/**
- * 位域数据处理结构体
- */
-#[derive(Debug)]
-pub struct Bitfield<T> {
-    bitfield: BitfieldBase<T>,
-    bitmap: HashMap<String,BitfieldValue>,
-}
-/**
- * 为 u8 数据 实现按位读写
- */
-#[allow(dead_code)]
-impl Bitfield<u8> {
-    /**
-     * @description:  静态函数，用于创建一个u8位域处理结构体
-     * @param {String} key  位域名字
-     * @param {u8} bitfielddata 需要做位域处理的数据
-     * @param {u8} start    位域开始地址
-     * @param {u8} length   位域长度
-     * @return {Result} 创建成功，返回一个Result枚举，失败返回失败信息
-     */    
-    pub fn create_u8(key:String, bitfielddata : u8 , start : u8 , length : u8) -> Result<Bitfield<u8>,String> {
-        let size = mem::size_of::<u8>() as u8;
-        if start > size * 8 - 1 {
-            let format = format!("Illegal starting position;start{}",start);
-            return Err(format);
-        }
-        else if (start + length) > size * 8  {
-            let format = format!("Illegal total length;start:{},length:{}",start ,length);
-            return Err(format);
-        }
-        let valuetype = BitfieldValue{start:start,length:length};
-        let mut bitmap:HashMap<String,BitfieldValue> = HashMap::new();
-        bitmap.insert(key, valuetype);
-        Ok(Bitfield{
-            bitfield:BitfieldBase{bitfielddata:bitfielddata},
-            bitmap:bitmap,
-        })
-    }
-    /**
-     * @description:    打印位域结构体
-     * @param {*} self  不可变的自己
-     * @return {无}      无
-     */    
-    pub fn traversal(& self){
-        println!("{:#?}",self);
-    }
-    /**
-     * @description:    设置需要做位域处理的数据
-     * @param {*} mut self  可变的自己
-     * @param {u8} bitfielddata 需要做位域处理的数据
-     * @return {无} 无
-     */    
-    pub fn set_data(& mut self , bitfielddata :u8){
-        self.bitfield.bitfielddata = bitfielddata ;
-    }
-    /**
-     * @description: 获取做位域处理的数据
-     * @param {*} self  不可变的自己
-     * @return {无} 无
-     */    
-    pub fn get_data(& self) -> u8 {
-        self.bitfield.bitfielddata
-    }
-    /**
-     * @description: 插入一个位域信息
-     * @param {*} mut self  可变的自己
-     * @param {String} key  位域名字
-     * @param {u8} start    位域开始地址
-     * @param {u8} length   位域长度
-     * @return {Result}     成功，无信息，失败时，返回失败原因
-     */    
-    pub fn insert(& mut self , key:String , start : u8 , length : u8) -> Result<(), String>{
-        let size = mem::size_of::<u8>() as u8;
-        if start > size * 8 - 1 {
-            let format = format!("Insert failed,Illegal starting position;start{}",start);
-            return Err(format);
-        }
-        else if (start + length) > size * 8  {
-            let format = format!("Insert failed,Illegal total length;start:{},length:{}",start ,length);
-            return Err(format);
-        }
-        match self.bitmap.insert(key, BitfieldValue{start:start,length:length}){
-            Some(_) =>{
-                let format = format!("Insert failed,Key already exists");
-                Err(format)
-            },
-            None => {Ok(())}
-        }
-        
-    }
-    /**
-     * @description:    修改一个位域信息
-     * @param {*} mut self  可变的自己
-     * @param {String} oldkey   旧位域名字
-     * @param {String} newkey   新位域名字
-     * @param {u8} start        位域开始地址
-     * @param {u8} length       位域长度
-     * @return {Result} 成功，无信息，失败时，返回失败原因
-     */    
-    pub fn modify(& mut self ,oldkey:String , newkey:String , start :u8 , length : u8) -> Result<(),String> {
-        match self.del_param(oldkey.as_str()) {
-            Ok(()) =>{
-                match self.insert(newkey, start, length){
-                    Ok(()) => Ok(()) ,
-                    Err(err) => Err(err) ,
-                }
-            },
-            Err(_) => {
-                let format = format!("Not found key:{}",oldkey);
-                Err(format)
-            },
-        }
-    }
-    /**
-     * @description: 获取位域设置信息
-     * @param {*} self  不可变的自己
-     * @param {&str} key   位域名字
-     * @return {Result} 成功时返回BitfieldValue结构体,失败时，返回失败原因
-     */    
-    pub fn get_param(&self , key : &str) -> Result<&BitfieldValue,String> {
-        match self.bitmap.get(key) {
-            Some(value) => Ok(value),
-            None => {
-                let format = format!("Not found key:{}",key);
-                Err(format)
-            },
-        }
-    }
-    /**
-     * @description: 删除位域信息
-     * @param {*}   可变的自己
-     * @param {&str} key   位域名字
-     * @return {Result} 成功，无信息，失败时，返回失败原因
-     */    
-    pub fn del_param(& mut self,key:&str) ->Result<(),String>{
-        match self.bitmap.remove(key) {
-            Some(_) => Ok(()),
-            None => Err(String::from("Delete failed , key not found")),
-        }
-    }
-    /**
-     * @description: 根据位域信息，获取对应的值
-     * @param {*} self  不可变的自己
-     * @param {&str} key   位域名字
-     * @return {Result} 成功，返回位域对应的数据,失败时，返回失败原因
-     */    
-    pub fn get_value(& self,key:&str) ->Result<u8 , String> {
-        let valuetype = self.bitmap.get(key);
-        match valuetype {
-            Some(value) => {
-                let size = mem::size_of::<u8>() as u8;
-                if value.length == size * 8 {
-                    let resul = self.bitfield.bitfielddata;
-                    Ok(resul)
-                }
-                else {
-                    let resul = (self.bitfield.bitfielddata >> value.start) & ((2u8.pow(value.length as u32)) - 1); //取出指定的位数
-                    Ok(resul)
-                }
-            },
-            None => {
-                let format = format!("Not found key:{}",key);
-                Err(format)
-            },
-        }
-    }
-    /**
-     * @description: 根据位域信息，设置对应的值
-     * @param {*}    可变的自己
-     * @param {&str} key    位域名字
-     * @param {u8} value    要改变位域的对应的值
-     * @return {Result}     成功，无信息，失败，返回失败原因
-     */    
-    pub fn set_value(& mut self,key:&str , value : u8) -> Result<(),String> {
-        match self.bitmap.get(key) {
-            Some(mvalue) => {
-                let size = mem::size_of::<u8>() as u8;
-                if mvalue.start == 0 && mvalue.length == size * 8 {
-                    self.bitfield.bitfielddata = value;
-                    Ok(())
-                }
-                else if value >= 2u8.pow(mvalue.length as u32)  { //防止超范围
-                    let format = format!("The value is out of range,the maximum value is:{},value:{}",(2u8.pow(mvalue.length as u32)) - 1,value);
-                    Err(format)
-                }
-                else {
-                    let tmp = (2u8.pow(mvalue.length as u32)) - 1 << mvalue.start;      //计算预数据
-                    let tmp1 = !tmp & self.bitfield.bitfielddata;    //清空指定位数据
-                    let tmp2 = value << mvalue.start;
-                    self.bitfield.bitfielddata = tmp1 | tmp2 ;
-                    Ok(())
-                }
-            },
-            None => {
-                let format = format!("Not found key:{}",key);
-                Err(format)
-            },
-        }
-    }
-    /**
-     * @description: 获取位数
-     * @param {*} self  不可变的自己
-     * @return {usize}  返回对应的位数
-     */    
-    pub fn get_totalbit(& self) -> usize {
-        8
-    }
-}
-
-#[allow(dead_code)]
-impl Bitfield<i8> {
-    pub fn create_i8(key:String, bitfielddata : i8 , start : u8 , length : u8) -> Result<Bitfield<i8>,String> {
-        let size = mem::size_of::<i8>() as u8;
-        if start > size * 8 - 1 {
-            let format = format!("Illegal starting position;start{}",start);
-            return Err(format);
-        }
-        else if (start + length) > size * 8 {
-            let format = format!("Illegal total length;start:{},length:{}",start ,length);
-            return Err(format);
-        }
-        let valuetype = BitfieldValue{start:start,length:length};
-        let mut bitmap:HashMap<String,BitfieldValue> = HashMap::new();
-        bitmap.insert(key, valuetype);
-        Ok(Bitfield{
-            bitfield:BitfieldBase{bitfielddata:bitfielddata},
-            bitmap:bitmap,
-        })
-    }
-    pub fn traversal(& self){
-        println!("{:#?}",self);
-    }
-    pub fn set_data(& mut self , bitfielddata :i8){
-        self.bitfield.bitfielddata = bitfielddata ;
-    }
-    pub fn get_data(& self) -> i8 {
-        self.bitfield.bitfielddata
-    }
-    pub fn insert(& mut self , key:String , start : u8 , length : u8) -> Result<(), String>{
-        let size = mem::size_of::<i8>() as u8;
-        if start > size * 8 - 1 {
-            let format = format!("Insert failed,Illegal starting position;start{}",start);
-            return Err(format);
-        }
-        else if (start + length) > size * 8  {
-            let format = format!("Insert failed,Illegal total length;start:{},length:{}",start ,length);
-            return Err(format);
-        }
-        match self.bitmap.insert(key, BitfieldValue{start:start,length:length}){
-            Some(_) =>{
-                let format = format!("Insert failed,Key already exists");
-                Err(format)
-            },
-            None => {Ok(())}
-        }
-        
-    }
-    pub fn modify(& mut self ,oldkey:String , newkey:String , start :u8 , length : u8) -> Result<(),String> {
-        match self.del_param(oldkey.as_str()) {
-            Ok(()) =>{
-                match self.insert(newkey, start, length){
-                    Ok(()) => Ok(()) ,
-                    Err(err) => Err(err) ,
-                }
-            },
-            Err(_) => {
-                let format = format!("Not found key:{}",oldkey);
-                Err(format)
-            },
-        }
-    }
-    pub fn get_param(&self , key : &str) -> Result<&BitfieldValue,String> {
-        match self.bitmap.get(key) {
-            Some(value) => Ok(value),
-            None => {
-                let format = format!("Not found key:{}",key);
-                Err(format)
-            },
-        }
-    }
-    pub fn del_param(& mut self,key:&str) ->Result<(),String>{
-        match self.bitmap.remove(key) {
-            Some(_) => Ok(()),
-            None => Err(String::from("Delete failed , key not found")),
-        }
-    }
-    pub fn get_value(& self,key:&str) ->Result<i8 , String> {
-        let valuetype = self.bitmap.get(key);
-        match valuetype {
-            Some(value) => {
-                let size = mem::size_of::<i8>() as u8;
-                if value.length == size * 8 {
-                    let resul = self.bitfield.bitfielddata ;
-                    Ok(resul)
-                }
-                else {
-                    let resul = (self.bitfield.bitfielddata >> value.start) & ((2i8.pow(value.length as u32)) - 1); //取出指定的位数
-                    Ok(resul)
-                }
-            },
-            None => {
-                let format = format!("Not found key:{}",key);
-                Err(format)
-            },
-        }
-    }
-    pub fn set_value(& mut self,key:&str , value : i8) -> Result<(),String> {
-        match self.bitmap.get(key) {
-            Some(mvalue) => {
-                let size = mem::size_of::<i8>() as u8;
-                if mvalue.start == 0 && mvalue.length == size * 8 {
-                    self.bitfield.bitfielddata = value;
-                    Ok(())
-                }
-                else if value >= 2i8.pow(mvalue.length as u32)  { //防止超范围
-                    let format = format!("The value is out of range,the maximum value is:{},value:{}",(2i8.pow(mvalue.length as u32)) - 1,value);
-                    Err(format)
-                }
-                else {
-                    let tmp = (2i8.pow(mvalue.length as u32)) - 1 << mvalue.start;      //计算预数据
-                    let tmp1 = !tmp & self.bitfield.bitfielddata;    //清空指定位数据
-                    let tmp2 = value << mvalue.start;
-                    self.bitfield.bitfielddata = tmp1 | tmp2 ;
-                    Ok(())
-                }
-            },
-            None => {
-                let format = format!("Not found key:{}",key);
-                Err(format)
-            },
-        }
-    }
-    pub fn get_totalbit(& self) -> usize {
-        8
-    }
-}
-
-#[allow(dead_code)]
-impl Bitfield<u16> {
-    pub fn create_u16(key:String, bitfielddata : u16 , start : u8 , length : u8) -> Result<Bitfield<u16>,String> {
-        let size = mem::size_of::<u16>() as u8;
-        if start > size * 8 - 1 {
-            let format = format!("Illegal starting position;start{}",start);
-            return Err(format);
-        }
-        else if (start + length) > size * 8  {
-            let format = format!("Illegal total length;start:{},length:{}",start ,length);
-            return Err(format);
-        }
-        let valuetype = BitfieldValue{start:start,length:length};
-        let mut bitmap:HashMap<String,BitfieldValue> = HashMap::new();
-        bitmap.insert(key, valuetype);
-        Ok(Bitfield{
-            bitfield:BitfieldBase{bitfielddata:bitfielddata},
-            bitmap:bitmap,
-        })
-    }
-    pub fn traversal(& self){
-        println!("{:#?}",self);
-    }
-    pub fn set_data(& mut self , bitfielddata :u16){
-        self.bitfield.bitfielddata = bitfielddata ;
-    }
-    pub fn get_data(& self) -> u16 {
-        self.bitfield.bitfielddata
-    }
-    pub fn insert(& mut self , key:String , start : u8 , length : u8) -> Result<(), String>{
-        let size = mem::size_of::<u16>() as u8;
-        if start > size * 8 - 1 {
-            let format = format!("Insert failed,Illegal starting position;start{}",start);
-            return Err(format);
-        }
-        else if (start + length) > size * 8  {
-            let format = format!("Insert failed,Illegal total length;start:{},length:{}",start ,length);
-            return Err(format);
-        }
-        match self.bitmap.insert(key, BitfieldValue{start:start,length:length}){
-            Some(_) =>{
-                let format = format!("Insert failed,Key already exists",);
-                Err(format)
-            },
-            None => {Ok(())}
-        }
-        
-    }
-    pub fn modify(& mut self ,oldkey:String , newkey:String , start :u8 , length : u8) -> Result<(),String> {
-        match self.del_param(oldkey.as_str()) {
-            Ok(()) =>{
-                match self.insert(newkey, start, length){
-                    Ok(()) => Ok(()) ,
-                    Err(err) => Err(err) ,
-                }
-            },
-            Err(_) => {
-                let format = format!("Not found key:{}",oldkey);
-                Err(format)
-            },
-        }
-    }
-    pub fn get_param(&self , key : &str) -> Result<&BitfieldValue,String> {
-        match self.bitmap.get(key) {
-            Some(value) => Ok(value),
-            None => {
-                let format = format!("Not found key:{}",key);
-                Err(format)
-            },
-        }
-    }
-    pub fn del_param(& mut self,key:&str) ->Result<(),String>{
-        match self.bitmap.remove(key) {
-            Some(_) => Ok(()),
-            None => Err(String::from("Delete failed , key not found")),
-        }
-    }
-    pub fn get_value(& self,key:&str) ->Result<u16 , String> {
-        let valuetype = self.bitmap.get(key);
-        match valuetype {
-            Some(value) => {
-                let size = mem::size_of::<u16>() as u8;
-                if value.length == size * 8 {
-                    let resul = self.bitfield.bitfielddata;
-                    Ok(resul)
-                }
-                else {
-                    let resul = (self.bitfield.bitfielddata >> value.start) & ((2u16.pow(value.length as u32)) - 1); //取出指定的位数
-                    Ok(resul)
-                }
-            },
-            None => {
-                let format = format!("Not found key:{}",key);
-                Err(format)
-            },
-        }
-    }
-    pub fn set_value(& mut self,key:&str , value : u16) -> Result<(),String> {
-        match self.bitmap.get(key) {
-            Some(mvalue) => {
-                let size = mem::size_of::<u16>() as u8;
-                if mvalue.start == 0 && mvalue.length == size * 8 {
-                    self.bitfield.bitfielddata = value;
-                    Ok(())
-                }
-                else if value >= 2u16.pow(mvalue.length as u32)  { //防止超范围
-                    let format = format!("The value is out of range,the maximum value is:{},value:{}",(2u16.pow(mvalue.length as u32)) - 1,value);
-                    Err(format)
-                }
-                else {
-                    let tmp = (2u16.pow(mvalue.length as u32)) - 1 << mvalue.start;      //计算预数据
-                    let tmp1 = !tmp & self.bitfield.bitfielddata;    //清空指定位数据
-                    let tmp2 = value << mvalue.start;
-                    self.bitfield.bitfielddata = tmp1 | tmp2 ;
-                    Ok(())
-                }
-            },
-            None => {
-                let format = format!("Not found key:{}",key);
-                Err(format)
-            },
-        }
-    }
-    pub fn get_totalbit(& self) -> usize {
-        16
-    }
-}
-
-#[allow(dead_code)]
-impl Bitfield<i16> {
-    pub fn create_i16(key:String, bitfielddata : i16 , start : u8 , length : u8) -> Result<Bitfield<i16>,String> {
-        let size = mem::size_of::<i16>() as u8;
-        if start > size * 8 - 1 {
-            let format = format!("Illegal starting position;start{}",start);
-            return Err(format);
-        }
-        else if (start + length) > size * 8 {
-            let format = format!("Illegal total length;start:{},length:{}",start ,length);
-            return Err(format);
-        }
-        let valuetype = BitfieldValue{start:start,length:length};
-        let mut bitmap:HashMap<String,BitfieldValue> = HashMap::new();
-        bitmap.insert(key, valuetype);
-        Ok(Bitfield{
-            bitfield:BitfieldBase{bitfielddata:bitfielddata},
-            bitmap:bitmap,
-        })
-    }
-    pub fn traversal(& self){
-        println!("{:#?}",self);
-    }
-    pub fn set_data(& mut self , bitfielddata :i16){
-        self.bitfield.bitfielddata = bitfielddata ;
-    }
-    pub fn get_data(& self) -> i16 {
-        self.bitfield.bitfielddata
-    }
-    pub fn insert(& mut self , key:String , start : u8 , length : u8) -> Result<(), String>{
-        let size = mem::size_of::<i16>() as u8;
-        if start > size * 8 - 1 {
-            let format = format!("Insert failed,Illegal starting position;start{}",start);
-            return Err(format);
-        }
-        else if (start + length) > size * 8  {
-            let format = format!("Insert failed,Illegal total length;start:{},length:{}",start ,length);
-            return Err(format);
-        }
-        match self.bitmap.insert(key, BitfieldValue{start:start,length:length}){
-            Some(_) =>{
-                let format = format!("Insert failed,Key already exists");
-                Err(format)
-            },
-            None => {Ok(())}
-        }
-        
-    }
-    pub fn modify(& mut self ,oldkey:String , newkey:String , start :u8 , length : u8) -> Result<(),String> {
-        match self.del_param(oldkey.as_str()) {
-            Ok(()) =>{
-                match self.insert(newkey, start, length){
-                    Ok(()) => Ok(()) ,
-                    Err(err) => Err(err) ,
-                }
-            },
-            Err(_) => {
-                let format = format!("Not found key:{}",oldkey);
-                Err(format)
-            },
-        }
-    }
-    pub fn get_param(&self , key : &str) -> Result<&BitfieldValue,String> {
-        match self.bitmap.get(key) {
-            Some(value) => Ok(value),
-            None => {
-                let format = format!("Not found key:{}",key);
-                Err(format)
-            },
-        }
-    }
-    pub fn del_param(& mut self,key:&str) ->Result<(),String>{
-        match self.bitmap.remove(key) {
-            Some(_) => Ok(()),
-            None => Err(String::from("Delete failed , key not found")),
-        }
-    }
-    pub fn get_value(& self,key:&str) ->Result<i16 , String> {
-        let valuetype = self.bitmap.get(key);
-        match valuetype {
-            Some(value) => {
-                let size = mem::size_of::<i16>() as u8;
-                if value.length == size * 8 {
-                    let resul = self.bitfield.bitfielddata;
-                    Ok(resul)
-                }
-                else {
-                    let resul = (self.bitfield.bitfielddata >> value.start) & ((2i16.pow(value.length as u32)) - 1); //取出指定的位数
-                    Ok(resul)
-                }
-            },
-            None => {
-                let format = format!("Not found key:{}",key);
-                Err(format)
-            },
-        }
-    }
-    pub fn set_value(& mut self,key:&str , value : i16) -> Result<(),String> {
-        match self.bitmap.get(key) {
-            Some(mvalue) => {
-                let size = mem::size_of::<i16>() as u8;
-                if mvalue.start == 0 && mvalue.length == size * 8 {
-                    self.bitfield.bitfielddata = value;
-                    Ok(())
-                }
-                else if value >= 2i16.pow(mvalue.length as u32)  { //防止超范围
-                    let format = format!("The value is out of range,the maximum value is:{},value:{}",(2i16.pow(mvalue.length as u32)) - 1,value);
-                    Err(format)
-                }
-                else {
-                    let tmp = (2i16.pow(mvalue.length as u32)) - 1 << mvalue.start;      //计算预数据
-                    let tmp1 = !tmp & self.bitfield.bitfielddata;    //清空指定位数据
-                    let tmp2 = value << mvalue.start;
-                    self.bitfield.bitfielddata = tmp1 | tmp2 ;
-                    Ok(())
-                }
-            },
-            None => {
-                let format = format!("Not found key:{}",key);
-                Err(format)
-            },
-        }
-    }
-    pub fn get_totalbit(& self) -> usize {
-        16
-    }
-}
-
-#[allow(dead_code)]
-impl Bitfield<u32> {
-    pub fn create_u32(key:String, bitfielddata : u32 , start : u8 , length : u8) -> Result<Bitfield<u32>,String> {
-        let size = mem::size_of::<u32>() as u8;
-        if start > size * 8 - 1 {
-            let format = format!("Illegal starting position;start{}",start);
-            return Err(format);
-        }
-        else if (start + length) > size * 8  {
-            let format = format!("Illegal total length;start:{},length:{}",start ,length);
-            return Err(format);
-        }
-        let valuetype = BitfieldValue{start:start,length:length};
-        let mut bitmap:HashMap<String,BitfieldValue> = HashMap::new();
-        bitmap.insert(key, valuetype);
-        Ok(Bitfield{
-            bitfield:BitfieldBase{bitfielddata:bitfielddata},
-            bitmap:bitmap,
-        })
-    }
-    pub fn traversal(& self){
-        println!("{:#?}",self);
-    }
-    pub fn set_data(& mut self , bitfielddata :u32){
-        self.bitfield.bitfielddata = bitfielddata ;
-    }
-    pub fn get_data(& self) -> u32 {
-        self.bitfield.bitfielddata
-    }
-    pub fn insert(& mut self , key:String , start : u8 , length : u8) -> Result<(), String>{
-        let size = mem::size_of::<u32>() as u8;
-        if start > size * 8 - 1 {
-            let format = format!("Insert failed,Illegal starting position;start{}",start);
-            return Err(format);
-        }
-        else if (start + length) > size * 8  {
-            let format = format!("Insert failed,Illegal total length;start:{},length:{}",start ,length);
-            return Err(format);
-        }
-        match self.bitmap.insert(key, BitfieldValue{start:start,length:length}){
-            Some(_) =>{
-                let format = format!("Insert failed,Key already exists",);
-                Err(format)
-            },
-            None => {Ok(())}
-        }
-        
-    }
-    pub fn modify(& mut self ,oldkey:String , newkey:String , start :u8 , length : u8) -> Result<(),String> {
-        match self.del_param(oldkey.as_str()) {
-            Ok(()) =>{
-                match self.insert(newkey, start, length){
-                    Ok(()) => Ok(()) ,
-                    Err(err) => Err(err) ,
-                }
-            },
-            Err(_) => {
-                let format = format!("Not found key:{}",oldkey);
-                Err(format)
-            },
-        }
-    }
-    pub fn get_param(&self , key : &str) -> Result<&BitfieldValue,String> {
-        match self.bitmap.get(key) {
-            Some(value) => Ok(value),
-            None => {
-                let format = format!("Not found key:{}",key);
-                Err(format)
-            },
-        }
-    }
-    pub fn del_param(& mut self,key:&str) ->Result<(),String>{
-        match self.bitmap.remove(key) {
-            Some(_) => Ok(()),
-            None => Err(String::from("Delete failed , key not found")),
-        }
-    }
-    pub fn get_value(& self,key:&str) ->Result<u32 , String> {
-        let valuetype = self.bitmap.get(key);
-        match valuetype {
-            Some(value) => {
-                let size = mem::size_of::<u32>() as u8;
-                if value.length == size * 8 {
-                    let resul = self.bitfield.bitfielddata;
-                    Ok(resul)
-                }
-                else {
-                    let resul = (self.bitfield.bitfielddata >> value.start) & ((2u32.pow(value.length as u32)) - 1); //取出指定的位数
-                    Ok(resul)
-                }
-            },
-            None => {
-                let format = format!("Not found key:{}",key);
-                Err(format)
-            },
-        }
-    }
-    pub fn set_value(& mut self,key:&str , value : u32) -> Result<(),String> {
-        match self.bitmap.get(key) {
-            Some(mvalue) => {
-                let size = mem::size_of::<u32>() as u8;
-                if mvalue.start == 0 && mvalue.length == size * 8 {
-                    self.bitfield.bitfielddata = value;
-                    Ok(())
-                }
-                else if value >= 2u32.pow(mvalue.length as u32)  { //防止超范围
-                    let format = format!("The value is out of range,the maximum value is:{},value:{}",(2u32.pow(mvalue.length as u32)) - 1,value);
-                    Err(format)
-                }
-                else {
-                    let tmp = (2u32.pow(mvalue.length as u32)) - 1 << mvalue.start;      //计算预数据
-                    let tmp1 = !tmp & self.bitfield.bitfielddata;    //清空指定位数据
-                    let tmp2 = value << mvalue.start;
-                    self.bitfield.bitfielddata = tmp1 | tmp2 ;
-                    Ok(())
-                }
-            },
-            None => {
-                let format = format!("Not found key:{}",key);
-                Err(format)
-            },
-        }
-    }
-    pub fn get_totalbit(& self) -> usize {
-        32
-    }
-}
-
-#[allow(dead_code)]
-impl Bitfield<i32> {
-    pub fn create_i32(key:String, bitfielddata : i32 , start : u8 , length : u8) -> Result<Bitfield<i32>,String> {
-        let size = mem::size_of::<i32>() as u8;
-        if start > size * 8 - 1 {
-            let format = format!("Illegal starting position;start{}",start);
-            return Err(format);
-        }
-        else if (start + length) > size * 8 {
-            let format = format!("Illegal total length;start:{},length:{}",start ,length);
-            return Err(format);
-        }
-        let valuetype = BitfieldValue{start:start,length:length};
-        let mut bitmap:HashMap<String,BitfieldValue> = HashMap::new();
-        bitmap.insert(key, valuetype);
-        Ok(Bitfield{
-            bitfield:BitfieldBase{bitfielddata:bitfielddata},
-            bitmap:bitmap,
-        })
-    }
-    pub fn traversal(& self){
-        println!("{:#?}",self);
-    }
-    pub fn set_data(& mut self , bitfielddata :i32){
-        self.bitfield.bitfielddata = bitfielddata ;
-    }
-    pub fn get_data(& self) -> i32 {
-        self.bitfield.bitfielddata
-    }
-    pub fn insert(& mut self , key:String , start : u8 , length : u8) -> Result<(), String>{
-        let size = mem::size_of::<i32>() as u8;
-        if start > size * 8 - 1 {
-            let format = format!("Insert failed,Illegal starting position;start{}",start);
-            return Err(format);
-        }
-        else if (start + length) > size * 8  {
-            let format = format!("Insert failed,Illegal total length;start:{},length:{}",start ,length);
-            return Err(format);
-        }
-        match self.bitmap.insert(key, BitfieldValue{start:start,length:length}){
-            Some(_) =>{
-                let format = format!("Insert failed,Key already exists");
-                Err(format)
-            },
-            None => {Ok(())}
-        }
-        
-    }
-    pub fn modify(& mut self ,oldkey:String , newkey:String , start :u8 , length : u8) -> Result<(),String> {
-        match self.del_param(oldkey.as_str()) {
-            Ok(()) =>{
-                match self.insert(newkey, start, length){
-                    Ok(()) => Ok(()) ,
-                    Err(err) => Err(err) ,
-                }
-            },
-            Err(_) => {
-                let format = format!("Not found key:{}",oldkey);
-                Err(format)
-            },
-        }
-    }
-    pub fn get_param(&self , key : &str) -> Result<&BitfieldValue,String> {
-        match self.bitmap.get(key) {
-            Some(value) => Ok(value),
-            None => {
-                let format = format!("Not found key:{}",key);
-                Err(format)
-            },
-        }
-    }
-    pub fn del_param(& mut self,key:&str) ->Result<(),String>{
-        match self.bitmap.remove(key) {
-            Some(_) => Ok(()),
-            None => Err(String::from("Delete failed , key not found")),
-        }
-    }
-    pub fn get_value(& self,key:&str) ->Result<i32 , String> {
-        let valuetype = self.bitmap.get(key);
-        match valuetype {
-            Some(value) => {
-                let size = mem::size_of::<i32>() as u8;
-                if value.length == size * 8 {
-                    let resul = self.bitfield.bitfielddata;
-                    Ok(resul)
-                }
-                else {
-                    let resul = (self.bitfield.bitfielddata >> value.start) & ((2i32.pow(value.length as u32)) - 1); //取出指定的位数
-                    Ok(resul)
-                }
-            },
-            None => {
-                let format = format!("Not found key:{}",key);
-                Err(format)
-            },
-        }
-    }
-    pub fn set_value(& mut self,key:&str , value : i32) -> Result<(),String> {
-        match self.bitmap.get(key) {
-            Some(mvalue) => {
-                let size = mem::size_of::<i32>() as u8;
-                if mvalue.start == 0 && mvalue.length == size * 8 {
-                    self.bitfield.bitfielddata = value;
-                    Ok(())
-                }
-                else if value >= 2i32.pow(mvalue.length as u32)  { //防止超范围
-                    let format = format!("The value is out of range,the maximum value is:{},value:{}",(2i32.pow(mvalue.length as u32)) - 1,value);
-                    Err(format)
-                }
-                else {
-                    let tmp = (2i32.pow(mvalue.length as u32)) - 1 << mvalue.start;      //计算预数据
-                    let tmp1 = !tmp & self.bitfield.bitfielddata;    //清空指定位数据
-                    let tmp2 = value << mvalue.start;
-                    self.bitfield.bitfielddata = tmp1 | tmp2 ;
-                    Ok(())
-                }
-            },
-            None => {
-                let format = format!("Not found key:{}",key);
-                Err(format)
-            },
-        }
-    }
-    pub fn get_totalbit(& self) -> usize {
-        32
-    }
-}
-
-#[allow(dead_code)]
-impl Bitfield<u64> {
-    pub fn create_u64(key:String, bitfielddata : u64 , start : u8 , length : u8) -> Result<Bitfield<u64>,String> {
-        let size = mem::size_of::<u64>() as u8;
-        if start > size * 8 - 1 {
-            let format = format!("Illegal starting position;start{}",start);
-            return Err(format);
-        }
-        else if (start + length) > size * 8  {
-            let format = format!("Illegal total length;start:{},length:{}",start ,length);
-            return Err(format);
-        }
-        let valuetype = BitfieldValue{start:start,length:length};
-        let mut bitmap:HashMap<String,BitfieldValue> = HashMap::new();
-        bitmap.insert(key, valuetype);
-        Ok(Bitfield{
-            bitfield:BitfieldBase{bitfielddata:bitfielddata},
-            bitmap:bitmap,
-        })
-    }
-    pub fn traversal(& self){
-        println!("{:#?}",self);
-    }
-    pub fn set_data(& mut self , bitfielddata :u64){
-        self.bitfield.bitfielddata = bitfielddata ;
-    }
-    pub fn get_data(& self) -> u64 {
-        self.bitfield.bitfielddata
-    }
-    pub fn insert(& mut self , key:String , start : u8 , length : u8) -> Result<(), String>{
-        let size = mem::size_of::<u64>() as u8;
-        if start > size * 8 - 1 {
-            let format = format!("Insert failed,Illegal starting position;start{}",start);
-            return Err(format);
-        }
-        else if (start + length) > size * 8  {
-            let format = format!("Insert failed,Illegal total length;start:{},length:{}",start ,length);
-            return Err(format);
-        }
-        match self.bitmap.insert(key, BitfieldValue{start:start,length:length}){
-            Some(_) =>{
-                let format = format!("Insert failed,Key already exists",);
-                Err(format)
-            },
-            None => {Ok(())}
-        }
-        
-    }
-    pub fn modify(& mut self ,oldkey:String , newkey:String , start :u8 , length : u8) -> Result<(),String> {
-        match self.del_param(oldkey.as_str()) {
-            Ok(()) =>{
-                match self.insert(newkey, start, length){
-                    Ok(()) => Ok(()) ,
-                    Err(err) => Err(err) ,
-                }
-            },
-            Err(_) => {
-                let format = format!("Not found key:{}",oldkey);
-                Err(format)
-            },
-        }
-    }
-    pub fn get_param(&self , key : &str) -> Result<&BitfieldValue,String> {
-        match self.bitmap.get(key) {
-            Some(value) => Ok(value),
-            None => {
-                let format = format!("Not found key:{}",key);
-                Err(format)
-            },
-        }
-    }
-    pub fn del_param(& mut self,key:&str) ->Result<(),String>{
-        match self.bitmap.remove(key) {
-            Some(_) => Ok(()),
-            None => Err(String::from("Delete failed , key not found")),
-        }
-    }
-    pub fn get_value(& self,key:&str) ->Result<u64 , String> {
-        let valuetype = self.bitmap.get(key);
-        match valuetype {
-            Some(value) => {
-                let size = mem::size_of::<u64>() as u8;
-                if value.length == size * 8 {
-                    let resul = self.bitfield.bitfielddata;
-                    Ok(resul)
-                }
-                else {
-                    let resul = (self.bitfield.bitfielddata >> value.start) & ((2u64.pow(value.length as u32)) - 1); //取出指定的位数
-                    Ok(resul)
-                }
-            },
-            None => {
-                let format = format!("Not found key:{}",key);
-                Err(format)
-            },
-        }
-    }
-    pub fn set_value(& mut self,key:&str , value : u64) -> Result<(),String> {
-        match self.bitmap.get(key) {
-            Some(mvalue) => {
-                let size = mem::size_of::<u64>() as u8;
-                if mvalue.start == 0 && mvalue.length == size * 8 {
-                    self.bitfield.bitfielddata = value;
-                    Ok(())
-                }
-                else if value >= 2u64.pow(mvalue.length as u32)  { //防止超范围
-                    let format = format!("The value is out of range,the maximum value is:{},value:{}",(2u64.pow(mvalue.length as u32)) - 1,value);
-                    Err(format)
-                }
-                else {
-                    let tmp = (2u64.pow(mvalue.length as u32)) - 1 << mvalue.start;      //计算预数据
-                    let tmp1 = !tmp & self.bitfield.bitfielddata;    //清空指定位数据
-                    let tmp2 = value << mvalue.start;
-                    self.bitfield.bitfielddata = tmp1 | tmp2 ;
-                    Ok(())
-                }
-            },
-            None => {
-                let format = format!("Not found key:{}",key);
-                Err(format)
-            },
-        }
-    }
-    pub fn get_totalbit(& self) -> usize {
-        64
-    }
-}
-
-#[allow(dead_code)]
-impl Bitfield<i64> {
-    pub fn create_i64(key:String, bitfielddata : i64 , start : u8 , length : u8) -> Result<Bitfield<i64>,String> {
-        let size = mem::size_of::<i64>() as u8;
-        if start > size * 8 - 1 {
-            let format = format!("Illegal starting position;start{}",start);
-            return Err(format);
-        }
-        else if (start + length) > size * 8 {
-            let format = format!("Illegal total length;start:{},length:{}",start ,length);
-            return Err(format);
-        }
-        let valuetype = BitfieldValue{start:start,length:length};
-        let mut bitmap:HashMap<String,BitfieldValue> = HashMap::new();
-        bitmap.insert(key, valuetype);
-        Ok(Bitfield{
-            bitfield:BitfieldBase{bitfielddata:bitfielddata},
-            bitmap:bitmap,
-        })
-    }
-    pub fn traversal(& self){
-        println!("{:#?}",self);
-    }
-    pub fn set_data(& mut self , bitfielddata :i64){
-        self.bitfield.bitfielddata = bitfielddata ;
-    }
-    pub fn get_data(& self) -> i64 {
-        self.bitfield.bitfielddata
-    }
-    pub fn insert(& mut self , key:String , start : u8 , length : u8) -> Result<(), String>{
-        let size = mem::size_of::<i64>() as u8;
-        if start > size * 8 - 1 {
-            let format = format!("Insert failed,Illegal starting position;start{}",start);
-            return Err(format);
-        }
-        else if (start + length) > size * 8  {
-            let format = format!("Insert failed,Illegal total length;start:{},length:{}",start ,length);
-            return Err(format);
-        }
-        match self.bitmap.insert(key, BitfieldValue{start:start,length:length}){
-            Some(_) =>{
-                let format = format!("Insert failed,Key already exists");
-                Err(format)
-            },
-            None => {Ok(())}
-        }
-        
-    }
-    pub fn modify(& mut self ,oldkey:String , newkey:String , start :u8 , length : u8) -> Result<(),String> {
-        match self.del_param(oldkey.as_str()) {
-            Ok(()) =>{
-                match self.insert(newkey, start, length){
-                    Ok(()) => Ok(()) ,
-                    Err(err) => Err(err) ,
-                }
-            },
-            Err(_) => {
-                let format = format!("Not found key:{}",oldkey);
-                Err(format)
-            },
-        }
-    }
-    pub fn get_param(&self , key : &str) -> Result<&BitfieldValue,String> {
-        match self.bitmap.get(key) {
-            Some(value) => Ok(value),
-            None => {
-                let format = format!("Not found key:{}",key);
-                Err(format)
-            },
-        }
-    }
-    pub fn del_param(& mut self,key:&str) ->Result<(),String>{
-        match self.bitmap.remove(key) {
-            Some(_) => Ok(()),
-            None => Err(String::from("Delete failed , key not found")),
-        }
-    }
-    pub fn get_value(& self,key:&str) ->Result<i64 , String> {
-        let valuetype = self.bitmap.get(key);
-        match valuetype {
-            Some(value) => {
-                let size = mem::size_of::<i64>() as u8;
-                if value.length == size * 8 {
-                    let resul = self.bitfield.bitfielddata ;
-                    Ok(resul)
-                }
-                else {
-                    let resul = (self.bitfield.bitfielddata >> value.start) & ((2i64.pow(value.length as u32)) - 1); //取出指定的位数
-                    Ok(resul)
-                }
-            },
-            None => {
-                let format = format!("Not found key:{}",key);
-                Err(format)
-            },
-        }
-    }
-    pub fn set_value(& mut self,key:&str , value : i64) -> Result<(),String> {
-        match self.bitmap.get(key) {
-            Some(mvalue) => {
-                let size = mem::size_of::<i64>() as u8;
-                if mvalue.start == 0 && mvalue.length == size * 8 {
-                    self.bitfield.bitfielddata = value;
-                    Ok(())
-                }
-                else if value >= 2i64.pow(mvalue.length as u32)  { //防止超范围
-                    let format = format!("The value is out of range,the maximum value is:{},value:{}",(2i64.pow(mvalue.length as u32)) - 1,value);
-                    Err(format)
-                }
-                else {
-                    let tmp = (2i64.pow(mvalue.length as u32)) - 1 << mvalue.start;      //计算预数据
-                    let tmp1 = !tmp & self.bitfield.bitfielddata;    //清空指定位数据
-                    let tmp2 = value << mvalue.start;
-                    self.bitfield.bitfielddata = tmp1 | tmp2 ;
-                    Ok(())
-                }
-            },
-            None => {
-                let format = format!("Not found key:{}",key);
-                Err(format)
-            },
-        }
-    }
-    pub fn get_totalbit(& self) -> usize {
-        64
-    }
-}
-
-#[allow(dead_code)]
-impl Bitfield<u128> {
-    pub fn create_u128(key:String, bitfielddata : u128 , start : u8 , length : u8) -> Result<Bitfield<u128>,String> {
-        let size = mem::size_of::<u128>() as u8;
-        if start > size * 8 - 1 {
-            let format = format!("Illegal starting position;start{}",start);
-            return Err(format);
-        }
-        else if (start + length) > size * 8  {
-            let format = format!("Illegal total length;start:{},length:{}",start ,length);
-            return Err(format);
-        }
-        let valuetype = BitfieldValue{start:start,length:length};
-        let mut bitmap:HashMap<String,BitfieldValue> = HashMap::new();
-        bitmap.insert(key, valuetype);
-        Ok(Bitfield{
-            bitfield:BitfieldBase{bitfielddata:bitfielddata},
-            bitmap:bitmap,
-        })
-    }
-    pub fn traversal(& self){
-        println!("{:#?}",self);
-    }
-    pub fn set_data(& mut self , bitfielddata :u128){
-        self.bitfield.bitfielddata = bitfielddata ;
-    }
-    pub fn get_data(& self) -> u128 {
-        self.bitfield.bitfielddata
-    }
-    pub fn insert(& mut self , key:String , start : u8 , length : u8) -> Result<(), String>{
-        let size = mem::size_of::<u128>() as u8;
-        if start > size * 8 - 1 {
-            let format = format!("Insert failed,Illegal starting position;start{}",start);
-            return Err(format);
-        }
-        else if (start + length) > size * 8  {
-            let format = format!("Insert failed,Illegal total length;start:{},length:{}",start ,length);
-            return Err(format);
-        }
-        match self.bitmap.insert(key, BitfieldValue{start:start,length:length}){
-            Some(_) =>{
-                let format = format!("Insert failed,Key already exists",);
-                Err(format)
-            },
-            None => {Ok(())}
-        }
-        
-    }
-    pub fn modify(& mut self ,oldkey:String , newkey:String , start :u8 , length : u8) -> Result<(),String> {
-        match self.del_param(oldkey.as_str()) {
-            Ok(()) =>{
-                match self.insert(newkey, start, length){
-                    Ok(()) => Ok(()) ,
-                    Err(err) => Err(err) ,
-                }
-            },
-            Err(_) => {
-                let format = format!("Not found key:{}",oldkey);
-                Err(format)
-            },
-        }
-    }
-    pub fn get_param(&self , key : &str) -> Result<&BitfieldValue,String> {
-        match self.bitmap.get(key) {
-            Some(value) => Ok(value),
-            None => {
-                let format = format!("Not found key:{}",key);
-                Err(format)
-            },
-        }
-    }
-    pub fn del_param(& mut self,key:&str) ->Result<(),String>{
-        match self.bitmap.remove(key) {
-            Some(_) => Ok(()),
-            None => Err(String::from("Delete failed , key not found")),
-        }
-    }
-    pub fn get_value(& self,key:&str) ->Result<u128 , String> {
-        let valuetype = self.bitmap.get(key);
-        match valuetype {
-            Some(value) => {
-                let size = mem::size_of::<u128>() as u8;
-                if value.length == size * 8 {
-                    let resul = self.bitfield.bitfielddata ;
-                    Ok(resul)
-                }
-                else {
-                    let resul = (self.bitfield.bitfielddata >> value.start) & ((2u128.pow(value.length as u32)) - 1); //取出指定的位数
-                    Ok(resul)
-                }
-            },
-            None => {
-                let format = format!("Not found key:{}",key);
-                Err(format)
-            },
-        }
-    }
-    pub fn set_value(& mut self,key:&str , value : u128) -> Result<(),String> {
-        match self.bitmap.get(key) {
-            Some(mvalue) => {
-                let size = mem::size_of::<u128>() as u8;
-                if mvalue.start == 0 && mvalue.length == size * 8 {
-                    self.bitfield.bitfielddata = value;
-                    Ok(())
-                }
-                else if value >= 2u128.pow(mvalue.length as u32)  { //防止超范围
-                    let format = format!("The value is out of range,the maximum value is:{},value:{}",(2u128.pow(mvalue.length as u32)) - 1,value);
-                    Err(format)
-                }
-                else {
-                    let tmp = (2u128.pow(mvalue.length as u32)) - 1 << mvalue.start;      //计算预数据
-                    let tmp1 = !tmp & self.bitfield.bitfielddata;    //清空指定位数据
-                    let tmp2 = value << mvalue.start;
-                    self.bitfield.bitfielddata = tmp1 | tmp2 ;
-                    Ok(())
-                }
-            },
-            None => {
-                let format = format!("Not found key:{}",key);
-                Err(format)
-            },
-        }
-    }
-    pub fn get_totalbit(& self) -> usize {
-        128
-    }
-}
-
-#[allow(dead_code)]
-impl Bitfield<i128> {
-    pub fn create_i128(key:String, bitfielddata : i128 , start : u8 , length : u8) -> Result<Bitfield<i128>,String> {
-        let size = mem::size_of::<i128>() as u8;
-        if start > size * 8 - 1 {
-            let format = format!("Illegal starting position;start{}",start);
-            return Err(format);
-        }
-        else if (start + length) > size * 8 {
-            let format = format!("Illegal total length;start:{},length:{}",start ,length);
-            return Err(format);
-        }
-        let valuetype = BitfieldValue{start:start,length:length};
-        let mut bitmap:HashMap<String,BitfieldValue> = HashMap::new();
-        bitmap.insert(key, valuetype);
-        Ok(Bitfield{
-            bitfield:BitfieldBase{bitfielddata:bitfielddata},
-            bitmap:bitmap,
-        })
-    }
-    pub fn traversal(& self){
-        println!("{:#?}",self);
-    }
-    pub fn set_data(& mut self , bitfielddata :i128){
-        self.bitfield.bitfielddata = bitfielddata ;
-    }
-    pub fn get_data(& self) -> i128 {
-        self.bitfield.bitfielddata
-    }
-    pub fn insert(& mut self , key:String , start : u8 , length : u8) -> Result<(), String>{
-        let size = mem::size_of::<i128>() as u8;
-        if start > size * 8 - 1 {
-            let format = format!("Insert failed,Illegal starting position;start{}",start);
-            return Err(format);
-        }
-        else if (start + length) > size * 8  {
-            let format = format!("Insert failed,Illegal total length;start:{},length:{}",start ,length);
-            return Err(format);
-        }
-        match self.bitmap.insert(key, BitfieldValue{start:start,length:length}){
-            Some(_) =>{
-                let format = format!("Insert failed,Key already exists");
-                Err(format)
-            },
-            None => {Ok(())}
-        }
-        
-    }
-    pub fn modify(& mut self ,oldkey:String , newkey:String , start :u8 , length : u8) -> Result<(),String> {
-        match self.del_param(oldkey.as_str()) {
-            Ok(()) =>{
-                match self.insert(newkey, start, length){
-                    Ok(()) => Ok(()) ,
-                    Err(err) => Err(err) ,
-                }
-            },
-            Err(_) => {
-                let format = format!("Not found key:{}",oldkey);
-                Err(format)
-            },
-        }
-    }
-    pub fn get_param(&self , key : &str) -> Result<&BitfieldValue,String> {
-        match self.bitmap.get(key) {
-            Some(value) => Ok(value),
-            None => {
-                let format = format!("Not found key:{}",key);
-                Err(format)
-            },
-        }
-    }
-    pub fn del_param(& mut self,key:&str) ->Result<(),String>{
-        match self.bitmap.remove(key) {
-            Some(_) => Ok(()),
-            None => Err(String::from("Delete failed , key not found")),
-        }
-    }
-    pub fn get_value(& self,key:&str) ->Result<i128 , String> {
-        let valuetype = self.bitmap.get(key);
-        match valuetype {
-            Some(value) => {
-                let size = mem::size_of::<i128>() as u8;
-                if value.length == size * 8 {
-                    let resul = self.bitfield.bitfielddata;
-                    Ok(resul)
-                }
-                else {
-                    let resul = (self.bitfield.bitfielddata >> value.start) & ((2i128.pow(value.length as u32)) - 1); //取出指定的位数
-                    Ok(resul)
-                }
-            },
-            None => {
-                let format = format!("Not found key:{}",key);
-                Err(format)
-            },
-        }
-    }
-    pub fn set_value(& mut self,key:&str , value : i128) -> Result<(),String> {
-        match self.bitmap.get(key) {
-            Some(mvalue) => {
-                let size = mem::size_of::<i128>() as u8;
-                if mvalue.start == 0 && mvalue.length == size * 8 {
-                    self.bitfield.bitfielddata = value;
-                    Ok(())
-                }
-                else if value >= 2i128.pow(mvalue.length as u32)  { //防止超范围
-                    let format = format!("The value is out of range,the maximum value is:{},value:{}",(2i128.pow(mvalue.length as u32)) - 1,value);
-                    Err(format)
-                }
-                else {
-                    let tmp = (2i128.pow(mvalue.length as u32)) - 1 << mvalue.start;      //计算预数据
-                    let tmp1 = !tmp & self.bitfield.bitfielddata;    //清空指定位数据
-                    let tmp2 = value << mvalue.start;
-                    self.bitfield.bitfielddata = tmp1 | tmp2 ;
-                    Ok(())
-                }
-            },
-            None => {
-                let format = format!("Not found key:{}",key);
-                Err(format)
-            },
-        }
-    }
-    pub fn get_totalbit(& self) -> usize {
-        128
-    }
-}
\ No newline at end of file
+use std::fmt::{Debug, Display};
+
+use crate::access::Access;
+use crate::enumeration::EnumTable;
+use crate::error::BitfieldError;
+use crate::handle::FieldHandle;
+use crate::order::{BitOrder, ByteOrder};
+use crate::scaling::Scaling;
+pub use crate::layout::{BitfieldValue, Layout};
+
+/**
+ * 位域存储类型特征,描述一个整数类型的位宽、掩码、移位以及与u128之间的转换
+ * 所有位运算都在u128上完成,因此每种整数类型只需要提供转换即可
+ */
+#[allow(dead_code)]
+pub trait BitStorage: Copy + Debug + Display + PartialEq {
+    /**
+     * 存储类型的位数
+     */
+    const BITS: u8;
+    /**
+     * @description: 按位转换为u128,高位补0(有符号数不做符号扩展)
+     * @param {Self} self  原始数据
+     * @return {u128}   对应的位模式
+     */
+    fn to_raw(self) -> u128;
+    /**
+     * @description: 由u128的低位构造本类型,超出位宽的部分被截断
+     * @param {u128} raw   位模式
+     * @return {Self}   对应的数据
+     */
+    fn from_raw(raw: u128) -> Self;
+    /**
+     * @description: 计算指定长度的低位掩码
+     * @param {u8} length   位域长度
+     * @return {u128}   掩码
+     */
+    fn mask(length: u8) -> u128 {
+        if length >= 128 {
+            u128::MAX
+        } else {
+            (1u128 << length) - 1
+        }
+    }
+    /**
+     * @description: 取出指定位置的位域,结果右对齐
+     * @param {u8} start    位域开始地址
+     * @param {u8} length   位域长度
+     * @return {Self}   位域的值
+     */
+    fn extract(self, start: u8, length: u8) -> Self {
+        Self::from_raw((self.to_raw() >> start) & Self::mask(length))
+    }
+    /**
+     * @description: 把length位的补码符号扩展到u128
+     * @param {u128} raw    右对齐的位模式
+     * @param {u8} length   位域长度
+     * @return {u128}   符号扩展后的位模式
+     */
+    fn sign_extend(raw: u128, length: u8) -> u128 {
+        if length == 0 || length >= 128 || raw & (1u128 << (length - 1)) == 0 {
+            raw
+        } else {
+            raw | !Self::mask(length)
+        }
+    }
+    /**
+     * @description: 将value写入指定位置的位域,其余位保持不变
+     * @param {u8} start    位域开始地址
+     * @param {u8} length   位域长度
+     * @param {Self} value  要写入的值,超出长度的部分被截断
+     * @return {Self}   写入后的数据
+     */
+    fn deposit(self, start: u8, length: u8, value: Self) -> Self {
+        let mask = Self::mask(length) << start;
+        Self::from_raw((self.to_raw() & !mask) | ((value.to_raw() << start) & mask))
+    }
+}
+
+macro_rules! impl_bit_storage {
+    ($($t:ty => $u:ty),* $(,)?) => {
+        $(
+            impl BitStorage for $t {
+                const BITS: u8 = <$t>::BITS as u8;
+                fn to_raw(self) -> u128 {
+                    self as $u as u128
+                }
+                fn from_raw(raw: u128) -> Self {
+                    raw as $u as $t
+                }
+            }
+        )*
+    };
+}
+
+impl_bit_storage!(
+    u8 => u8, i8 => u8,
+    u16 => u16, i16 => u16,
+    u32 => u32, i32 => u32,
+    u64 => u64, i64 => u64,
+    u128 => u128, i128 => u128,
+);
+
+/**
+ * 基数结构体,原始数据存放位置,committed为最近一次提交时的数据,用于跟踪修改过的位域
+ */
+#[derive(Debug)]
+struct BitfieldBase<T> {
+    bitfielddata: T,
+    committed: T,
+}
+
+/**
+ * 位域数据处理结构体
+ */
+#[derive(Debug)]
+pub struct Bitfield<T> {
+    bitfield: BitfieldBase<T>,
+    layout: Layout<T>,
+}
+
+/**
+ * 为所有实现了BitStorage的整数类型实现按位读写,位域信息由内部的Layout管理
+ */
+#[allow(dead_code)]
+impl<T: BitStorage> Bitfield<T> {
+    /**
+     * @description:  静态函数，用于创建一个位域处理结构体
+     * @param {String} key  位域名字
+     * @param {T} bitfielddata 需要做位域处理的数据
+     * @param {u8} start    位域开始地址
+     * @param {u8} length   位域长度
+     * @return {Result} 创建成功，返回一个Result枚举，失败返回失败信息
+     */
+    pub fn new(key: String, bitfielddata: T, start: u8, length: u8) -> Result<Bitfield<T>, BitfieldError> {
+        let mut layout = Layout::new();
+        layout.insert(key, start, length)?;
+        Ok(Bitfield::from_layout(layout, bitfielddata))
+    }
+    /**
+     * @description:  静态函数，用已经构建好的布局创建一个位域处理结构体
+     * @param {Layout} layout  位域布局
+     * @param {T} bitfielddata 需要做位域处理的数据
+     * @return {Bitfield} 位域处理结构体
+     */
+    pub fn from_layout(layout: Layout<T>, bitfielddata: T) -> Bitfield<T> {
+        Bitfield {
+            bitfield: BitfieldBase { bitfielddata, committed: bitfielddata },
+            layout,
+        }
+    }
+    /**
+     * @description: 获取位域布局
+     * @param {*} self  不可变的自己
+     * @return {&Layout}    位域布局
+     */
+    pub fn layout(&self) -> &Layout<T> {
+        &self.layout
+    }
+    /**
+     * @description: 拆分为位域布局和数据
+     * @param {*} self  自己
+     * @return {(Layout, T)}    位域布局和数据
+     */
+    pub fn into_parts(self) -> (Layout<T>, T) {
+        (self.layout, self.bitfield.bitfielddata)
+    }
+    /**
+     * @description: 修改位编号方式和字节序,已有位域按新的设置重新解析
+     * @param {*} mut self  可变的自己
+     * @param {BitOrder} bit_order  位编号方式
+     * @param {ByteOrder} byte_order    字节序
+     * @return {Result}     成功，无信息，失败时，返回失败原因且布局保持不变
+     */
+    pub fn set_order(&mut self, bit_order: BitOrder, byte_order: ByteOrder) -> Result<(), BitfieldError> {
+        self.layout.set_order(bit_order, byte_order)
+    }
+    /**
+     * @description: 校验整个布局,报告所有互相重叠的非别名位域
+     * @param {*} self  不可变的自己
+     * @return {Result} 无冲突，无信息，有冲突时，按位置顺序返回每一对冲突
+     */
+    pub fn validate(&self) -> Result<(), Vec<BitfieldError>> {
+        self.layout.validate()
+    }
+    /**
+     * @description: 按开始地址顺序遍历位域及其当前值
+     * @param {*} self  不可变的自己
+     * @return {Iterator}   (位域名字, 开始地址, 长度, 值)
+     */
+    pub fn iter(&self) -> impl Iterator<Item = (&str, u8, u8, T)> {
+        let bitfielddata = self.bitfield.bitfielddata;
+        self.layout
+            .fields()
+            .map(move |(name, value)| (name, value.start(), value.length(), FieldHandle::new(value).get(bitfielddata)))
+    }
+    /**
+     * @description: 按插入顺序遍历位域及其当前值
+     * @param {*} self  不可变的自己
+     * @return {Iterator}   (位域名字, 开始地址, 长度, 值)
+     */
+    pub fn iter_by_insertion(&self) -> impl Iterator<Item = (&str, u8, u8, T)> {
+        let bitfielddata = self.bitfield.bitfielddata;
+        self.layout
+            .fields_by_insertion()
+            .map(move |(name, value)| (name, value.start(), value.length(), FieldHandle::new(value).get(bitfielddata)))
+    }
+    /**
+     * @description: 遍历没有被任何位域占用的位范围
+     * @param {*} self  不可变的自己
+     * @return {Iterator}   (最低位在数据字中的位置, 长度),从低位到高位
+     */
+    pub fn gaps(&self) -> impl Iterator<Item = (u8, u8)> {
+        self.layout.gaps()
+    }
+    /**
+     * @description:    打印位域结构体
+     * @param {*} self  不可变的自己
+     * @return {无}      无
+     */
+    pub fn traversal(&self) {
+        println!("{:#?}", self);
+    }
+    /**
+     * @description:    设置需要做位域处理的数据,与已提交数据不同的位域记为修改过
+     * @param {*} mut self  可变的自己
+     * @param {T} bitfielddata 需要做位域处理的数据
+     * @return {无} 无
+     */
+    pub fn set_data(&mut self, bitfielddata: T) {
+        self.bitfield.bitfielddata = bitfielddata;
+    }
+    /**
+     * @description: 获取做位域处理的数据
+     * @param {*} self  不可变的自己
+     * @return {T} 原始数据
+     */
+    pub fn get_data(&self) -> T {
+        self.bitfield.bitfielddata
+    }
+    /**
+     * @description: 载入数据并作为已提交的数据,载入后没有修改过的位域
+     * @param {*} mut self  可变的自己
+     * @param {T} bitfielddata 需要做位域处理的数据
+     * @return {无} 无
+     */
+    pub fn load(&mut self, bitfielddata: T) {
+        self.bitfield.bitfielddata = bitfielddata;
+        self.bitfield.committed = bitfielddata;
+    }
+    /**
+     * @description: 获取最近一次提交时的数据
+     * @param {*} self  不可变的自己
+     * @return {T} 已提交的数据
+     */
+    pub fn get_committed(&self) -> T {
+        self.bitfield.committed
+    }
+    /**
+     * @description: 按开始地址顺序遍历自上次提交以来值发生变化的位域,写入相同的值不算修改
+     * @param {*} self  不可变的自己
+     * @return {Iterator}   位域名字
+     */
+    pub fn dirty_fields(&self) -> impl Iterator<Item = &str> {
+        let changed = self.bitfield.bitfielddata.to_raw() ^ self.bitfield.committed.to_raw();
+        self.layout
+            .fields()
+            .filter(move |(_, value)| changed & (T::mask(value.length()) << value.offset()) != 0)
+            .map(|(name, _)| name)
+    }
+    /**
+     * @description: 自上次提交以来是否有位域的值发生变化,只改变了不属于任何位域的位时不算修改
+     * @param {*} self  不可变的自己
+     * @return {bool}   有变化时返回true
+     */
+    pub fn is_dirty(&self) -> bool {
+        self.dirty_fields().next().is_some()
+    }
+    /**
+     * @description: 计算修改过的位域的合并掩码
+     * @param {*} self  不可变的自己
+     * @return {(T, T)} (修改过的位域占用的全部位, 当前数据)
+     */
+    fn changes(&self) -> (T, T) {
+        let changed = self.bitfield.bitfielddata.to_raw() ^ self.bitfield.committed.to_raw();
+        let mask = self
+            .layout
+            .fields()
+            .map(|(_, value)| T::mask(value.length()) << value.offset())
+            .filter(|bits| changed & bits != 0)
+            .fold(0, |mask, bits| mask | bits);
+        (T::from_raw(mask), self.bitfield.bitfielddata)
+    }
+    /**
+     * @description: 提交当前数据,返回自上次提交以来修改过的位域
+     * @param {*} mut self  可变的自己
+     * @return {(T, T)} (修改过的位域占用的全部位, 当前数据),没有修改时掩码为0
+     */
+    pub fn commit(&mut self) -> (T, T) {
+        let changes = self.changes();
+        self.bitfield.committed = self.bitfield.bitfielddata;
+        changes
+    }
+    /**
+     * @description: 有修改时提交当前数据并返回修改,用于只在需要时写回
+     * @param {*} mut self  可变的自己
+     * @return {Option}     有位域修改时返回(修改过的位域占用的全部位, 当前数据),掩码不为0;只改变了不属于任何位域的位时返回None且不提交
+     */
+    pub fn take_changes(&mut self) -> Option<(T, T)> {
+        if self.is_dirty() {
+            Some(self.commit())
+        } else {
+            None
+        }
+    }
+    /**
+     * @description: 放弃自上次提交以来的修改,恢复为已提交的数据
+     * @param {*} mut self  可变的自己
+     * @return {无} 无
+     */
+    pub fn revert(&mut self) {
+        self.bitfield.bitfielddata = self.bitfield.committed;
+    }
+    /**
+     * @description: 插入一个位域信息
+     * @param {*} mut self  可变的自己
+     * @param {String} key  位域名字
+     * @param {u8} start    位域开始地址
+     * @param {u8} length   位域长度
+     * @return {Result}     成功，无信息，失败时，返回失败原因
+     */
+    pub fn insert(&mut self, key: String, start: u8, length: u8) -> Result<(), BitfieldError> {
+        self.layout.insert(key, start, length)
+    }
+    /**
+     * @description: 插入一个有符号位域
+     * @param {*} mut self  可变的自己
+     * @param {String} key  位域名字
+     * @param {u8} start    位域开始地址
+     * @param {u8} length   位域长度
+     * @return {Result}     成功，无信息，失败时，返回失败原因
+     */
+    pub fn insert_signed(&mut self, key: String, start: u8, length: u8) -> Result<(), BitfieldError> {
+        self.layout.insert_signed(key, start, length)
+    }
+    /**
+     * @description: 插入一个允许与其他位域重叠的别名位域
+     * @param {*} mut self  可变的自己
+     * @param {String} key  位域名字
+     * @param {u8} start    位域开始地址
+     * @param {u8} length   位域长度
+     * @return {Result}     成功，无信息，失败时，返回失败原因
+     */
+    pub fn insert_alias(&mut self, key: String, start: u8, length: u8) -> Result<(), BitfieldError> {
+        self.layout.insert_alias(key, start, length)
+    }
+    /**
+     * @description: 插入或替换一个位域信息
+     * @param {*} mut self  可变的自己
+     * @param {String} key  位域名字
+     * @param {u8} start    位域开始地址
+     * @param {u8} length   位域长度
+     * @return {Result}     成功，返回被替换的旧位域(不存在时为None)，失败时，返回失败原因
+     */
+    pub fn upsert(&mut self, key: String, start: u8, length: u8) -> Result<Option<BitfieldValue>, BitfieldError> {
+        self.layout.upsert(key, start, length)
+    }
+    /**
+     * @description:    修改一个位域信息
+     * @param {*} mut self  可变的自己
+     * @param {String} oldkey   旧位域名字
+     * @param {String} newkey   新位域名字
+     * @param {u8} start        位域开始地址
+     * @param {u8} length       位域长度
+     * @return {Result} 成功，无信息，失败时，返回失败原因且原位域保持不变
+     */
+    pub fn modify(&mut self, oldkey: String, newkey: String, start: u8, length: u8) -> Result<(), BitfieldError> {
+        self.layout.modify(oldkey, newkey, start, length)
+    }
+    /**
+     * @description: 设置位域的说明文字
+     * @param {*} mut self  可变的自己
+     * @param {&str} key   位域名字
+     * @param {String} description  说明文字
+     * @return {Result} 成功，无信息，失败时，返回失败原因
+     */
+    pub fn set_description(&mut self, key: &str, description: String) -> Result<(), BitfieldError> {
+        self.layout.set_description(key, description)
+    }
+    /**
+     * @description: 设置或清除位域的物理量换算信息
+     * @param {*} mut self  可变的自己
+     * @param {&str} key   位域名字
+     * @param {Option} scaling  换算信息,None表示清除,比例系数必须是非0的有限数,偏移量必须是有限数
+     * @return {Result} 成功，无信息，失败时，返回失败原因
+     */
+    pub fn set_scaling(&mut self, key: &str, scaling: Option<Scaling>) -> Result<(), BitfieldError> {
+        self.layout.set_scaling(key, scaling)
+    }
+    /**
+     * @description: 设置或清除位域的值到名字的对照表
+     * @param {*} mut self  可变的自己
+     * @param {&str} key   位域名字
+     * @param {Option} table  对照表,None表示清除
+     * @return {Result} 成功，无信息，失败时，返回失败原因
+     */
+    pub fn set_enum_table(&mut self, key: &str, table: Option<EnumTable>) -> Result<(), BitfieldError> {
+        self.layout.set_enum_table(key, table)
+    }
+    /**
+     * @description: 设置位域的访问权限
+     * @param {*} mut self  可变的自己
+     * @param {&str} key   位域名字
+     * @param {Access} access  访问权限
+     * @return {Result} 成功，无信息，失败时，返回失败原因
+     */
+    pub fn set_access(&mut self, key: &str, access: Access) -> Result<(), BitfieldError> {
+        self.layout.set_access(key, access)
+    }
+    /**
+     * @description: 获取位域设置信息
+     * @param {*} self  不可变的自己
+     * @param {&str} key   位域名字
+     * @return {Result} 成功时返回BitfieldValue结构体,失败时，返回失败原因
+     */
+    pub fn get_param(&self, key: &str) -> Result<&BitfieldValue, BitfieldError> {
+        self.layout.get_param(key)
+    }
+    /**
+     * @description: 删除位域信息
+     * @param {*}   可变的自己
+     * @param {&str} key   位域名字
+     * @return {Result} 成功，无信息，失败时，返回失败原因
+     */
+    pub fn del_param(&mut self, key: &str) -> Result<(), BitfieldError> {
+        self.layout.del_param(key)
+    }
+    /**
+     * @description: 根据位域信息，获取对应的值
+     * @param {*} self  不可变的自己
+     * @param {&str} key   位域名字
+     * @return {Result} 成功，返回位域对应的数据,失败时，返回失败原因
+     */
+    pub fn get_value(&self, key: &str) -> Result<T, BitfieldError> {
+        self.layout.get_value(self.bitfield.bitfielddata, key)
+    }
+    /**
+     * @description: 根据位域信息，设置对应的值
+     * @param {*}    可变的自己
+     * @param {&str} key    位域名字
+     * @param {T} value     要改变位域的对应的值
+     * @return {Result}     成功，无信息，失败，返回失败原因
+     */
+    pub fn set_value(&mut self, key: &str, value: T) -> Result<(), BitfieldError> {
+        self.bitfield.bitfielddata = self.layout.set_value(self.bitfield.bitfielddata, key, value)?;
+        Ok(())
+    }
+    /**
+     * @description: 解析位域名字,返回缓存了移位量和掩码的句柄
+     * @param {*} self  不可变的自己
+     * @param {&str} key   位域名字
+     * @return {Result} 成功，返回位域句柄,失败时，返回失败原因
+     */
+    pub fn field(&self, key: &str) -> Result<FieldHandle<T>, BitfieldError> {
+        self.layout.field(key)
+    }
+    /**
+     * @description: 通过句柄获取位域的值,不查找哈希表
+     * @param {*} self  不可变的自己
+     * @param {FieldHandle} handle  位域句柄
+     * @return {T}  位域的值
+     */
+    #[inline]
+    pub fn get(&self, handle: FieldHandle<T>) -> T {
+        handle.get(self.bitfield.bitfielddata)
+    }
+    /**
+     * @description: 通过句柄设置位域的值,不查找哈希表
+     * @param {*}    可变的自己
+     * @param {FieldHandle} handle  位域句柄
+     * @param {T} value     要改变位域的对应的值
+     * @return {Result}     成功，无信息，失败，返回失败原因
+     */
+    #[inline]
+    pub fn set(&mut self, handle: FieldHandle<T>, value: T) -> Result<(), BitfieldError> {
+        self.bitfield.bitfielddata = handle.set(self.bitfield.bitfielddata, value)?;
+        Ok(())
+    }
+    /**
+     * @description: 获取位数
+     * @param {*} self  不可变的自己
+     * @return {usize}  返回对应的位数
+     */
+    pub fn get_totalbit(&self) -> usize {
+        T::BITS as usize
+    }
+}
+
+/**
+ * 保留按类型命名的创建函数,均转发到Bitfield::new
+ */
+macro_rules! impl_create {
+    ($($name:ident => $t:ty),* $(,)?) => {
+        $(
+            #[allow(dead_code)]
+            impl Bitfield<$t> {
+                pub fn $name(key: String, bitfielddata: $t, start: u8, length: u8) -> Result<Bitfield<$t>, BitfieldError> {
+                    Bitfield::new(key, bitfielddata, start, length)
+                }
+            }
+        )*
+    };
+}
+
+impl_create!(
+    create_u8 => u8, create_i8 => i8,
+    create_u16 => u16, create_i16 => i16,
+    create_u32 => u32, create_i32 => i32,
+    create_u64 => u64, create_i64 => i64,
+    create_u128 => u128, create_i128 => i128,
+);
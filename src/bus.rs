@@ -0,0 +1,184 @@
+use std::collections::HashMap;
+
+use crate::bitfield::{BitStorage, Bitfield};
+use crate::error::BitfieldError;
+use crate::layout::Layout;
+
+/**
+ * 寄存器总线,按地址读写整个寄存器,可以是真实的硬件访问,也可以是测试用的模拟总线
+ */
+pub trait RegisterBus<T: BitStorage> {
+    /**
+     * @description: 读取寄存器
+     * @param {*} mut self  可变的自己,读操作在硬件上可能有副作用
+     * @param {u64} addr    地址
+     * @return {T}  读出的值
+     */
+    fn read(&mut self, addr: u64) -> T;
+    /**
+     * @description: 写入寄存器
+     * @param {*} mut self  可变的自己
+     * @param {u64} addr    地址
+     * @param {T} value     写入的值
+     * @return {无} 无
+     */
+    fn write(&mut self, addr: u64, value: T);
+}
+
+/**
+ * 模拟总线上的一次访问
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Transaction<T> {
+    Read { addr: u64, value: T },
+    Write { addr: u64, value: T },
+}
+
+/**
+ * 内存中的模拟总线,记录每一次读写,没有写过的地址读出为0
+ */
+#[derive(Debug, Clone, Default)]
+pub struct MockBus<T> {
+    memory: HashMap<u64, T>,
+    transactions: Vec<Transaction<T>>,
+}
+
+impl<T: BitStorage> MockBus<T> {
+    /**
+     * @description: 创建空的模拟总线
+     * @return {MockBus}    模拟总线
+     */
+    pub fn new() -> MockBus<T> {
+        MockBus { memory: HashMap::new(), transactions: Vec::new() }
+    }
+    /**
+     * @description: 预置寄存器的值,不记录访问
+     * @param {*} mut self  可变的自己
+     * @param {u64} addr    地址
+     * @param {T} value     寄存器的值
+     * @return {无} 无
+     */
+    pub fn set(&mut self, addr: u64, value: T) {
+        self.memory.insert(addr, value);
+    }
+    /**
+     * @description: 查看寄存器的值,不记录访问
+     * @param {*} self  不可变的自己
+     * @param {u64} addr    地址
+     * @return {T}  寄存器的值,没有写过时为0
+     */
+    pub fn get(&self, addr: u64) -> T {
+        self.memory.get(&addr).copied().unwrap_or(T::from_raw(0))
+    }
+    /**
+     * @description: 获取按时间顺序记录的全部访问
+     * @param {*} self  不可变的自己
+     * @return {&[Transaction]} 访问记录
+     */
+    pub fn transactions(&self) -> &[Transaction<T>] {
+        &self.transactions
+    }
+    /**
+     * @description: 取出并清空访问记录,寄存器的值保持不变
+     * @param {*} mut self  可变的自己
+     * @return {Vec}    访问记录
+     */
+    pub fn take_transactions(&mut self) -> Vec<Transaction<T>> {
+        std::mem::take(&mut self.transactions)
+    }
+}
+
+impl<T: BitStorage> RegisterBus<T> for MockBus<T> {
+    fn read(&mut self, addr: u64) -> T {
+        let value = self.get(addr);
+        self.transactions.push(Transaction::Read { addr, value });
+        value
+    }
+    fn write(&mut self, addr: u64, value: T) {
+        self.memory.insert(addr, value);
+        self.transactions.push(Transaction::Write { addr, value });
+    }
+}
+
+impl<T: BitStorage> Layout<T> {
+    /**
+     * @description: 读出寄存器,按set_value的语义修改一个或多个位域后写回,任一位域的名字或值无效时既不读也不写
+     * @param {*} self  不可变的自己
+     * @param {&mut B} bus  寄存器总线
+     * @param {u64} addr    寄存器地址
+     * @param {&[(&str, T)]} updates    (位域名字, 值)
+     * @return {Result}     成功，返回写回的值，失败时，返回失败原因
+     */
+    pub fn read_modify_write<B: RegisterBus<T>>(&self, bus: &mut B, addr: u64, updates: &[(&str, T)]) -> Result<T, BitfieldError> {
+        //先解析名字并校验全部的值再读,失败时不产生读操作的副作用
+        let mut handles = Vec::with_capacity(updates.len());
+        for (key, value) in updates {
+            let handle = self.field(key)?;
+            handle.check(*value)?;
+            handles.push((handle, *value));
+        }
+        let mut bitfielddata = bus.read(addr);
+        for (handle, value) in handles {
+            bitfielddata = handle.set(bitfielddata, value)?;
+        }
+        bus.write(addr, bitfielddata);
+        Ok(bitfielddata)
+    }
+}
+
+impl<T: BitStorage> Bitfield<T> {
+    /**
+     * @description: 从总线读取寄存器作为当前数据,读出的值同时作为已提交的数据
+     * @param {*} mut self  可变的自己
+     * @param {&mut B} bus  寄存器总线
+     * @param {u64} addr    寄存器地址
+     * @return {无} 无
+     */
+    pub fn read_from<B: RegisterBus<T>>(&mut self, bus: &mut B, addr: u64) {
+        self.load(bus.read(addr));
+    }
+    /**
+     * @description: 把当前数据写入总线上的寄存器并提交
+     * @param {*} mut self  可变的自己
+     * @param {&mut B} bus  寄存器总线
+     * @param {u64} addr    寄存器地址
+     * @return {无} 无
+     */
+    pub fn write_to<B: RegisterBus<T>>(&mut self, bus: &mut B, addr: u64) {
+        bus.write(addr, self.get_data());
+        self.commit();
+    }
+    /**
+     * @description: 只把修改过的位域写回寄存器:读出寄存器,替换修改过的位域后写回一次,写回的值作为当前数据和已提交的数据,没有修改时不访问总线
+     * @param {*} mut self  可变的自己
+     * @param {&mut B} bus  寄存器总线
+     * @param {u64} addr    寄存器地址
+     * @return {bool}   访问了总线时返回true
+     */
+    pub fn flush<B: RegisterBus<T>>(&mut self, bus: &mut B, addr: u64) -> bool {
+        match self.take_changes() {
+            Some((mask, bitfielddata)) => {
+                let mask = mask.to_raw();
+                let current = bus.read(addr).to_raw();
+                let merged = T::from_raw((current & !mask) | (bitfielddata.to_raw() & mask));
+                bus.write(addr, merged);
+                self.load(merged);
+                true
+            }
+            None => false,
+        }
+    }
+    /**
+     * @description: 读出寄存器,修改一个或多个位域后写回,成功时写回的值作为当前数据和已提交的数据
+     * @param {*} mut self  可变的自己
+     * @param {&mut B} bus  寄存器总线
+     * @param {u64} addr    寄存器地址
+     * @param {&[(&str, T)]} updates    (位域名字, 值)
+     * @return {Result}     成功，无信息，失败时，返回失败原因且不访问总线
+     */
+    pub fn read_modify_write<B: RegisterBus<T>>(&mut self, bus: &mut B, addr: u64, updates: &[(&str, T)]) -> Result<(), BitfieldError> {
+        let bitfielddata = self.layout().read_modify_write(bus, addr, updates)?;
+        self.load(bitfielddata);
+        Ok(())
+    }
+}
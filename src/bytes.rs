@@ -0,0 +1,353 @@
+use std::collections::HashMap;
+
+use crate::error::BitfieldError;
+use crate::order::{BitOrder, ByteOrder, Placement};
+
+/**
+ * 单个位域允许的最大长度,取出的值放在u128中
+ */
+pub const MAX_FIELD_BITS: usize = 128;
+
+/**
+ * 字节缓冲区上的位域数据存放结构体
+ */
+#[derive(Debug, Clone)]
+pub struct BytesField {
+    start : usize ,
+    length : u8 ,
+    alias : bool ,
+    signed : bool ,
+    byte_order : Option<ByteOrder> ,
+    placement : Placement ,
+}
+
+impl BytesField {
+    /**
+     * @description: 获取位域开始地址,按缓冲区的位编号方式计数
+     * @param {*} self  不可变的自己
+     * @return {usize} 开始地址
+     */
+    pub fn start(&self) -> usize {
+        self.start
+    }
+    /**
+     * @description: 获取位域长度
+     * @param {*} self  不可变的自己
+     * @return {u8} 长度
+     */
+    pub fn length(&self) -> u8 {
+        self.length
+    }
+    /**
+     * @description: 位域是否按有符号补码读写
+     * @param {*} self  不可变的自己
+     * @return {bool}   有符号时返回true
+     */
+    pub fn is_signed(&self) -> bool {
+        self.signed
+    }
+    /**
+     * @description: 位域是否为允许重叠的别名位域
+     * @param {*} self  不可变的自己
+     * @return {bool}   别名位域时返回true
+     */
+    pub fn is_alias(&self) -> bool {
+        self.alias
+    }
+    /**
+     * @description: 获取位域自己的字节序
+     * @param {*} self  不可变的自己
+     * @return {Option} 通过insert_with_order插入时返回该字节序,否则为None,使用缓冲区的字节序
+     */
+    pub fn byte_order(&self) -> Option<ByteOrder> {
+        self.byte_order
+    }
+}
+
+/**
+ * 字节缓冲区位域处理结构体,不受整数位宽限制
+ * 缓冲区可以是Vec<u8>、&[u8]或&mut [u8],只读缓冲区只能读取位域
+ * 位编号方式和字节序见BitOrder与ByteOrder,默认LSB-0小端,位域可以跨越字节边界
+ */
+#[derive(Debug, Clone)]
+pub struct BitfieldBytes<B> {
+    buffer: B,
+    bitmap: HashMap<String, BytesField>,
+    bit_order: BitOrder,
+    byte_order: ByteOrder,
+}
+
+impl<B: AsRef<[u8]>> BitfieldBytes<B> {
+    /**
+     * @description:  静态函数，用于在字节缓冲区上创建一个没有位域的处理结构体
+     * @param {B} buffer    字节缓冲区
+     * @return {BitfieldBytes}  处理结构体
+     */
+    pub fn new(buffer: B) -> BitfieldBytes<B> {
+        BitfieldBytes::with_order(buffer, BitOrder::Lsb0, ByteOrder::LittleEndian)
+    }
+    /**
+     * @description:  静态函数，用指定的位编号方式和字节序创建一个没有位域的处理结构体
+     * @param {B} buffer    字节缓冲区
+     * @param {BitOrder} bit_order  位编号方式
+     * @param {ByteOrder} byte_order    字节序
+     * @return {BitfieldBytes}  处理结构体
+     */
+    pub fn with_order(buffer: B, bit_order: BitOrder, byte_order: ByteOrder) -> BitfieldBytes<B> {
+        BitfieldBytes {
+            buffer,
+            bitmap: HashMap::new(),
+            bit_order,
+            byte_order,
+        }
+    }
+    /**
+     * @description: 获取位编号方式
+     * @param {*} self  不可变的自己
+     * @return {BitOrder}   位编号方式
+     */
+    pub fn bit_order(&self) -> BitOrder {
+        self.bit_order
+    }
+    /**
+     * @description: 获取字节序
+     * @param {*} self  不可变的自己
+     * @return {ByteOrder}  字节序
+     */
+    pub fn byte_order(&self) -> ByteOrder {
+        self.byte_order
+    }
+    /**
+     * @description: 修改位编号方式和字节序,已有位域按新的设置重新解析
+     * @param {*} mut self  可变的自己
+     * @param {BitOrder} bit_order  位编号方式
+     * @param {ByteOrder} byte_order    字节序
+     * @return {Result}     成功，无信息，有位域越界或重叠时，按开始地址顺序返回第一个失败原因且布局保持不变
+     */
+    pub fn set_order(&mut self, bit_order: BitOrder, byte_order: ByteOrder) -> Result<(), BitfieldError> {
+        let mut sorted: Vec<(&String, &BytesField)> = self.bitmap.iter().collect();
+        sorted.sort_by_key(|(name, value)| (value.start, *name));
+        let mut bitmap = HashMap::new();
+        for (key, value) in sorted {
+            let placement = self.locate(value.start, value.length, bit_order, value.byte_order.unwrap_or(byte_order))?;
+            bitmap.insert(key.clone(), BytesField { placement, ..value.clone() });
+        }
+        let mut fields: Vec<(&String, &BytesField)> = bitmap.iter().filter(|(_, value)| !value.alias).collect();
+        fields.sort_by_key(|(name, value)| (value.start, *name));
+        for (i, (key, first)) in fields.iter().enumerate() {
+            for (other, second) in &fields[i + 1..] {
+                if let Some((start, end)) = first.placement.overlap(&second.placement) {
+                    return Err(BitfieldError::Overlap { key: key.to_string(), other: other.to_string(), start, end });
+                }
+            }
+        }
+        self.bitmap = bitmap;
+        self.bit_order = bit_order;
+        self.byte_order = byte_order;
+        Ok(())
+    }
+    /**
+     * @description: 获取字节缓冲区
+     * @param {*} self  不可变的自己
+     * @return {&[u8]}  字节缓冲区
+     */
+    pub fn get_data(&self) -> &[u8] {
+        self.buffer.as_ref()
+    }
+    /**
+     * @description: 取回字节缓冲区
+     * @param {*} self  自己
+     * @return {B}  字节缓冲区
+     */
+    pub fn into_inner(self) -> B {
+        self.buffer
+    }
+    /**
+     * @description: 获取位数
+     * @param {*} self  不可变的自己
+     * @return {usize}  缓冲区的总位数
+     */
+    pub fn get_totalbit(&self) -> usize {
+        self.buffer.as_ref().len() * 8
+    }
+    /**
+     * @description: 按位编号方式和字节序解析位域,并检查是否落在缓冲区之内
+     * @param {usize} start    位域开始地址
+     * @param {u8} length   位域长度
+     * @param {BitOrder} bit_order  位编号方式
+     * @param {ByteOrder} byte_order    字节序
+     * @return {Result}     合法，返回位域的实际位置，非法时，返回失败原因
+     */
+    fn locate(&self, start: usize, length: u8, bit_order: BitOrder, byte_order: ByteOrder) -> Result<Placement, BitfieldError> {
+        let width = self.get_totalbit();
+        if length as usize > MAX_FIELD_BITS {
+            return Err(BitfieldError::FieldTooWide { length: length as usize, max: MAX_FIELD_BITS });
+        }
+        if start >= width {
+            return Err(BitfieldError::StartOutOfRange { start, width });
+        }
+        let placement = Placement::resolve(start, length as usize, bit_order, byte_order);
+        if placement.end() > width {
+            return Err(BitfieldError::LengthOverflow { start, length: length as usize, width });
+        }
+        Ok(placement)
+    }
+    /**
+     * @description: 检查新位域的名字和位置,别名位域不参与重叠检查
+     * @param {&str} key    位域名字
+     * @param {usize} start    位域开始地址
+     * @param {u8} length   位域长度
+     * @param {bool} alias  是否为别名位域
+     * @param {ByteOrder} byte_order    位域的字节序
+     * @return {Result}     合法，返回位域的实际位置，非法时，返回失败原因
+     */
+    fn check_insert(&self, key: &str, start: usize, length: u8, alias: bool, byte_order: ByteOrder) -> Result<Placement, BitfieldError> {
+        if self.bitmap.contains_key(key) {
+            return Err(BitfieldError::DuplicateKey { key: key.to_string() });
+        }
+        let placement = self.locate(start, length, self.bit_order, byte_order)?;
+        if alias {
+            return Ok(placement);
+        }
+        let mut conflicts: Vec<(&String, (usize, usize))> = self
+            .bitmap
+            .iter()
+            .filter(|(_, value)| !value.alias)
+            .filter_map(|(name, value)| Some((name, value.placement.overlap(&placement)?)))
+            .collect();
+        conflicts.sort_by_key(|(name, range)| (*range, *name));
+        match conflicts.first() {
+            Some((other, (lo, hi))) => Err(BitfieldError::Overlap { key: key.to_string(), other: other.to_string(), start: *lo, end: *hi }),
+            None => Ok(placement),
+        }
+    }
+    /**
+     * @description: 插入一个位域,byte_order为None时使用缓冲区的字节序
+     * @param {*} mut self  可变的自己
+     * @param {String} key  位域名字
+     * @param {usize} start    位域开始地址
+     * @param {u8} length   位域长度,最多128位
+     * @param {bool} alias  是否为别名位域
+     * @param {bool} signed 是否有符号
+     * @param {Option} byte_order   位域自己的字节序
+     * @return {Result}     成功，无信息，失败时，返回失败原因
+     */
+    pub(crate) fn insert_field(&mut self, key: String, start: usize, length: u8, alias: bool, signed: bool, byte_order: Option<ByteOrder>) -> Result<(), BitfieldError> {
+        let placement = self.check_insert(&key, start, length, alias, byte_order.unwrap_or(self.byte_order))?;
+        self.bitmap.insert(key, BytesField { start, length, alias, signed, byte_order, placement });
+        Ok(())
+    }
+    /**
+     * @description: 插入一个位域信息
+     * @param {*} mut self  可变的自己
+     * @param {String} key  位域名字
+     * @param {usize} start    位域开始地址
+     * @param {u8} length   位域长度,最多128位
+     * @return {Result}     成功，无信息，失败时，返回失败原因
+     */
+    pub fn insert(&mut self, key: String, start: usize, length: u8) -> Result<(), BitfieldError> {
+        self.insert_field(key, start, length, false, false, None)
+    }
+    /**
+     * @description: 插入一个有符号位域
+     * @param {*} mut self  可变的自己
+     * @param {String} key  位域名字
+     * @param {usize} start    位域开始地址
+     * @param {u8} length   位域长度,最多128位
+     * @return {Result}     成功，无信息，失败时，返回失败原因
+     */
+    pub fn insert_signed(&mut self, key: String, start: usize, length: u8) -> Result<(), BitfieldError> {
+        self.insert_field(key, start, length, false, true, None)
+    }
+    /**
+     * @description: 插入一个允许与其他位域重叠的别名位域
+     * @param {*} mut self  可变的自己
+     * @param {String} key  位域名字
+     * @param {usize} start    位域开始地址
+     * @param {u8} length   位域长度,最多128位
+     * @return {Result}     成功，无信息，失败时，返回失败原因
+     */
+    pub fn insert_alias(&mut self, key: String, start: usize, length: u8) -> Result<(), BitfieldError> {
+        self.insert_field(key, start, length, true, false, None)
+    }
+    /**
+     * @description: 插入一个使用自己字节序的位域,例如同一CAN报文中混用Intel和Motorola信号,修改缓冲区字节序时保持不变
+     * @param {*} mut self  可变的自己
+     * @param {String} key  位域名字
+     * @param {usize} start    位域开始地址
+     * @param {u8} length   位域长度,最多128位
+     * @param {bool} signed 是否有符号
+     * @param {ByteOrder} byte_order    位域的字节序
+     * @return {Result}     成功，无信息，失败时，返回失败原因
+     */
+    pub fn insert_with_order(&mut self, key: String, start: usize, length: u8, signed: bool, byte_order: ByteOrder) -> Result<(), BitfieldError> {
+        self.insert_field(key, start, length, false, signed, Some(byte_order))
+    }
+    /**
+     * @description: 获取位域设置信息
+     * @param {*} self  不可变的自己
+     * @param {&str} key   位域名字
+     * @return {Result} 成功时返回BytesField结构体,失败时，返回失败原因
+     */
+    pub fn get_param(&self, key: &str) -> Result<&BytesField, BitfieldError> {
+        match self.bitmap.get(key) {
+            Some(value) => Ok(value),
+            None => Err(BitfieldError::KeyNotFound { key: key.to_string() }),
+        }
+    }
+    /**
+     * @description: 删除位域信息
+     * @param {*}   可变的自己
+     * @param {&str} key   位域名字
+     * @return {Result} 成功，无信息，失败时，返回失败原因
+     */
+    pub fn del_param(&mut self, key: &str) -> Result<(), BitfieldError> {
+        match self.bitmap.remove(key) {
+            Some(_) => Ok(()),
+            None => Err(BitfieldError::KeyNotFound { key: key.to_string() }),
+        }
+    }
+    /**
+     * @description: 根据位域信息，获取对应的值
+     * @param {*} self  不可变的自己
+     * @param {&str} key   位域名字
+     * @return {Result} 成功，返回位域对应的数据,有符号位域符号扩展到128位,失败时，返回失败原因
+     */
+    pub fn get_value(&self, key: &str) -> Result<u128, BitfieldError> {
+        let field = self.get_param(key)?;
+        let length = field.length as usize;
+        let mut value = field.placement.read(self.buffer.as_ref());
+        if field.signed && length > 0 && length < MAX_FIELD_BITS && value >> (length - 1) & 1 == 1 {
+            value |= u128::MAX << length;
+        }
+        Ok(value)
+    }
+}
+
+impl<B: AsRef<[u8]> + AsMut<[u8]>> BitfieldBytes<B> {
+    /**
+     * @description: 根据位域信息，设置对应的值
+     * @param {*}    可变的自己
+     * @param {&str} key    位域名字
+     * @param {u128} value  要改变位域的对应的值,有符号位域按128位补码解释
+     * @return {Result}     成功，无信息，失败，返回失败原因
+     */
+    pub fn set_value(&mut self, key: &str, value: u128) -> Result<(), BitfieldError> {
+        let field = self.get_param(key)?;
+        let (placement, length) = (field.placement, field.length as usize);
+        let mask = if length >= MAX_FIELD_BITS { u128::MAX } else { (1u128 << length) - 1 };
+        //防止超范围
+        if field.signed {
+            let signed = value as i128;
+            let max = (mask >> 1) as i128;
+            let min = if length == 0 { 0 } else { -max - 1 };
+            if signed < min || signed > max {
+                return Err(BitfieldError::SignedValueOutOfRange { value: signed, min, max });
+            }
+        } else if value > mask {
+            return Err(BitfieldError::ValueOutOfRange { value, max: mask });
+        }
+        placement.write(self.buffer.as_mut(), value);
+        Ok(())
+    }
+}
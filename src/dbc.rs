@@ -0,0 +1,540 @@
+use crate::bytes::BitfieldBytes;
+use crate::error::BitfieldError;
+use crate::order::ByteOrder;
+use crate::scaling::Scaling;
+
+/**
+ * 信号的多路复用角色
+ * Multiplexor: 多路复用选择信号(M),Multiplexed(n): 只在选择信号等于n时有效的信号(mn)
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Multiplex {
+    None,
+    Multiplexor,
+    Multiplexed(u64),
+}
+
+/**
+ * DBC中的一个信号,物理值 = 原始值 * factor + offset
+ * Intel(@1)信号的开始地址是最低位,Motorola(@0)信号的开始地址是最高位,两者都按LSB-0编号
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub struct DbcSignal {
+    name: String,
+    start: usize,
+    length: u8,
+    byte_order: ByteOrder,
+    signed: bool,
+    factor: f64,
+    offset: f64,
+    min: f64,
+    max: f64,
+    unit: String,
+    multiplex: Multiplex,
+    receivers: Vec<String>,
+}
+
+/**
+ * DBC中的一个报文,size为报文字节数,CAN为0到8,CAN-FD最多64
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub struct DbcMessage {
+    id: u32,
+    name: String,
+    size: usize,
+    transmitter: String,
+    signals: Vec<DbcSignal>,
+}
+
+/**
+ * CAN DBC信号数据库,只读取BO_和SG_行,其余内容(注释、属性、值表等)被忽略
+ */
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct DbcDatabase {
+    messages: Vec<DbcMessage>,
+}
+
+/**
+ * 一行文本的读取位置,出错时报告行号和列号
+ */
+struct Cursor {
+    chars: Vec<char>,
+    pos: usize,
+    line: usize,
+}
+
+impl Cursor {
+    /**
+     * @description: 生成当前位置的语法错误
+     * @param {*} self  不可变的自己
+     * @param {String} message  错误信息
+     * @return {BitfieldError}  Syntax错误
+     */
+    fn error(&self, message: String) -> BitfieldError {
+        BitfieldError::Syntax { line: self.line, column: self.pos + 1, message }
+    }
+    /**
+     * @description: 跳过空白
+     * @param {*} mut self  可变的自己
+     * @return {无} 无
+     */
+    fn skip_whitespace(&mut self) {
+        while self.chars.get(self.pos).is_some_and(|c| c.is_whitespace()) {
+            self.pos += 1;
+        }
+    }
+    /**
+     * @description: 跳过空白后读取一个指定字符
+     * @param {*} mut self  可变的自己
+     * @param {char} c  字符
+     * @return {Result} 成功，无信息，失败时，返回失败原因
+     */
+    fn expect(&mut self, c: char) -> Result<(), BitfieldError> {
+        self.skip_whitespace();
+        if self.chars.get(self.pos) != Some(&c) {
+            return Err(self.error(format!("expected '{}'", c)));
+        }
+        self.pos += 1;
+        Ok(())
+    }
+    /**
+     * @description: 跳过空白后读取满足条件的一段字符
+     * @param {*} mut self  可变的自己
+     * @param {&str} what   内容的含义,用于生成错误信息
+     * @param {Fn} accept   字符是否属于这段内容
+     * @return {Result} 成功，返回(开始位置, 内容)，失败时，返回失败原因
+     */
+    fn take(&mut self, what: &str, accept: impl Fn(char) -> bool) -> Result<(usize, String), BitfieldError> {
+        self.skip_whitespace();
+        let start = self.pos;
+        while self.chars.get(self.pos).is_some_and(|c| accept(*c)) {
+            self.pos += 1;
+        }
+        if self.pos == start {
+            return Err(self.error(format!("expected {}", what)));
+        }
+        Ok((start, self.chars[start..self.pos].iter().collect()))
+    }
+    /**
+     * @description: 读取名字
+     * @param {*} mut self  可变的自己
+     * @return {Result} 成功，返回名字，失败时，返回失败原因
+     */
+    fn ident(&mut self) -> Result<String, BitfieldError> {
+        self.take("name", |c| c.is_alphanumeric() || c == '_').map(|(_, text)| text)
+    }
+    /**
+     * @description: 读取无符号整数
+     * @param {*} mut self  可变的自己
+     * @param {&str} what   数字的含义,用于生成错误信息
+     * @return {Result} 成功，返回数字，失败时，返回失败原因
+     */
+    fn unsigned(&mut self, what: &str) -> Result<u64, BitfieldError> {
+        let (start, text) = self.take(what, |c| c.is_ascii_digit())?;
+        text.parse::<u64>().map_err(|_| BitfieldError::Syntax { line: self.line, column: start + 1, message: format!("invalid {}: {}", what, text) })
+    }
+    /**
+     * @description: 读取浮点数
+     * @param {*} mut self  可变的自己
+     * @param {&str} what   数字的含义,用于生成错误信息
+     * @return {Result} 成功，返回数字，失败时，返回失败原因
+     */
+    fn float(&mut self, what: &str) -> Result<f64, BitfieldError> {
+        let (start, text) = self.take(what, |c| c.is_ascii_digit() || "+-.eE".contains(c))?;
+        text.parse::<f64>().map_err(|_| BitfieldError::Syntax { line: self.line, column: start + 1, message: format!("invalid {}: {}", what, text) })
+    }
+    /**
+     * @description: 读取双引号中的文字
+     * @param {*} mut self  可变的自己
+     * @return {Result} 成功，返回文字，失败时，返回失败原因
+     */
+    fn quoted(&mut self) -> Result<String, BitfieldError> {
+        self.expect('"')?;
+        let start = self.pos;
+        while self.chars.get(self.pos).is_some_and(|c| *c != '"') {
+            self.pos += 1;
+        }
+        if self.pos >= self.chars.len() {
+            self.pos = start - 1;
+            return Err(self.error("unterminated string".to_string()));
+        }
+        self.pos += 1;
+        Ok(self.chars[start..self.pos - 1].iter().collect())
+    }
+    /**
+     * @description: 读取剩余的以逗号或空白分隔的名字列表
+     * @param {*} mut self  可变的自己
+     * @return {Vec<String>}    名字列表
+     */
+    fn rest(&mut self) -> Vec<String> {
+        let rest: String = self.chars[self.pos..].iter().collect();
+        self.pos = self.chars.len();
+        rest.split(|c: char| c == ',' || c.is_whitespace()).filter(|name| !name.is_empty()).map(str::to_string).collect()
+    }
+}
+
+/**
+ * @description: 解析BO_行:BO_ <id> <名字>: <字节数> <发送节点>
+ * @param {&mut Cursor} cursor  读取位置,已经跳过BO_
+ * @return {Result} 成功，返回没有信号的报文，失败时，返回失败原因
+ */
+fn message(cursor: &mut Cursor) -> Result<DbcMessage, BitfieldError> {
+    let id = cursor.unsigned("message id")?;
+    let id = u32::try_from(id).map_err(|_| cursor.error(format!("message id out of range: {}", id)))?;
+    let name = cursor.ident()?;
+    cursor.expect(':')?;
+    let size = cursor.unsigned("message size")? as usize;
+    if size > 64 {
+        return Err(cursor.error(format!("message size must be at most 64, found {}", size)));
+    }
+    let transmitter = cursor.rest().join(" ");
+    Ok(DbcMessage { id, name, size, transmitter, signals: Vec::new() })
+}
+
+/**
+ * @description: 解析SG_行:SG_ <名字> [M|m<n>] : <开始>|<长度>@<0|1><+|-> (<factor>,<offset>) [<min>|<max>] "<单位>" <接收节点>
+ * @param {&mut Cursor} cursor  读取位置,已经跳过SG_
+ * @return {Result} 成功，返回信号，失败时，返回失败原因
+ */
+fn signal(cursor: &mut Cursor) -> Result<DbcSignal, BitfieldError> {
+    let name = cursor.ident()?;
+    cursor.skip_whitespace();
+    let multiplex = match cursor.chars.get(cursor.pos) {
+        Some(':') => Multiplex::None,
+        _ => {
+            let (start, text) = cursor.take("multiplexer indicator", |c| c.is_alphanumeric())?;
+            match text.strip_prefix('m').map(|value| value.parse::<u64>()) {
+                _ if text == "M" => Multiplex::Multiplexor,
+                Some(Ok(value)) => Multiplex::Multiplexed(value),
+                _ => {
+                    cursor.pos = start;
+                    return Err(cursor.error(format!("expected M or m<n>, found {}", text)));
+                }
+            }
+        }
+    };
+    cursor.expect(':')?;
+    let start = cursor.unsigned("start bit")? as usize;
+    cursor.expect('|')?;
+    let length = cursor.unsigned("length")?;
+    let length = u8::try_from(length).map_err(|_| cursor.error(format!("signal length out of range: {}", length)))?;
+    cursor.expect('@')?;
+    let byte_order = match cursor.chars.get(cursor.pos) {
+        Some('0') => ByteOrder::BigEndian,
+        Some('1') => ByteOrder::LittleEndian,
+        _ => return Err(cursor.error("expected byte order 0 or 1".to_string())),
+    };
+    cursor.pos += 1;
+    let signed = match cursor.chars.get(cursor.pos) {
+        Some('-') => true,
+        Some('+') => false,
+        _ => return Err(cursor.error("expected value type + or -".to_string())),
+    };
+    cursor.pos += 1;
+    cursor.expect('(')?;
+    let at = cursor.pos;
+    let factor = cursor.float("factor")?;
+    if factor == 0.0 || !factor.is_finite() {
+        cursor.pos = at;
+        return Err(cursor.error(format!("factor must be finite and non-zero, found {}", factor)));
+    }
+    cursor.expect(',')?;
+    let offset = cursor.float("offset")?;
+    cursor.expect(')')?;
+    cursor.expect('[')?;
+    let min = cursor.float("minimum")?;
+    cursor.expect('|')?;
+    let max = cursor.float("maximum")?;
+    cursor.expect(']')?;
+    let unit = cursor.quoted()?;
+    let receivers = cursor.rest();
+    Ok(DbcSignal { name, start, length, byte_order, signed, factor, offset, min, max, unit, multiplex, receivers })
+}
+
+impl DbcDatabase {
+    /**
+     * @description: 静态函数，解析DBC文本
+     * @param {&str} text   DBC文本
+     * @return {Result} 成功，返回信号数据库，失败时，返回带行号列号的失败原因
+     */
+    pub fn parse(text: &str) -> Result<DbcDatabase, BitfieldError> {
+        let mut messages: Vec<DbcMessage> = Vec::new();
+        for (index, line) in text.lines().enumerate() {
+            let mut cursor = Cursor { chars: line.chars().collect(), pos: 0, line: index + 1 };
+            cursor.skip_whitespace();
+            let keyword = match cursor.take("keyword", |c| !c.is_whitespace()) {
+                Ok((_, keyword)) => keyword,
+                Err(_) => continue,
+            };
+            match keyword.as_str() {
+                "BO_" => messages.push(message(&mut cursor)?),
+                "SG_" => {
+                    let column = cursor.pos;
+                    let signal = signal(&mut cursor)?;
+                    let message = messages.last_mut().ok_or_else(|| BitfieldError::Syntax {
+                        line: index + 1,
+                        column,
+                        message: "signal outside of a message".to_string(),
+                    })?;
+                    //信号必须完全落在报文声明的字节数之内
+                    let mut bounds = BitfieldBytes::new(vec![0u8; message.size]);
+                    if let Err(err) = bounds.insert_field(signal.name.clone(), signal.start, signal.length, false, false, Some(signal.byte_order)) {
+                        return Err(BitfieldError::Syntax {
+                            line: index + 1,
+                            column,
+                            message: format!("signal {} does not fit in {} bytes: {}", signal.name, message.size, err),
+                        });
+                    }
+                    message.signals.push(signal);
+                }
+                _ => {}
+            }
+        }
+        Ok(DbcDatabase { messages })
+    }
+    /**
+     * @description: 获取全部报文,按文件中的顺序排列
+     * @param {*} self  不可变的自己
+     * @return {&[DbcMessage]}  报文
+     */
+    pub fn messages(&self) -> &[DbcMessage] {
+        &self.messages
+    }
+    /**
+     * @description: 按名字查找报文
+     * @param {*} self  不可变的自己
+     * @param {&str} name   报文名字
+     * @return {Option}     找到时返回报文
+     */
+    pub fn message(&self, name: &str) -> Option<&DbcMessage> {
+        self.messages.iter().find(|message| message.name == name)
+    }
+    /**
+     * @description: 按报文id查找报文
+     * @param {*} self  不可变的自己
+     * @param {u32} id  报文id,与DBC文件中的写法相同,扩展帧带有第31位
+     * @return {Option}     找到时返回报文
+     */
+    pub fn message_by_id(&self, id: u32) -> Option<&DbcMessage> {
+        self.messages.iter().find(|message| message.id == id)
+    }
+}
+
+impl DbcMessage {
+    /**
+     * @description: 获取报文id
+     * @param {*} self  不可变的自己
+     * @return {u32}    报文id,扩展帧带有第31位
+     */
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+    /**
+     * @description: 获取报文名字
+     * @param {*} self  不可变的自己
+     * @return {&str}   报文名字
+     */
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    /**
+     * @description: 获取报文字节数
+     * @param {*} self  不可变的自己
+     * @return {usize}  字节数
+     */
+    pub fn size(&self) -> usize {
+        self.size
+    }
+    /**
+     * @description: 获取发送节点
+     * @param {*} self  不可变的自己
+     * @return {&str}   发送节点
+     */
+    pub fn transmitter(&self) -> &str {
+        &self.transmitter
+    }
+    /**
+     * @description: 获取全部信号,按文件中的顺序排列
+     * @param {*} self  不可变的自己
+     * @return {&[DbcSignal]}   信号
+     */
+    pub fn signals(&self) -> &[DbcSignal] {
+        &self.signals
+    }
+    /**
+     * @description: 按名字查找信号
+     * @param {*} self  不可变的自己
+     * @param {&str} name   信号名字
+     * @return {Result} 成功，返回信号，失败时，返回KeyNotFound错误
+     */
+    pub fn signal(&self, name: &str) -> Result<&DbcSignal, BitfieldError> {
+        self.signals.iter().find(|signal| signal.name == name).ok_or_else(|| BitfieldError::KeyNotFound { key: name.to_string() })
+    }
+    /**
+     * @description: 在帧数据上建立报文的布局,每个信号是一个使用自己字节序的位域,多路复用的信号互为别名
+     * @param {B} frame    帧数据,长度不能小于报文字节数
+     * @return {Result} 成功，返回布局，失败时，返回失败原因
+     */
+    pub fn layout<B: AsRef<[u8]>>(&self, frame: B) -> Result<BitfieldBytes<B>, BitfieldError> {
+        if frame.as_ref().len() < self.size {
+            return Err(BitfieldError::BufferSize { expected: self.size, actual: frame.as_ref().len() });
+        }
+        let mut layout = BitfieldBytes::new(frame);
+        for signal in &self.signals {
+            let alias = matches!(signal.multiplex, Multiplex::Multiplexed(_));
+            layout.insert_field(signal.name.clone(), signal.start, signal.length, alias, signal.signed, Some(signal.byte_order))?;
+        }
+        Ok(layout)
+    }
+    /**
+     * @description: 解码一帧数据,多路复用的信号只在选择信号匹配时输出
+     * @param {&[u8]} frame    帧数据,CAN为8字节,CAN-FD最多64字节
+     * @return {Result} 成功，返回按文件顺序排列的(信号名字, 物理值)，失败时，返回失败原因
+     */
+    pub fn decode(&self, frame: &[u8]) -> Result<Vec<(&str, f64)>, BitfieldError> {
+        //只解码报文声明的字节,帧中多余的字节不属于报文
+        let frame = frame.get(..self.size).ok_or(BitfieldError::BufferSize { expected: self.size, actual: frame.len() })?;
+        let layout = self.layout(frame)?;
+        let selector = match self.signals.iter().find(|signal| signal.multiplex == Multiplex::Multiplexor) {
+            Some(signal) => Some(layout.get_value(&signal.name)? as u64),
+            None => None,
+        };
+        let mut values = Vec::new();
+        for signal in &self.signals {
+            if let Multiplex::Multiplexed(value) = signal.multiplex {
+                if selector != Some(value) {
+                    continue;
+                }
+            }
+            values.push((signal.name.as_str(), signal.physical(layout.get_value(&signal.name)?)));
+        }
+        Ok(values)
+    }
+    /**
+     * @description: 把信号的物理值编码成一帧数据,没有给出的信号为0
+     * @param {&[(&str, f64)]} values   (信号名字, 物理值)
+     * @return {Result} 成功，返回长度为报文字节数的帧数据，失败时，返回失败原因
+     */
+    pub fn encode(&self, values: &[(&str, f64)]) -> Result<Vec<u8>, BitfieldError> {
+        let mut layout = self.layout(vec![0u8; self.size])?;
+        for (name, value) in values {
+            layout.set_value(name, self.signal(name)?.raw(*value)?)?;
+        }
+        Ok(layout.into_inner())
+    }
+}
+
+impl DbcSignal {
+    /**
+     * @description: 获取信号名字
+     * @param {*} self  不可变的自己
+     * @return {&str}   信号名字
+     */
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    /**
+     * @description: 获取开始地址,Intel信号为最低位,Motorola信号为最高位
+     * @param {*} self  不可变的自己
+     * @return {usize}  开始地址
+     */
+    pub fn start(&self) -> usize {
+        self.start
+    }
+    /**
+     * @description: 获取信号长度
+     * @param {*} self  不可变的自己
+     * @return {u8} 长度
+     */
+    pub fn length(&self) -> u8 {
+        self.length
+    }
+    /**
+     * @description: 获取字节序,Motorola为BigEndian,Intel为LittleEndian
+     * @param {*} self  不可变的自己
+     * @return {ByteOrder}  字节序
+     */
+    pub fn byte_order(&self) -> ByteOrder {
+        self.byte_order
+    }
+    /**
+     * @description: 信号是否有符号
+     * @param {*} self  不可变的自己
+     * @return {bool}   有符号时返回true
+     */
+    pub fn is_signed(&self) -> bool {
+        self.signed
+    }
+    /**
+     * @description: 获取缩放系数和偏移
+     * @param {*} self  不可变的自己
+     * @return {(f64, f64)} (factor, offset)
+     */
+    pub fn scale(&self) -> (f64, f64) {
+        (self.factor, self.offset)
+    }
+    /**
+     * @description: 获取物理值范围
+     * @param {*} self  不可变的自己
+     * @return {(f64, f64)} (最小值, 最大值)
+     */
+    pub fn range(&self) -> (f64, f64) {
+        (self.min, self.max)
+    }
+    /**
+     * @description: 获取单位
+     * @param {*} self  不可变的自己
+     * @return {&str}   单位
+     */
+    pub fn unit(&self) -> &str {
+        &self.unit
+    }
+    /**
+     * @description: 获取多路复用角色
+     * @param {*} self  不可变的自己
+     * @return {Multiplex}  多路复用角色
+     */
+    pub fn multiplex(&self) -> Multiplex {
+        self.multiplex
+    }
+    /**
+     * @description: 获取接收节点
+     * @param {*} self  不可变的自己
+     * @return {&[String]}  接收节点
+     */
+    pub fn receivers(&self) -> &[String] {
+        &self.receivers
+    }
+    /**
+     * @description: 把原始值换算为物理值
+     * @param {*} self  不可变的自己
+     * @param {u128} raw    原始值,有符号信号已经符号扩展
+     * @return {f64}    物理值
+     */
+    pub fn physical(&self, raw: u128) -> f64 {
+        let raw = if self.signed { raw as i128 as f64 } else { raw as f64 };
+        raw * self.factor + self.offset
+    }
+    /**
+     * @description: 获取信号的换算信息,范围[0|0]在DBC中表示不限制,此时换算信息不设置范围
+     * @param {*} self  不可变的自己
+     * @return {Scaling}    换算信息
+     */
+    pub fn scaling(&self) -> Scaling {
+        let scaling = Scaling::new(self.factor, self.offset).with_unit(&self.unit);
+        if self.min == 0.0 && self.max == 0.0 {
+            scaling
+        } else {
+            scaling.with_range(self.min, self.max)
+        }
+    }
+    /**
+     * @description: 把物理值换算为原始值,按最近的整数取整,与Layout::set_physical使用相同的检查
+     * @param {*} self  不可变的自己
+     * @param {f64} value   物理值
+     * @return {Result} 成功，返回原始值,有符号信号为128位补码，物理值不是有限数、超出min..=max或原始值超出信号能表示的范围时，返回失败原因
+     */
+    pub fn raw(&self, value: f64) -> Result<u128, BitfieldError> {
+        Ok(self.scaling().to_raw(value, self.length, self.signed)? as u128)
+    }
+}
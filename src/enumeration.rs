@@ -0,0 +1,268 @@
+use std::fmt;
+
+use crate::bitfield::{BitStorage, Bitfield};
+use crate::error::BitfieldError;
+use crate::layout::{BitfieldRef, Layout};
+
+/**
+ * 位域的值到名字的对照表,值为位域宽度内的原始位模式,有符号位域同样按补码位模式查表
+ */
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct EnumTable {
+    entries: Vec<(u128, String)>,
+}
+
+impl EnumTable {
+    /**
+     * @description: 创建空的对照表
+     * @return {EnumTable}  对照表
+     */
+    pub fn new() -> EnumTable {
+        EnumTable { entries: Vec::new() }
+    }
+    /**
+     * @description: 增加一个值和名字,值已存在时替换其名字
+     * @param {*} mut self  自己
+     * @param {u128} value  原始值
+     * @param {&str} name   名字
+     * @return {EnumTable}  对照表
+     */
+    pub fn with_value(mut self, value: u128, name: &str) -> EnumTable {
+        match self.entries.iter_mut().find(|(raw, _)| *raw == value) {
+            Some(entry) => entry.1 = name.to_string(),
+            None => self.entries.push((value, name.to_string())),
+        }
+        self
+    }
+    /**
+     * @description: 查找原始值对应的名字
+     * @param {*} self  不可变的自己
+     * @param {u128} value  原始值
+     * @return {Option}     值在表中时返回名字
+     */
+    pub fn name(&self, value: u128) -> Option<&str> {
+        self.entries.iter().find(|(raw, _)| *raw == value).map(|(_, name)| name.as_str())
+    }
+    /**
+     * @description: 查找名字对应的原始值
+     * @param {*} self  不可变的自己
+     * @param {&str} name   名字
+     * @return {Option}     名字在表中时返回原始值
+     */
+    pub fn value(&self, name: &str) -> Option<u128> {
+        self.entries.iter().find(|(_, entry)| entry == name).map(|(raw, _)| *raw)
+    }
+    /**
+     * @description: 按加入顺序遍历对照表
+     * @param {*} self  不可变的自己
+     * @return {Iterator}   (原始值, 名字)
+     */
+    pub fn entries(&self) -> impl Iterator<Item = (u128, &str)> {
+        self.entries.iter().map(|(raw, name)| (*raw, name.as_str()))
+    }
+}
+
+/**
+ * 可以与位域原始值互相转换的Rust枚举,供get_enum_as和set_enum_as使用
+ */
+pub trait FieldEnum: Sized {
+    /**
+     * @description: 由位域原始值转换为枚举
+     * @param {u128} raw    位域宽度内的原始位模式
+     * @return {Option}     没有对应的枚举成员时返回None
+     */
+    fn from_raw(raw: u128) -> Option<Self>;
+    /**
+     * @description: 把枚举转换为位域原始值
+     * @param {*} self  自己
+     * @return {u128}   原始位模式
+     */
+    fn to_raw(self) -> u128;
+}
+
+/**
+ * 按枚举读取的位域值,不认识的原始值原样保留在Unknown中
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EnumValue<E> {
+    Known(E),
+    Unknown(u128),
+}
+
+impl<E: fmt::Debug> fmt::Display for EnumValue<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EnumValue::Known(value) => write!(f, "{:?}", value),
+            EnumValue::Unknown(raw) => write!(f, "Unknown({})", raw),
+        }
+    }
+}
+
+impl<T: BitStorage> Layout<T> {
+    /**
+     * @description: 取出位域宽度内的原始位模式
+     * @param {*} self  不可变的自己
+     * @param {T} bitfielddata 需要做位域处理的数据
+     * @param {&str} key   位域名字
+     * @return {Result} 成功，返回原始位模式,失败时，返回失败原因
+     */
+    fn raw_bits(&self, bitfielddata: T, key: &str) -> Result<u128, BitfieldError> {
+        let value = self.get_param(key)?;
+        Ok((bitfielddata.to_raw() >> value.offset()) & T::mask(value.length()))
+    }
+    /**
+     * @description: 按对照表获取位域当前值的名字
+     * @param {*} self  不可变的自己
+     * @param {T} bitfielddata 需要做位域处理的数据
+     * @param {&str} key   位域名字
+     * @return {Result} 成功，返回名字,位域没有对照表或值不在表中时为None,失败时，返回失败原因
+     */
+    pub fn get_enum(&self, bitfielddata: T, key: &str) -> Result<Option<&str>, BitfieldError> {
+        let raw = self.raw_bits(bitfielddata, key)?;
+        Ok(self.get_param(key)?.enum_table().and_then(|table| table.name(raw)))
+    }
+    /**
+     * @description: 按对照表把名字对应的值写入数据字
+     * @param {*} self  不可变的自己
+     * @param {T} bitfielddata 需要做位域处理的数据
+     * @param {&str} key    位域名字
+     * @param {&str} name   名字
+     * @return {Result}     成功，返回写入后的数据字，名字不在对照表中时，返回失败原因
+     */
+    pub fn set_enum(&self, bitfielddata: T, key: &str, name: &str) -> Result<T, BitfieldError> {
+        let value = self.get_param(key)?;
+        match value.enum_table().and_then(|table| table.value(name)) {
+            Some(raw) => self.set_raw_bits(bitfielddata, key, raw),
+            None => Err(BitfieldError::UnknownEnumName { key: key.to_string(), name: name.to_string() }),
+        }
+    }
+    /**
+     * @description: 把位域当前值转换为Rust枚举
+     * @param {*} self  不可变的自己
+     * @param {T} bitfielddata 需要做位域处理的数据
+     * @param {&str} key   位域名字
+     * @return {Result} 成功，返回枚举值,不认识的原始值返回Unknown,失败时，返回失败原因
+     */
+    pub fn get_enum_as<E: FieldEnum>(&self, bitfielddata: T, key: &str) -> Result<EnumValue<E>, BitfieldError> {
+        let raw = self.raw_bits(bitfielddata, key)?;
+        Ok(E::from_raw(raw).map_or(EnumValue::Unknown(raw), EnumValue::Known))
+    }
+    /**
+     * @description: 把Rust枚举的原始值写入数据字
+     * @param {*} self  不可变的自己
+     * @param {T} bitfielddata 需要做位域处理的数据
+     * @param {&str} key    位域名字
+     * @param {E} value     枚举值
+     * @return {Result}     成功，返回写入后的数据字，原始值超出位域宽度时，返回失败原因
+     */
+    pub fn set_enum_as<E: FieldEnum>(&self, bitfielddata: T, key: &str, value: E) -> Result<T, BitfieldError> {
+        self.set_raw_bits(bitfielddata, key, value.to_raw())
+    }
+    /**
+     * @description: 把位域宽度内的原始位模式写入数据字
+     * @param {*} self  不可变的自己
+     * @param {T} bitfielddata 需要做位域处理的数据
+     * @param {&str} key    位域名字
+     * @param {u128} raw    原始位模式
+     * @return {Result}     成功，返回写入后的数据字，原始值超出位域宽度时，返回失败原因
+     */
+    fn set_raw_bits(&self, bitfielddata: T, key: &str, raw: u128) -> Result<T, BitfieldError> {
+        let value = self.get_param(key)?;
+        let mask = T::mask(value.length());
+        if raw > mask {
+            return Err(BitfieldError::ValueOutOfRange { value: raw, max: mask });
+        }
+        let mask = mask << value.offset();
+        Ok(T::from_raw((bitfielddata.to_raw() & !mask) | (raw << value.offset())))
+    }
+}
+
+impl<T: BitStorage> Bitfield<T> {
+    /**
+     * @description: 按对照表获取位域当前值的名字
+     * @param {*} self  不可变的自己
+     * @param {&str} key   位域名字
+     * @return {Result} 成功，返回名字,位域没有对照表或值不在表中时为None,失败时，返回失败原因
+     */
+    pub fn get_enum(&self, key: &str) -> Result<Option<&str>, BitfieldError> {
+        self.layout().get_enum(self.get_data(), key)
+    }
+    /**
+     * @description: 按对照表把名字对应的值写入位域
+     * @param {*} mut self  可变的自己
+     * @param {&str} key    位域名字
+     * @param {&str} name   名字
+     * @return {Result}     成功，无信息，失败，返回失败原因
+     */
+    pub fn set_enum(&mut self, key: &str, name: &str) -> Result<(), BitfieldError> {
+        let bitfielddata = self.layout().set_enum(self.get_data(), key, name)?;
+        self.set_data(bitfielddata);
+        Ok(())
+    }
+    /**
+     * @description: 把位域当前值转换为Rust枚举
+     * @param {*} self  不可变的自己
+     * @param {&str} key   位域名字
+     * @return {Result} 成功，返回枚举值,不认识的原始值返回Unknown,失败时，返回失败原因
+     */
+    pub fn get_enum_as<E: FieldEnum>(&self, key: &str) -> Result<EnumValue<E>, BitfieldError> {
+        self.layout().get_enum_as(self.get_data(), key)
+    }
+    /**
+     * @description: 把Rust枚举的原始值写入位域
+     * @param {*} mut self  可变的自己
+     * @param {&str} key    位域名字
+     * @param {E} value     枚举值
+     * @return {Result}     成功，无信息，失败，返回失败原因
+     */
+    pub fn set_enum_as<E: FieldEnum>(&mut self, key: &str, value: E) -> Result<(), BitfieldError> {
+        let bitfielddata = self.layout().set_enum_as(self.get_data(), key, value)?;
+        self.set_data(bitfielddata);
+        Ok(())
+    }
+}
+
+impl<'a, T: BitStorage> BitfieldRef<'a, T> {
+    /**
+     * @description: 按对照表获取位域当前值的名字
+     * @param {*} self  不可变的自己
+     * @param {&str} key   位域名字
+     * @return {Result} 成功，返回名字,位域没有对照表或值不在表中时为None,失败时，返回失败原因
+     */
+    pub fn get_enum(&self, key: &str) -> Result<Option<&'a str>, BitfieldError> {
+        self.layout().get_enum(self.get_data(), key)
+    }
+    /**
+     * @description: 按对照表把名字对应的值写入位域
+     * @param {*} mut self  可变的自己
+     * @param {&str} key    位域名字
+     * @param {&str} name   名字
+     * @return {Result}     成功，无信息，失败，返回失败原因
+     */
+    pub fn set_enum(&mut self, key: &str, name: &str) -> Result<(), BitfieldError> {
+        let bitfielddata = self.layout().set_enum(self.get_data(), key, name)?;
+        self.set_data(bitfielddata);
+        Ok(())
+    }
+    /**
+     * @description: 把位域当前值转换为Rust枚举
+     * @param {*} self  不可变的自己
+     * @param {&str} key   位域名字
+     * @return {Result} 成功，返回枚举值,不认识的原始值返回Unknown,失败时，返回失败原因
+     */
+    pub fn get_enum_as<E: FieldEnum>(&self, key: &str) -> Result<EnumValue<E>, BitfieldError> {
+        self.layout().get_enum_as(self.get_data(), key)
+    }
+    /**
+     * @description: 把Rust枚举的原始值写入位域
+     * @param {*} mut self  可变的自己
+     * @param {&str} key    位域名字
+     * @param {E} value     枚举值
+     * @return {Result}     成功，无信息，失败，返回失败原因
+     */
+    pub fn set_enum_as<E: FieldEnum>(&mut self, key: &str, value: E) -> Result<(), BitfieldError> {
+        let bitfielddata = self.layout().set_enum_as(self.get_data(), key, value)?;
+        self.set_data(bitfielddata);
+        Ok(())
+    }
+}
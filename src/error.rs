@@ -0,0 +1,168 @@
+use std::error::Error;
+use std::fmt;
+
+use crate::access::Access;
+
+/**
+ * 位域操作的错误类型,所有可能失败的接口都返回该枚举
+ * 位置与长度统一使用usize,数值统一使用u128的位模式,物理值保存为f64::to_bits的位模式,以便整个枚举实现Eq
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BitfieldError {
+    /**
+     * 位域名字不存在
+     */
+    KeyNotFound { key: String },
+    /**
+     * 位域名字已经存在
+     */
+    DuplicateKey { key: String },
+    /**
+     * 位域开始地址超出存储位宽
+     */
+    StartOutOfRange { start: usize, width: usize },
+    /**
+     * 位域结束地址超出存储位宽
+     */
+    LengthOverflow { start: usize, length: usize, width: usize },
+    /**
+     * 位域长度超过单个位域允许的最大位数
+     */
+    FieldTooWide { length: usize, max: usize },
+    /**
+     * 字节缓冲区长度与期望不符
+     */
+    BufferSize { expected: usize, actual: usize },
+    /**
+     * 写入的值超出位域能表示的范围
+     */
+    ValueOutOfRange { value: u128, max: u128 },
+    /**
+     * 写入有符号位域的值超出min..=max
+     */
+    SignedValueOutOfRange { value: i128, min: i128, max: i128 },
+    /**
+     * 两个位域占用了相同的位,start..end为重叠部分
+     */
+    Overlap { key: String, other: String, start: usize, end: usize },
+    /**
+     * 布局定义的存储位宽与存储类型的位宽不符
+     */
+    WidthMismatch { expected: usize, actual: usize },
+    /**
+     * 布局定义文本无法解析,line和column从1开始计数
+     */
+    Syntax { line: usize, column: usize, message: String },
+    /**
+     * 导入的文档结构不符合要求,path指出出错的位置,例如fields[2].start
+     */
+    InvalidDocument { path: String, message: String },
+    /**
+     * 写入的物理值超出位域换算信息设置的min..=max,或者不是有限数
+     * 三个值都是f64::to_bits的位模式,通过physical_range读取
+     */
+    PhysicalOutOfRange { value: u64, min: u64, max: u64 },
+    /**
+     * 换算信息的比例系数为0或者不是有限数,或者偏移量不是有限数,两个值都是f64::to_bits的位模式
+     */
+    InvalidScaling { factor: u64, offset: u64 },
+    /**
+     * 名字不在位域的值到名字对照表中,或者位域没有对照表
+     */
+    UnknownEnumName { key: String, name: String },
+    /**
+     * 按标志访问的位域长度不为1,或者是别名
+     */
+    NotAFlag { key: String, length: usize },
+    /**
+     * 位域的访问权限不允许该操作,path为"寄存器.位域"
+     */
+    AccessDenied { path: String, access: Access },
+    /**
+     * 寄存器组中没有该地址偏移的寄存器
+     */
+    AddressNotFound { offset: u64 },
+    /**
+     * 地址偏移已经被另一个寄存器占用
+     */
+    AddressInUse { offset: u64, key: String },
+}
+
+impl BitfieldError {
+    /**
+     * @description: 生成PhysicalOutOfRange错误
+     * @param {f64} value   写入的物理值
+     * @param {f64} min     允许的最小物理值
+     * @param {f64} max     允许的最大物理值
+     * @return {BitfieldError}  PhysicalOutOfRange错误
+     */
+    pub fn physical_out_of_range(value: f64, min: f64, max: f64) -> BitfieldError {
+        BitfieldError::PhysicalOutOfRange { value: value.to_bits(), min: min.to_bits(), max: max.to_bits() }
+    }
+    /**
+     * @description: 读取PhysicalOutOfRange错误中的物理值
+     * @param {*} self  不可变的自己
+     * @return {Option} PhysicalOutOfRange错误时，返回(写入的值, 最小值, 最大值),其他错误返回None
+     */
+    pub fn physical_range(&self) -> Option<(f64, f64, f64)> {
+        match self {
+            BitfieldError::PhysicalOutOfRange { value, min, max } => Some((f64::from_bits(*value), f64::from_bits(*min), f64::from_bits(*max))),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for BitfieldError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BitfieldError::KeyNotFound { key } => write!(f, "Not found key:{}", key),
+            BitfieldError::DuplicateKey { key } => write!(f, "Key already exists:{}", key),
+            BitfieldError::StartOutOfRange { start, width } => {
+                write!(f, "Illegal starting position;start:{},width:{}", start, width)
+            }
+            BitfieldError::LengthOverflow { start, length, width } => {
+                write!(f, "Illegal total length;start:{},length:{},width:{}", start, length, width)
+            }
+            BitfieldError::FieldTooWide { length, max } => {
+                write!(f, "Field is too wide;length:{},max:{}", length, max)
+            }
+            BitfieldError::BufferSize { expected, actual } => {
+                write!(f, "Illegal buffer size;expected:{},actual:{}", expected, actual)
+            }
+            BitfieldError::ValueOutOfRange { value, max } => {
+                write!(f, "The value is out of range,the maximum value is:{},value:{}", max, value)
+            }
+            BitfieldError::SignedValueOutOfRange { value, min, max } => {
+                write!(f, "The value is out of range,the range is:{}..={},value:{}", min, max, value)
+            }
+            BitfieldError::Overlap { key, other, start, end } => {
+                write!(f, "Overlapping fields;{} and {} both use bits {}..{}", key, other, start, end)
+            }
+            BitfieldError::WidthMismatch { expected, actual } => {
+                write!(f, "Illegal storage width;expected:{},actual:{}", expected, actual)
+            }
+            BitfieldError::Syntax { line, column, message } => {
+                write!(f, "Syntax error;line:{},column:{},{}", line, column, message)
+            }
+            BitfieldError::InvalidDocument { path, message } => {
+                write!(f, "Invalid document;path:{},{}", path, message)
+            }
+            BitfieldError::PhysicalOutOfRange { value, min, max } => {
+                let (value, min, max) = (f64::from_bits(*value), f64::from_bits(*min), f64::from_bits(*max));
+                write!(f, "The physical value is out of range,the range is:{}..={},value:{}", min, max, value)
+            }
+            BitfieldError::InvalidScaling { factor, offset } => {
+                write!(f, "Invalid scaling;factor:{},offset:{}", f64::from_bits(*factor), f64::from_bits(*offset))
+            }
+            BitfieldError::UnknownEnumName { key, name } => write!(f, "Unknown enum name:{},key:{}", name, key),
+            BitfieldError::NotAFlag { key, length } => write!(f, "Not a single-bit flag:{},length:{}", key, length),
+            BitfieldError::AccessDenied { path, access } => write!(f, "Access denied;path:{},access:{}", path, access),
+            BitfieldError::AddressNotFound { offset } => write!(f, "Not found register at offset:{:#x}", offset),
+            BitfieldError::AddressInUse { offset, key } => {
+                write!(f, "Register offset already in use;offset:{:#x},register:{}", offset, key)
+            }
+        }
+    }
+}
+
+impl Error for BitfieldError {}
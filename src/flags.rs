@@ -0,0 +1,309 @@
+use crate::bitfield::{BitStorage, Bitfield};
+use crate::error::BitfieldError;
+use crate::layout::{BitfieldRef, Layout};
+
+/**
+ * 标志文本中各标志之间的分隔符
+ */
+const SEPARATOR: &str = " | ";
+
+impl<T: BitStorage> Layout<T> {
+    /**
+     * @description: 获取标志位在数据字中的位模式,标志即长度为1且不是别名的位域,与flags的范围一致
+     * @param {*} self  不可变的自己
+     * @param {&str} key   位域名字
+     * @return {Result} 成功，返回只有该位为1的位模式,位域长度不为1或者是别名时，返回失败原因
+     */
+    fn flag_bit(&self, key: &str) -> Result<u128, BitfieldError> {
+        let value = self.get_param(key)?;
+        if value.length() != 1 || value.is_alias() {
+            return Err(BitfieldError::NotAFlag { key: key.to_string(), length: value.length() as usize });
+        }
+        Ok(1u128 << value.offset())
+    }
+    /**
+     * @description: 按开始地址顺序遍历标志,别名不算作独立的标志
+     * @param {*} self  不可变的自己
+     * @return {Iterator}   (标志名字, 位模式)
+     */
+    pub fn flags(&self) -> impl Iterator<Item = (&str, u128)> {
+        self.fields()
+            .filter(|(_, value)| value.length() == 1 && !value.is_alias())
+            .map(|(name, value)| (name, 1u128 << value.offset()))
+    }
+    /**
+     * @description: 获取全部已定义标志的掩码
+     * @param {*} self  不可变的自己
+     * @return {T}  掩码
+     */
+    pub fn flags_mask(&self) -> T {
+        T::from_raw(self.flags().fold(0, |mask, (_, bit)| mask | bit))
+    }
+    /**
+     * @description: 标志是否置位
+     * @param {*} self  不可变的自己
+     * @param {T} bitfielddata 需要做位域处理的数据
+     * @param {&str} key   标志名字
+     * @return {Result} 成功，置位时返回true,失败时，返回失败原因
+     */
+    pub fn is_set(&self, bitfielddata: T, key: &str) -> Result<bool, BitfieldError> {
+        Ok(bitfielddata.to_raw() & self.flag_bit(key)? != 0)
+    }
+    /**
+     * @description: 置位标志
+     * @param {*} self  不可变的自己
+     * @param {T} bitfielddata 需要做位域处理的数据
+     * @param {&str} key   标志名字
+     * @return {Result} 成功，返回写入后的数据字，失败时，返回失败原因
+     */
+    pub fn set_flag(&self, bitfielddata: T, key: &str) -> Result<T, BitfieldError> {
+        Ok(T::from_raw(bitfielddata.to_raw() | self.flag_bit(key)?))
+    }
+    /**
+     * @description: 清除标志
+     * @param {*} self  不可变的自己
+     * @param {T} bitfielddata 需要做位域处理的数据
+     * @param {&str} key   标志名字
+     * @return {Result} 成功，返回写入后的数据字，失败时，返回失败原因
+     */
+    pub fn clear_flag(&self, bitfielddata: T, key: &str) -> Result<T, BitfieldError> {
+        Ok(T::from_raw(bitfielddata.to_raw() & !self.flag_bit(key)?))
+    }
+    /**
+     * @description: 翻转标志
+     * @param {*} self  不可变的自己
+     * @param {T} bitfielddata 需要做位域处理的数据
+     * @param {&str} key   标志名字
+     * @return {Result} 成功，返回写入后的数据字，失败时，返回失败原因
+     */
+    pub fn toggle(&self, bitfielddata: T, key: &str) -> Result<T, BitfieldError> {
+        Ok(T::from_raw(bitfielddata.to_raw() ^ self.flag_bit(key)?))
+    }
+    /**
+     * @description: 把置位的标志格式化为"A | B",按开始地址顺序排列,没有置位的标志时为空字符串
+     * @param {*} self  不可变的自己
+     * @param {T} bitfielddata 需要做位域处理的数据
+     * @return {String} 标志文本
+     */
+    pub fn format_flags(&self, bitfielddata: T) -> String {
+        let raw = bitfielddata.to_raw();
+        self.flags().filter(|(_, bit)| raw & bit != 0).map(|(name, _)| name).collect::<Vec<&str>>().join(SEPARATOR)
+    }
+    /**
+     * @description: 解析"A | B"形式的标志文本,空白文本表示没有标志,别名标志同样可以使用
+     * @param {*} self  不可变的自己
+     * @param {&str} text   标志文本
+     * @return {Result} 成功，返回只包含这些标志的位模式，名字不存在或不是标志时，返回失败原因
+     */
+    pub fn parse_flags(&self, text: &str) -> Result<T, BitfieldError> {
+        if text.trim().is_empty() {
+            return Ok(T::from_raw(0));
+        }
+        let mut raw = 0;
+        for name in text.split('|') {
+            raw |= self.flag_bit(name.trim())?;
+        }
+        Ok(T::from_raw(raw))
+    }
+    /**
+     * @description: 并集,把flags中已定义的标志置位,其余位保持bitfielddata的值
+     * @param {*} self  不可变的自己
+     * @param {T} bitfielddata 需要做位域处理的数据
+     * @param {T} flags 另一组标志
+     * @return {T}  运算后的数据字
+     */
+    pub fn union(&self, bitfielddata: T, flags: T) -> T {
+        T::from_raw(bitfielddata.to_raw() | (flags.to_raw() & self.flags_mask().to_raw()))
+    }
+    /**
+     * @description: 交集,只保留同时在flags中置位的已定义标志,非标志位保持不变
+     * @param {*} self  不可变的自己
+     * @param {T} bitfielddata 需要做位域处理的数据
+     * @param {T} flags 另一组标志
+     * @return {T}  运算后的数据字
+     */
+    pub fn intersection(&self, bitfielddata: T, flags: T) -> T {
+        T::from_raw(bitfielddata.to_raw() & (flags.to_raw() | !self.flags_mask().to_raw()))
+    }
+    /**
+     * @description: 差集,清除在flags中置位的已定义标志,非标志位保持不变
+     * @param {*} self  不可变的自己
+     * @param {T} bitfielddata 需要做位域处理的数据
+     * @param {T} flags 另一组标志
+     * @return {T}  运算后的数据字
+     */
+    pub fn difference(&self, bitfielddata: T, flags: T) -> T {
+        T::from_raw(bitfielddata.to_raw() & !(flags.to_raw() & self.flags_mask().to_raw()))
+    }
+    /**
+     * @description: 补集,翻转全部已定义标志,非标志位保持不变
+     * @param {*} self  不可变的自己
+     * @param {T} bitfielddata 需要做位域处理的数据
+     * @return {T}  运算后的数据字
+     */
+    pub fn complement(&self, bitfielddata: T) -> T {
+        T::from_raw(bitfielddata.to_raw() ^ self.flags_mask().to_raw())
+    }
+}
+
+impl<T: BitStorage> Bitfield<T> {
+    /**
+     * @description: 获取当前置位的已定义标志
+     * @param {*} self  不可变的自己
+     * @return {T}  只包含标志位的位模式
+     */
+    pub fn flags(&self) -> T {
+        T::from_raw(self.get_data().to_raw() & self.layout().flags_mask().to_raw())
+    }
+    /**
+     * @description: 标志是否置位
+     * @param {*} self  不可变的自己
+     * @param {&str} key   标志名字
+     * @return {Result} 成功，置位时返回true,失败时，返回失败原因
+     */
+    pub fn is_set(&self, key: &str) -> Result<bool, BitfieldError> {
+        self.layout().is_set(self.get_data(), key)
+    }
+    /**
+     * @description: 置位标志
+     * @param {*} mut self  可变的自己
+     * @param {&str} key   标志名字
+     * @return {Result} 成功，无信息，失败时，返回失败原因
+     */
+    pub fn set_flag(&mut self, key: &str) -> Result<(), BitfieldError> {
+        let bitfielddata = self.layout().set_flag(self.get_data(), key)?;
+        self.set_data(bitfielddata);
+        Ok(())
+    }
+    /**
+     * @description: 清除标志
+     * @param {*} mut self  可变的自己
+     * @param {&str} key   标志名字
+     * @return {Result} 成功，无信息，失败时，返回失败原因
+     */
+    pub fn clear_flag(&mut self, key: &str) -> Result<(), BitfieldError> {
+        let bitfielddata = self.layout().clear_flag(self.get_data(), key)?;
+        self.set_data(bitfielddata);
+        Ok(())
+    }
+    /**
+     * @description: 翻转标志
+     * @param {*} mut self  可变的自己
+     * @param {&str} key   标志名字
+     * @return {Result} 成功，无信息，失败时，返回失败原因
+     */
+    pub fn toggle(&mut self, key: &str) -> Result<(), BitfieldError> {
+        let bitfielddata = self.layout().toggle(self.get_data(), key)?;
+        self.set_data(bitfielddata);
+        Ok(())
+    }
+    /**
+     * @description: 把置位的标志格式化为"A | B"
+     * @param {*} self  不可变的自己
+     * @return {String} 标志文本
+     */
+    pub fn format_flags(&self) -> String {
+        self.layout().format_flags(self.get_data())
+    }
+    /**
+     * @description: 解析"A | B"形式的标志文本
+     * @param {*} self  不可变的自己
+     * @param {&str} text   标志文本
+     * @return {Result} 成功，返回只包含这些标志的位模式，失败时，返回失败原因
+     */
+    pub fn parse_flags(&self, text: &str) -> Result<T, BitfieldError> {
+        self.layout().parse_flags(text)
+    }
+    /**
+     * @description: 与flags求并集,结果写回
+     * @param {*} mut self  可变的自己
+     * @param {T} flags 另一组标志
+     * @return {无} 无
+     */
+    pub fn union_with(&mut self, flags: T) {
+        let bitfielddata = self.layout().union(self.get_data(), flags);
+        self.set_data(bitfielddata);
+    }
+    /**
+     * @description: 与flags求交集,结果写回
+     * @param {*} mut self  可变的自己
+     * @param {T} flags 另一组标志
+     * @return {无} 无
+     */
+    pub fn intersection_with(&mut self, flags: T) {
+        let bitfielddata = self.layout().intersection(self.get_data(), flags);
+        self.set_data(bitfielddata);
+    }
+    /**
+     * @description: 与flags求差集,结果写回
+     * @param {*} mut self  可变的自己
+     * @param {T} flags 另一组标志
+     * @return {无} 无
+     */
+    pub fn difference_with(&mut self, flags: T) {
+        let bitfielddata = self.layout().difference(self.get_data(), flags);
+        self.set_data(bitfielddata);
+    }
+    /**
+     * @description: 翻转全部已定义标志,结果写回
+     * @param {*} mut self  可变的自己
+     * @return {无} 无
+     */
+    pub fn complement_flags(&mut self) {
+        let bitfielddata = self.layout().complement(self.get_data());
+        self.set_data(bitfielddata);
+    }
+}
+
+impl<T: BitStorage> BitfieldRef<'_, T> {
+    /**
+     * @description: 标志是否置位
+     * @param {*} self  不可变的自己
+     * @param {&str} key   标志名字
+     * @return {Result} 成功，置位时返回true,失败时，返回失败原因
+     */
+    pub fn is_set(&self, key: &str) -> Result<bool, BitfieldError> {
+        self.layout().is_set(self.get_data(), key)
+    }
+    /**
+     * @description: 置位标志
+     * @param {*} mut self  可变的自己
+     * @param {&str} key   标志名字
+     * @return {Result} 成功，无信息，失败时，返回失败原因
+     */
+    pub fn set_flag(&mut self, key: &str) -> Result<(), BitfieldError> {
+        let bitfielddata = self.layout().set_flag(self.get_data(), key)?;
+        self.set_data(bitfielddata);
+        Ok(())
+    }
+    /**
+     * @description: 清除标志
+     * @param {*} mut self  可变的自己
+     * @param {&str} key   标志名字
+     * @return {Result} 成功，无信息，失败时，返回失败原因
+     */
+    pub fn clear_flag(&mut self, key: &str) -> Result<(), BitfieldError> {
+        let bitfielddata = self.layout().clear_flag(self.get_data(), key)?;
+        self.set_data(bitfielddata);
+        Ok(())
+    }
+    /**
+     * @description: 翻转标志
+     * @param {*} mut self  可变的自己
+     * @param {&str} key   标志名字
+     * @return {Result} 成功，无信息，失败时，返回失败原因
+     */
+    pub fn toggle(&mut self, key: &str) -> Result<(), BitfieldError> {
+        let bitfielddata = self.layout().toggle(self.get_data(), key)?;
+        self.set_data(bitfielddata);
+        Ok(())
+    }
+    /**
+     * @description: 把置位的标志格式化为"A | B"
+     * @param {*} self  不可变的自己
+     * @return {String} 标志文本
+     */
+    pub fn format_flags(&self) -> String {
+        self.layout().format_flags(self.get_data())
+    }
+}
@@ -0,0 +1,477 @@
+use std::fmt;
+
+use crate::bitfield::BitStorage;
+use crate::error::BitfieldError;
+use crate::layout::Layout;
+use crate::order::{BitOrder, ByteOrder};
+
+/**
+ * 布局定义文件中的一个位域
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldDef {
+    name: String,
+    start: u8,
+    length: u8,
+    signed: bool,
+    alias: bool,
+    description: String,
+    line: usize,
+    column: usize,
+}
+
+/**
+ * 与存储类型无关的布局定义,是布局定义文件的内存形式
+ * 文件按行书写,#之后为注释,每行是下面的一种:
+ *   width <8|16|32|64|128>             存储位宽,必须出现且只能出现一次
+ *   order <lsb0|msb0> <little|big>     位编号方式和字节序,可省略,默认lsb0 little
+ *   <名字> <开始地址> <长度> [signed|alias] ["说明"]
+ * 名字中含有空白、#或与关键字相同时需要加双引号,说明必须加双引号,引号内可以用\"、\\和\n转义
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LayoutDef {
+    width: usize,
+    bit_order: BitOrder,
+    byte_order: ByteOrder,
+    fields: Vec<FieldDef>,
+}
+
+/**
+ * 一行中的一个词:开始列号,内容,是否加了引号
+ */
+struct Token {
+    column: usize,
+    text: String,
+    quoted: bool,
+}
+
+/**
+ * @description: 生成带行号列号的语法错误
+ * @param {usize} line  行号
+ * @param {usize} column    列号
+ * @param {String} message  错误信息
+ * @return {BitfieldError}  Syntax错误
+ */
+fn syntax(line: usize, column: usize, message: String) -> BitfieldError {
+    BitfieldError::Syntax { line, column, message }
+}
+
+/**
+ * @description: 把一行拆分成词,去掉注释,处理引号和转义
+ * @param {&str} text   一行文本
+ * @param {usize} line  行号,用于生成错误信息
+ * @return {Result} 成功，返回词列表，失败时，返回失败原因
+ */
+fn tokenize(text: &str, line: usize) -> Result<Vec<Token>, BitfieldError> {
+    let mut tokens = Vec::new();
+    let mut chars = text.chars().enumerate().peekable();
+    while let Some((index, c)) = chars.next() {
+        let column = index + 1;
+        if c.is_whitespace() {
+            continue;
+        }
+        if c == '#' {
+            break;
+        }
+        let mut token = String::new();
+        if c == '"' {
+            loop {
+                match chars.next() {
+                    Some((_, '"')) => break,
+                    Some((_, '\\')) => match chars.next() {
+                        Some((_, '"')) => token.push('"'),
+                        Some((_, '\\')) => token.push('\\'),
+                        Some((_, 'n')) => token.push('\n'),
+                        Some((index, other)) => return Err(syntax(line, index + 1, format!("unknown escape \\{}", other))),
+                        None => return Err(syntax(line, column, "unterminated string".to_string())),
+                    },
+                    Some((_, other)) => token.push(other),
+                    None => return Err(syntax(line, column, "unterminated string".to_string())),
+                }
+            }
+            if let Some((index, next)) = chars.peek() {
+                if !next.is_whitespace() && *next != '#' {
+                    return Err(syntax(line, index + 1, "expected whitespace after string".to_string()));
+                }
+            }
+            tokens.push(Token { column, text: token, quoted: true });
+        } else {
+            token.push(c);
+            while let Some((_, next)) = chars.peek() {
+                if next.is_whitespace() || *next == '#' {
+                    break;
+                }
+                if *next == '"' {
+                    let index = chars.peek().map(|(index, _)| *index).unwrap_or(index);
+                    return Err(syntax(line, index + 1, "unexpected quote".to_string()));
+                }
+                token.push(*next);
+                chars.next();
+            }
+            tokens.push(Token { column, text: token, quoted: false });
+        }
+    }
+    Ok(tokens)
+}
+
+/**
+ * @description: 把词解析为数字
+ * @param {&Token} token    词
+ * @param {usize} line  行号,用于生成错误信息
+ * @param {&str} what   数字的含义,用于生成错误信息
+ * @return {Result} 成功，返回数字，失败时，返回失败原因
+ */
+fn number<N: std::str::FromStr>(token: &Token, line: usize, what: &str) -> Result<N, BitfieldError> {
+    if token.quoted {
+        return Err(syntax(line, token.column, format!("expected {}, found string", what)));
+    }
+    token.text.parse::<N>().map_err(|_| syntax(line, token.column, format!("invalid {}: {}", what, token.text)))
+}
+
+/**
+ * @description: 判断名字在写出时是否需要加引号
+ * @param {&str} name   名字
+ * @return {bool}   需要加引号时返回true
+ */
+fn needs_quotes(name: &str) -> bool {
+    name.is_empty() || name == "width" || name == "order" || name.chars().any(|c| c.is_whitespace() || c == '#' || c == '"' || c == '\\')
+}
+
+/**
+ * @description: 给文字加上引号并转义
+ * @param {&str} text   文字
+ * @return {String} 加引号后的文字
+ */
+fn quote(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n"))
+}
+
+impl FieldDef {
+    /**
+     * @description: 创建一个不对应文件位置的位域定义,供其他格式的导入使用
+     * @param {String} name 位域名字
+     * @param {u8} start    位域开始地址
+     * @param {u8} length   位域长度
+     * @param {bool} signed 是否有符号
+     * @param {bool} alias  是否为别名位域
+     * @param {String} description  说明文字
+     * @return {FieldDef}   位域定义
+     */
+    pub(crate) fn new(name: String, start: u8, length: u8, signed: bool, alias: bool, description: String) -> FieldDef {
+        FieldDef { name, start, length, signed, alias, description, line: 0, column: 0 }
+    }
+    /**
+     * @description: 获取位域名字
+     * @param {*} self  不可变的自己
+     * @return {&str}   位域名字
+     */
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    /**
+     * @description: 获取位域开始地址
+     * @param {*} self  不可变的自己
+     * @return {u8} 开始地址
+     */
+    pub fn start(&self) -> u8 {
+        self.start
+    }
+    /**
+     * @description: 获取位域长度
+     * @param {*} self  不可变的自己
+     * @return {u8} 长度
+     */
+    pub fn length(&self) -> u8 {
+        self.length
+    }
+    /**
+     * @description: 位域是否按有符号补码读写
+     * @param {*} self  不可变的自己
+     * @return {bool}   有符号时返回true
+     */
+    pub fn is_signed(&self) -> bool {
+        self.signed
+    }
+    /**
+     * @description: 位域是否为允许重叠的别名位域
+     * @param {*} self  不可变的自己
+     * @return {bool}   别名位域时返回true
+     */
+    pub fn is_alias(&self) -> bool {
+        self.alias
+    }
+    /**
+     * @description: 获取位域的说明文字
+     * @param {*} self  不可变的自己
+     * @return {&str}   说明文字,没有说明时为空字符串
+     */
+    pub fn description(&self) -> &str {
+        &self.description
+    }
+}
+
+impl LayoutDef {
+    /**
+     * @description: 创建一个布局定义,不做任何检查,供其他格式的导入使用
+     * @param {usize} width    存储位宽
+     * @param {BitOrder} bit_order  位编号方式
+     * @param {ByteOrder} byte_order    字节序
+     * @param {Vec<FieldDef>} fields    位域定义
+     * @return {LayoutDef}  布局定义
+     */
+    pub(crate) fn new(width: usize, bit_order: BitOrder, byte_order: ByteOrder, fields: Vec<FieldDef>) -> LayoutDef {
+        LayoutDef { width, bit_order, byte_order, fields }
+    }
+    /**
+     * @description: 静态函数，解析布局定义文本,并检查位域是否越界或重叠
+     * @param {&str} text   布局定义文本
+     * @return {Result} 成功，返回布局定义，失败时，返回带行号列号的失败原因
+     */
+    pub fn parse(text: &str) -> Result<LayoutDef, BitfieldError> {
+        let mut width: Option<(usize, usize)> = None;
+        let mut order: Option<usize> = None;
+        let mut def = LayoutDef { width: 0, bit_order: BitOrder::Lsb0, byte_order: ByteOrder::LittleEndian, fields: Vec::new() };
+        let mut lines = 0;
+        for (index, text) in text.lines().enumerate() {
+            let line = index + 1;
+            lines = line;
+            let tokens = tokenize(text, line)?;
+            let Some(first) = tokens.first() else {
+                continue;
+            };
+            let keyword = if first.quoted { "" } else { first.text.as_str() };
+            match keyword {
+                "width" => {
+                    if let Some((previous, _)) = width {
+                        return Err(syntax(line, first.column, format!("width already given on line {}", previous)));
+                    }
+                    let bits = tokens.get(1).ok_or_else(|| syntax(line, first.column + 5, "expected width".to_string()))?;
+                    def.width = number::<usize>(bits, line, "width")?;
+                    if ![8, 16, 32, 64, 128].contains(&def.width) {
+                        return Err(syntax(line, bits.column, format!("width must be 8, 16, 32, 64 or 128, found {}", def.width)));
+                    }
+                    if let Some(extra) = tokens.get(2) {
+                        return Err(syntax(line, extra.column, format!("unexpected {}", extra.text)));
+                    }
+                    width = Some((line, bits.column));
+                }
+                "order" => {
+                    if let Some(previous) = order {
+                        return Err(syntax(line, first.column, format!("order already given on line {}", previous)));
+                    }
+                    let expected = || syntax(line, first.column + 5, "expected bit order and byte order".to_string());
+                    let (bit, byte) = match tokens.as_slice() {
+                        [_, bit, byte] => (bit, byte),
+                        [_, _, _, extra, ..] => return Err(syntax(line, extra.column, format!("unexpected {}", extra.text))),
+                        _ => return Err(expected()),
+                    };
+                    def.bit_order = match (bit.quoted, bit.text.as_str()) {
+                        (false, "lsb0") => BitOrder::Lsb0,
+                        (false, "msb0") => BitOrder::Msb0,
+                        _ => return Err(syntax(line, bit.column, format!("bit order must be lsb0 or msb0, found {}", bit.text))),
+                    };
+                    def.byte_order = match (byte.quoted, byte.text.as_str()) {
+                        (false, "little") => ByteOrder::LittleEndian,
+                        (false, "big") => ByteOrder::BigEndian,
+                        _ => return Err(syntax(line, byte.column, format!("byte order must be little or big, found {}", byte.text))),
+                    };
+                    order = Some(line);
+                }
+                _ => def.fields.push(LayoutDef::parse_field(&tokens, line)?),
+            }
+        }
+        if width.is_none() {
+            return Err(syntax(lines + 1, 1, "missing width".to_string()));
+        }
+        def.check()?;
+        Ok(def)
+    }
+    /**
+     * @description: 解析一行位域定义
+     * @param {&[Token]} tokens 一行中的词,至少有一个
+     * @param {usize} line  行号
+     * @return {Result} 成功，返回位域定义，失败时，返回失败原因
+     */
+    fn parse_field(tokens: &[Token], line: usize) -> Result<FieldDef, BitfieldError> {
+        let name = &tokens[0];
+        let end = name.column + name.text.chars().count() + if name.quoted { 2 } else { 0 };
+        let start = tokens.get(1).ok_or_else(|| syntax(line, end, "expected start".to_string()))?;
+        let length = tokens.get(2).ok_or_else(|| syntax(line, end, "expected length".to_string()))?;
+        let mut field = FieldDef {
+            name: name.text.clone(),
+            start: number::<u8>(start, line, "start")?,
+            length: number::<u8>(length, line, "length")?,
+            signed: false,
+            alias: false,
+            description: String::new(),
+            line,
+            column: start.column,
+        };
+        let mut rest = tokens[3..].iter().peekable();
+        if let Some(flag) = rest.next_if(|token| !token.quoted) {
+            match flag.text.as_str() {
+                "signed" => field.signed = true,
+                "alias" => field.alias = true,
+                _ => return Err(syntax(line, flag.column, format!("expected signed, alias or description, found {}", flag.text))),
+            }
+        }
+        if let Some(description) = rest.next_if(|token| token.quoted) {
+            field.description = description.text.clone();
+        }
+        if let Some(extra) = rest.next() {
+            return Err(syntax(line, extra.column, format!("unexpected {}", extra.text)));
+        }
+        Ok(field)
+    }
+    /**
+     * @description: 按存储位宽构建一次布局,把越界、重叠等错误定位到出错的位域
+     * @param {*} self  不可变的自己
+     * @return {Result} 成功，无信息，失败时，返回带行号列号的失败原因
+     */
+    fn check(&self) -> Result<(), BitfieldError> {
+        let result = match self.width {
+            8 => self.insert_all::<u8>().map(|_| ()),
+            16 => self.insert_all::<u16>().map(|_| ()),
+            32 => self.insert_all::<u32>().map(|_| ()),
+            64 => self.insert_all::<u64>().map(|_| ()),
+            _ => self.insert_all::<u128>().map(|_| ()),
+        };
+        result.map_err(|(index, err)| {
+            let field = &self.fields[index];
+            syntax(field.line, field.column, format!("{}: {}", field.name, err))
+        })
+    }
+    /**
+     * @description: 按顺序把所有位域插入新布局
+     * @param {*} self  不可变的自己
+     * @return {Result} 成功，返回布局，失败时，返回出错位域的序号和失败原因
+     */
+    fn insert_all<T: BitStorage>(&self) -> Result<Layout<T>, (usize, BitfieldError)> {
+        let mut layout = Layout::with_order(self.bit_order, self.byte_order);
+        for (index, field) in self.fields.iter().enumerate() {
+            let result = if field.alias {
+                layout.insert_alias(field.name.clone(), field.start, field.length)
+            } else if field.signed {
+                layout.insert_signed(field.name.clone(), field.start, field.length)
+            } else {
+                layout.insert(field.name.clone(), field.start, field.length)
+            };
+            result.and_then(|_| layout.set_description(&field.name, field.description.clone())).map_err(|err| (index, err))?;
+        }
+        Ok(layout)
+    }
+    /**
+     * @description: 静态函数，从已有布局生成布局定义,位域按插入顺序排列
+     * @param {&Layout} layout  位域布局
+     * @return {LayoutDef}  布局定义
+     */
+    pub fn from_layout<T: BitStorage>(layout: &Layout<T>) -> LayoutDef {
+        let fields = layout
+            .fields_by_insertion()
+            .map(|(name, value)| {
+                FieldDef::new(name.to_string(), value.start(), value.length(), value.is_signed(), value.is_alias(), value.description().to_string())
+            })
+            .collect();
+        LayoutDef::new(T::BITS as usize, layout.bit_order(), layout.byte_order(), fields)
+    }
+    /**
+     * @description: 构建指定存储类型的布局
+     * @param {*} self  不可变的自己
+     * @return {Result} 成功，返回布局，存储类型位宽与定义不符或位域非法时，返回失败原因
+     */
+    pub fn build<T: BitStorage>(&self) -> Result<Layout<T>, BitfieldError> {
+        if self.width != T::BITS as usize {
+            return Err(BitfieldError::WidthMismatch { expected: self.width, actual: T::BITS as usize });
+        }
+        self.insert_all().map_err(|(_, err)| err)
+    }
+    /**
+     * @description: 获取存储位宽
+     * @param {*} self  不可变的自己
+     * @return {usize}  存储位宽
+     */
+    pub fn width(&self) -> usize {
+        self.width
+    }
+    /**
+     * @description: 获取位编号方式
+     * @param {*} self  不可变的自己
+     * @return {BitOrder}   位编号方式
+     */
+    pub fn bit_order(&self) -> BitOrder {
+        self.bit_order
+    }
+    /**
+     * @description: 获取字节序
+     * @param {*} self  不可变的自己
+     * @return {ByteOrder}  字节序
+     */
+    pub fn byte_order(&self) -> ByteOrder {
+        self.byte_order
+    }
+    /**
+     * @description: 获取位域定义,按文件中的顺序排列
+     * @param {*} self  不可变的自己
+     * @return {&[FieldDef]}    位域定义
+     */
+    pub fn fields(&self) -> &[FieldDef] {
+        &self.fields
+    }
+}
+
+/**
+ * 按布局定义文件的格式写出,parse可以读回相同的定义
+ */
+impl fmt::Display for LayoutDef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "width {}", self.width)?;
+        if (self.bit_order, self.byte_order) != (BitOrder::Lsb0, ByteOrder::LittleEndian) {
+            let bit = match self.bit_order {
+                BitOrder::Lsb0 => "lsb0",
+                BitOrder::Msb0 => "msb0",
+            };
+            let byte = match self.byte_order {
+                ByteOrder::LittleEndian => "little",
+                ByteOrder::BigEndian => "big",
+            };
+            writeln!(f, "order {} {}", bit, byte)?;
+        }
+        for field in &self.fields {
+            if needs_quotes(&field.name) {
+                write!(f, "{} {} {}", quote(&field.name), field.start, field.length)?;
+            } else {
+                write!(f, "{} {} {}", field.name, field.start, field.length)?;
+            }
+            if field.signed {
+                write!(f, " signed")?;
+            }
+            if field.alias {
+                write!(f, " alias")?;
+            }
+            if !field.description.is_empty() {
+                write!(f, " {}", quote(&field.description))?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+impl<T: BitStorage> Layout<T> {
+    /**
+     * @description: 静态函数，从布局定义文本创建布局
+     * @param {&str} text   布局定义文本
+     * @return {Result} 成功，返回布局，失败时，返回失败原因
+     */
+    pub fn parse(text: &str) -> Result<Layout<T>, BitfieldError> {
+        LayoutDef::parse(text)?.build()
+    }
+    /**
+     * @description: 按布局定义文件的格式写出布局
+     * @param {*} self  不可变的自己
+     * @return {String} 布局定义文本
+     */
+    pub fn to_text(&self) -> String {
+        LayoutDef::from_layout(self).to_string()
+    }
+}
@@ -0,0 +1,100 @@
+use std::marker::PhantomData;
+
+use crate::bitfield::BitStorage;
+use crate::error::BitfieldError;
+use crate::layout::BitfieldValue;
+
+/**
+ * 预解析的位域句柄,保存位域的移位量和掩码
+ * 通过Layout::field获取一次后,读写时不再查找哈希表,也不做任何内存分配
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FieldHandle<T> {
+    offset: u8,
+    length: u8,
+    mask: u128,
+    signed: bool,
+    storage: PhantomData<T>,
+}
+
+impl<T: BitStorage> FieldHandle<T> {
+    /**
+     * @description: 由位域信息生成句柄
+     * @param {&BitfieldValue} value  位域信息
+     * @return {FieldHandle}    句柄
+     */
+    pub(crate) fn new(value: &BitfieldValue) -> FieldHandle<T> {
+        FieldHandle {
+            offset: value.offset(),
+            length: value.length(),
+            mask: T::mask(value.length()),
+            signed: value.is_signed(),
+            storage: PhantomData,
+        }
+    }
+    /**
+     * @description: 从数据字中取出位域的值
+     * @param {*} self  句柄
+     * @param {T} bitfielddata 需要做位域处理的数据
+     * @return {T}  位域的值,有符号位域按存储类型的位宽做符号扩展
+     */
+    #[inline]
+    pub fn get(self, bitfielddata: T) -> T {
+        let raw = (bitfielddata.to_raw() >> self.offset) & self.mask;
+        if self.signed {
+            T::from_raw(T::sign_extend(raw, self.length))
+        } else {
+            T::from_raw(raw)
+        }
+    }
+    /**
+     * @description: 只检查值能否写入位域,不修改任何数据,用于在访问寄存器之前校验
+     * @param {*} self  句柄
+     * @param {T} value     要写入的值,有符号位域按存储类型位宽的补码解释
+     * @return {Result}     能写入，无信息，超出位域能表示的范围时，返回失败原因
+     */
+    #[inline]
+    pub fn check(self, value: T) -> Result<(), BitfieldError> {
+        //防止超范围
+        if self.signed {
+            let value = T::sign_extend(value.to_raw(), T::BITS) as i128;
+            let max = (self.mask >> 1) as i128;
+            let min = if self.length == 0 { 0 } else { -max - 1 };
+            if value < min || value > max {
+                return Err(BitfieldError::SignedValueOutOfRange { value, min, max });
+            }
+        } else if value.to_raw() > self.mask {
+            return Err(BitfieldError::ValueOutOfRange { value: value.to_raw(), max: self.mask });
+        }
+        Ok(())
+    }
+    /**
+     * @description: 把值写入数据字中的位域
+     * @param {*} self  句柄
+     * @param {T} bitfielddata 需要做位域处理的数据
+     * @param {T} value     要写入的值,有符号位域按存储类型位宽的补码解释
+     * @return {Result}     成功，返回写入后的数据字，失败，返回失败原因
+     */
+    #[inline]
+    pub fn set(self, bitfielddata: T, value: T) -> Result<T, BitfieldError> {
+        self.check(value)?;
+        let mask = self.mask << self.offset;
+        Ok(T::from_raw((bitfielddata.to_raw() & !mask) | ((value.to_raw() << self.offset) & mask)))
+    }
+    /**
+     * @description: 获取位域最低位在数据字中的位置
+     * @param {*} self  句柄
+     * @return {u8} 最低位位置
+     */
+    pub fn offset(self) -> u8 {
+        self.offset
+    }
+    /**
+     * @description: 获取位域长度
+     * @param {*} self  句柄
+     * @return {u8} 长度
+     */
+    pub fn length(self) -> u8 {
+        self.length
+    }
+}
@@ -0,0 +1,271 @@
+use serde_json::{Map, Value};
+
+use crate::bitfield::{BitStorage, Bitfield};
+use crate::error::BitfieldError;
+use crate::format::{FieldDef, LayoutDef};
+use crate::handle::FieldHandle;
+use crate::layout::{BitfieldValue, Layout};
+use crate::order::{BitOrder, ByteOrder};
+
+/**
+ * @description: 生成文档结构错误
+ * @param {&str} path   出错的位置
+ * @param {&str} message    错误信息
+ * @return {BitfieldError}  InvalidDocument错误
+ */
+fn invalid(path: &str, message: &str) -> BitfieldError {
+    BitfieldError::InvalidDocument { path: path.to_string(), message: message.to_string() }
+}
+
+/**
+ * @description: 读取对象中的一个成员
+ * @param {&Map} object JSON对象
+ * @param {&str} path   对象的位置
+ * @param {&str} key    成员名字
+ * @return {(Option, String)} 成员(不存在时为None)和成员的位置
+ */
+fn member<'a>(object: &'a Map<String, Value>, path: &str, key: &str) -> (Option<&'a Value>, String) {
+    let path = if path.is_empty() { key.to_string() } else { format!("{}.{}", path, key) };
+    (object.get(key), path)
+}
+
+/**
+ * @description: 读取必需的无符号整数成员
+ * @param {&Map} object JSON对象
+ * @param {&str} path   对象的位置
+ * @param {&str} key    成员名字
+ * @param {u64} max 允许的最大值
+ * @return {Result} 成功，返回成员的值，失败时，返回失败原因
+ */
+fn integer(object: &Map<String, Value>, path: &str, key: &str, max: u64) -> Result<u64, BitfieldError> {
+    match member(object, path, key) {
+        (Some(value), path) => match value.as_u64() {
+            Some(number) if number <= max => Ok(number),
+            _ => Err(invalid(&path, &format!("expected integer 0..={}", max))),
+        },
+        (None, path) => Err(invalid(&path, "missing")),
+    }
+}
+
+/**
+ * @description: 读取可省略的布尔成员
+ * @param {&Map} object JSON对象
+ * @param {&str} path   对象的位置
+ * @param {&str} key    成员名字
+ * @return {Result} 成功，返回成员的值(省略时为false)，失败时，返回失败原因
+ */
+fn flag(object: &Map<String, Value>, path: &str, key: &str) -> Result<bool, BitfieldError> {
+    match member(object, path, key) {
+        (Some(value), path) => value.as_bool().ok_or_else(|| invalid(&path, "expected boolean")),
+        (None, _) => Ok(false),
+    }
+}
+
+/**
+ * @description: 读取可省略的字符串成员
+ * @param {&Map} object JSON对象
+ * @param {&str} path   对象的位置
+ * @param {&str} key    成员名字
+ * @param {&str} default    省略时的值
+ * @return {Result} 成功，返回成员的值，失败时，返回失败原因
+ */
+fn text<'a>(object: &'a Map<String, Value>, path: &str, key: &str, default: &'a str) -> Result<&'a str, BitfieldError> {
+    match member(object, path, key) {
+        (Some(value), path) => value.as_str().ok_or_else(|| invalid(&path, "expected string")),
+        (None, _) => Ok(default),
+    }
+}
+
+/**
+ * @description: 把位域的值转换为JSON数值,超出64位整数范围时转换为十进制字符串
+ * @param {&BitfieldValue} value  位域信息
+ * @param {T} bitfielddata 需要做位域处理的数据
+ * @return {Value}  JSON值
+ */
+fn field_to_json<T: BitStorage>(value: &BitfieldValue, bitfielddata: T) -> Value {
+    let raw = (bitfielddata.to_raw() >> value.offset()) & T::mask(value.length());
+    if value.is_signed() {
+        let number = T::sign_extend(raw, value.length()) as i128;
+        i64::try_from(number).map_or_else(|_| Value::String(number.to_string()), Value::from)
+    } else {
+        u64::try_from(raw).map_or_else(|_| Value::String(raw.to_string()), Value::from)
+    }
+}
+
+/**
+ * @description: 把JSON数值或十进制字符串转换为位域的值,并检查是否在位域能表示的范围内
+ * @param {&BitfieldValue} param  位域信息
+ * @param {&Value} value    JSON值
+ * @param {&str} path   值的位置
+ * @return {Result} 成功，返回按存储位宽补码表示的值，失败时，返回失败原因
+ */
+fn field_from_json<T: BitStorage>(param: &BitfieldValue, value: &Value, path: &str) -> Result<T, BitfieldError> {
+    //负数保存为i128的补码位模式
+    let number = match value {
+        Value::Number(number) => number.as_u64().map(|raw| (raw as u128, false)).or_else(|| number.as_i64().map(|raw| (raw as u128, raw < 0))),
+        Value::String(text) if text.starts_with('-') => text.parse::<i128>().ok().map(|raw| (raw as u128, raw < 0)),
+        Value::String(text) => text.parse::<u128>().ok().map(|raw| (raw, false)),
+        _ => None,
+    };
+    let (raw, negative) = number.ok_or_else(|| invalid(path, "expected integer"))?;
+    //在128位上按FieldHandle::check的规则校验,避免先截断到存储类型
+    if param.is_signed() {
+        //超过i128的正数按i128::MAX报告,避免被当作补码的负数
+        let value = if negative { raw as i128 } else { i128::try_from(raw).unwrap_or(i128::MAX) };
+        FieldHandle::<i128>::new(param).check(value)?;
+    } else if negative {
+        //API中负数只能以补码的形式写入,JSON中的负数对无符号位域总是超出范围
+        let max = T::mask(param.length()).min(i128::MAX as u128) as i128;
+        return Err(BitfieldError::SignedValueOutOfRange { value: raw as i128, min: 0, max });
+    } else {
+        FieldHandle::<u128>::new(param).check(raw)?;
+    }
+    Ok(T::from_raw(raw))
+}
+
+/**
+ * JSON导入导出,需要启用json特性
+ * 布局文档: {"width":32,"bit_order":"lsb0","byte_order":"little","fields":[{"name":"mode","start":0,"length":3,
+ *           "signed":false,"alias":false,"description":""}]},位域按插入顺序排列,导入时signed、alias、description可省略
+ * 位域值文档: {"mode":5,"level":-2},有符号位域为负数,超出64位整数范围的值写成十进制字符串
+ */
+impl<T: BitStorage> Layout<T> {
+    /**
+     * @description: 导出布局文档
+     * @param {*} self  不可变的自己
+     * @return {Value}  布局文档
+     */
+    pub fn to_json(&self) -> Value {
+        let def = LayoutDef::from_layout(self);
+        let fields: Vec<Value> = def
+            .fields()
+            .iter()
+            .map(|field| {
+                let mut object = Map::new();
+                object.insert("name".to_string(), Value::from(field.name()));
+                object.insert("start".to_string(), Value::from(field.start()));
+                object.insert("length".to_string(), Value::from(field.length()));
+                object.insert("signed".to_string(), Value::from(field.is_signed()));
+                object.insert("alias".to_string(), Value::from(field.is_alias()));
+                object.insert("description".to_string(), Value::from(field.description()));
+                Value::Object(object)
+            })
+            .collect();
+        let mut object = Map::new();
+        object.insert("width".to_string(), Value::from(def.width()));
+        object.insert("bit_order".to_string(), Value::from(if def.bit_order() == BitOrder::Msb0 { "msb0" } else { "lsb0" }));
+        object.insert("byte_order".to_string(), Value::from(if def.byte_order() == ByteOrder::BigEndian { "big" } else { "little" }));
+        object.insert("fields".to_string(), Value::Array(fields));
+        Value::Object(object)
+    }
+    /**
+     * @description: 静态函数，从布局文档创建布局,每个位域都经过与insert相同的检查
+     * @param {&Value} document 布局文档
+     * @return {Result} 成功，返回布局，失败时，返回失败原因
+     */
+    pub fn from_json(document: &Value) -> Result<Layout<T>, BitfieldError> {
+        let object = document.as_object().ok_or_else(|| invalid("", "expected object"))?;
+        let width = integer(object, "", "width", 128)? as usize;
+        let bit_order = match text(object, "", "bit_order", "lsb0")? {
+            "lsb0" => BitOrder::Lsb0,
+            "msb0" => BitOrder::Msb0,
+            _ => return Err(invalid("bit_order", "expected lsb0 or msb0")),
+        };
+        let byte_order = match text(object, "", "byte_order", "little")? {
+            "little" => ByteOrder::LittleEndian,
+            "big" => ByteOrder::BigEndian,
+            _ => return Err(invalid("byte_order", "expected little or big")),
+        };
+        let items = match member(object, "", "fields") {
+            (Some(Value::Array(items)), _) => items.as_slice(),
+            (Some(_), path) => return Err(invalid(&path, "expected array")),
+            (None, _) => &[],
+        };
+        let mut fields = Vec::new();
+        for (index, item) in items.iter().enumerate() {
+            let path = format!("fields[{}]", index);
+            let field = item.as_object().ok_or_else(|| invalid(&path, "expected object"))?;
+            let name = match member(field, &path, "name") {
+                (Some(Value::String(name)), _) => name.clone(),
+                (Some(_), path) => return Err(invalid(&path, "expected string")),
+                (None, path) => return Err(invalid(&path, "missing")),
+            };
+            let start = integer(field, &path, "start", u8::MAX as u64)? as u8;
+            let length = integer(field, &path, "length", u8::MAX as u64)? as u8;
+            let signed = flag(field, &path, "signed")?;
+            let alias = flag(field, &path, "alias")?;
+            if signed && alias {
+                return Err(invalid(&path, "alias fields cannot be signed"));
+            }
+            let description = text(field, &path, "description", "")?.to_string();
+            fields.push(FieldDef::new(name, start, length, signed, alias, description));
+        }
+        LayoutDef::new(width, bit_order, byte_order, fields).build()
+    }
+    /**
+     * @description: 导出数据字中每个位域的值
+     * @param {*} self  不可变的自己
+     * @param {T} bitfielddata 需要做位域处理的数据
+     * @return {Value}  位域值文档
+     */
+    pub fn values_to_json(&self, bitfielddata: T) -> Value {
+        Value::Object(self.fields().map(|(name, value)| (name.to_string(), field_to_json(value, bitfielddata))).collect())
+    }
+    /**
+     * @description: 按位域值文档把值写入数据字,文档中没有的位域保持不变
+     * @param {*} self  不可变的自己
+     * @param {T} bitfielddata 需要做位域处理的数据
+     * @param {&Value} document 位域值文档
+     * @return {Result} 成功，返回写入后的数据字，失败时，返回失败原因
+     */
+    pub fn values_from_json(&self, bitfielddata: T, document: &Value) -> Result<T, BitfieldError> {
+        let object = document.as_object().ok_or_else(|| invalid("", "expected object"))?;
+        object.iter().try_fold(bitfielddata, |bitfielddata, (key, value)| {
+            let param = self.get_param(key)?;
+            self.set_value(bitfielddata, key, field_from_json(param, value, key)?)
+        })
+    }
+}
+
+/**
+ * Bitfield文档是布局文档再加上"values"位域值文档
+ */
+impl<T: BitStorage> Bitfield<T> {
+    /**
+     * @description: 导出布局文档,并在"values"中附带当前数据的位域值
+     * @param {*} self  不可变的自己
+     * @return {Value}  Bitfield文档
+     */
+    pub fn to_json(&self) -> Value {
+        let mut document = self.layout().to_json();
+        if let Value::Object(object) = &mut document {
+            object.insert("values".to_string(), self.layout().values_to_json(self.get_data()));
+        }
+        document
+    }
+    /**
+     * @description: 导出当前数据的位域值
+     * @param {*} self  不可变的自己
+     * @return {Value}  位域值文档
+     */
+    pub fn values_to_json(&self) -> Value {
+        self.layout().values_to_json(self.get_data())
+    }
+    /**
+     * @description: 静态函数，从Bitfield文档创建位域处理结构体,没有"values"时数据为0
+     * @param {&Value} document Bitfield文档
+     * @return {Result} 成功，返回位域处理结构体，失败时，返回失败原因
+     */
+    pub fn from_json(document: &Value) -> Result<Bitfield<T>, BitfieldError> {
+        let layout = Layout::from_json(document)?;
+        let bitfielddata = match document.get("values") {
+            Some(values) => layout.values_from_json(T::from_raw(0), values).map_err(|err| match err {
+                BitfieldError::InvalidDocument { path, message } if path.is_empty() => invalid("values", &message),
+                BitfieldError::InvalidDocument { path, message } => invalid(&format!("values.{}", path), &message),
+                err => err,
+            })?,
+            None => T::from_raw(0),
+        };
+        Ok(Bitfield::from_layout(layout, bitfielddata))
+    }
+}
@@ -0,0 +1,702 @@
+use std::collections::HashMap;
+use std::marker::PhantomData;
+
+use crate::access::Access;
+use crate::bitfield::BitStorage;
+use crate::enumeration::EnumTable;
+use crate::error::BitfieldError;
+use crate::handle::FieldHandle;
+use crate::order::{BitOrder, ByteOrder, Placement};
+use crate::scaling::Scaling;
+
+/**
+ * 位域数据存放结构体
+ */
+#[derive(Debug, Clone)]
+pub struct BitfieldValue {
+    start : u8 ,
+    offset : u8 ,
+    length : u8 ,
+    alias : bool ,
+    signed : bool ,
+    order : usize ,
+    description : String ,
+    scaling : Option<Scaling> ,
+    enum_table : Option<EnumTable> ,
+    access : Access ,
+}
+
+impl BitfieldValue {
+    /**
+     * @description: 获取位域开始地址,按布局的位编号方式计数
+     * @param {*} self  不可变的自己
+     * @return {u8} 开始地址
+     */
+    pub fn start(&self) -> u8 {
+        self.start
+    }
+    /**
+     * @description: 获取按位编号方式和字节序解析后,位域最低位在数据字中的位置
+     * @param {*} self  不可变的自己
+     * @return {u8} 最低位位置
+     */
+    pub fn offset(&self) -> u8 {
+        self.offset
+    }
+    /**
+     * @description: 获取位域长度
+     * @param {*} self  不可变的自己
+     * @return {u8} 长度
+     */
+    pub fn length(&self) -> u8 {
+        self.length
+    }
+    /**
+     * @description: 位域是否按有符号补码读写
+     * @param {*} self  不可变的自己
+     * @return {bool}   有符号时返回true
+     */
+    pub fn is_signed(&self) -> bool {
+        self.signed
+    }
+    /**
+     * @description: 位域是否为允许重叠的别名位域
+     * @param {*} self  不可变的自己
+     * @return {bool}   别名位域时返回true
+     */
+    pub fn is_alias(&self) -> bool {
+        self.alias
+    }
+    /**
+     * @description: 获取位域的说明文字
+     * @param {*} self  不可变的自己
+     * @return {&str}   说明文字,没有说明时为空字符串
+     */
+    pub fn description(&self) -> &str {
+        &self.description
+    }
+    /**
+     * @description: 获取位域的物理量换算信息
+     * @param {*} self  不可变的自己
+     * @return {Option}     设置了换算信息时返回
+     */
+    pub fn scaling(&self) -> Option<&Scaling> {
+        self.scaling.as_ref()
+    }
+    /**
+     * @description: 获取位域的值到名字的对照表
+     * @param {*} self  不可变的自己
+     * @return {Option}     设置了对照表时返回
+     */
+    pub fn enum_table(&self) -> Option<&EnumTable> {
+        self.enum_table.as_ref()
+    }
+    /**
+     * @description: 获取位域的访问权限
+     * @param {*} self  不可变的自己
+     * @return {Access}     访问权限,默认为可读可写
+     */
+    pub fn access(&self) -> Access {
+        self.access
+    }
+}
+
+/**
+ * 位域布局结构体
+ */
+#[derive(Debug, Clone)]
+pub struct Layout<T> {
+    bitmap: HashMap<String,BitfieldValue>,
+    bit_order: BitOrder,
+    byte_order: ByteOrder,
+    inserted: usize,
+    storage: PhantomData<T>,
+}
+
+/**
+ * 数据视图结构体,引用一个布局并持有一个数据字
+ */
+#[derive(Debug, Clone, Copy)]
+pub struct BitfieldRef<'a, T> {
+    layout: &'a Layout<T>,
+    bitfielddata: T,
+}
+
+/**
+ * 位域布局,只保存位域名字到位置的映射,不保存数据
+ * 布局构建完成后可以放进Arc在多个线程间共享,对任意多个数据字做编解码
+ */
+impl<T: BitStorage> Layout<T> {
+    /**
+     * @description:  静态函数，用于创建一个空布局
+     * @return {Layout} 空布局
+     */
+    pub fn new() -> Layout<T> {
+        Layout::with_order(BitOrder::Lsb0, ByteOrder::LittleEndian)
+    }
+    /**
+     * @description:  静态函数，用指定的位编号方式和字节序创建一个空布局
+     * @param {BitOrder} bit_order  位编号方式
+     * @param {ByteOrder} byte_order    字节序,数据字视为按该字节序排列的字节映像
+     * @return {Layout} 空布局
+     */
+    pub fn with_order(bit_order: BitOrder, byte_order: ByteOrder) -> Layout<T> {
+        Layout {
+            bitmap: HashMap::new(),
+            bit_order,
+            byte_order,
+            inserted: 0,
+            storage: PhantomData,
+        }
+    }
+    /**
+     * @description: 获取位编号方式
+     * @param {*} self  不可变的自己
+     * @return {BitOrder}   位编号方式
+     */
+    pub fn bit_order(&self) -> BitOrder {
+        self.bit_order
+    }
+    /**
+     * @description: 获取字节序
+     * @param {*} self  不可变的自己
+     * @return {ByteOrder}  字节序
+     */
+    pub fn byte_order(&self) -> ByteOrder {
+        self.byte_order
+    }
+    /**
+     * @description: 修改位编号方式和字节序,已有位域按新的设置重新解析
+     * @param {*} mut self  可变的自己
+     * @param {BitOrder} bit_order  位编号方式
+     * @param {ByteOrder} byte_order    字节序
+     * @return {Result}     成功，无信息，有位域越界或重叠时，按开始地址顺序返回第一个失败原因且布局保持不变
+     */
+    pub fn set_order(&mut self, bit_order: BitOrder, byte_order: ByteOrder) -> Result<(), BitfieldError> {
+        let mut layout = Layout::with_order(bit_order, byte_order);
+        layout.inserted = self.inserted;
+        for (key, value) in self.fields() {
+            let offset = layout.locate(value.start, value.length)?;
+            layout.bitmap.insert(key.to_string(), BitfieldValue { offset, ..value.clone() });
+        }
+        if let Err(mut errors) = layout.validate() {
+            return Err(errors.remove(0));
+        }
+        *self = layout;
+        Ok(())
+    }
+    /**
+     * @description: 把字节映像按布局的字节序转换为数据字
+     * @param {*} self  不可变的自己
+     * @param {&[u8]} bytes   字节映像,长度必须等于存储类型的字节数
+     * @return {Result}     成功，返回数据字，失败时，返回失败原因
+     */
+    pub fn data_from_bytes(&self, bytes: &[u8]) -> Result<T, BitfieldError> {
+        let expected = T::BITS as usize / 8;
+        if bytes.len() != expected {
+            return Err(BitfieldError::BufferSize { expected, actual: bytes.len() });
+        }
+        let raw = match self.byte_order {
+            ByteOrder::LittleEndian => bytes.iter().rev().fold(0u128, |raw, byte| raw << 8 | *byte as u128),
+            ByteOrder::BigEndian => bytes.iter().fold(0u128, |raw, byte| raw << 8 | *byte as u128),
+        };
+        Ok(T::from_raw(raw))
+    }
+    /**
+     * @description: 把数据字按布局的字节序转换为字节映像
+     * @param {*} self  不可变的自己
+     * @param {T} bitfielddata 需要转换的数据
+     * @return {Vec<u8>}    字节映像
+     */
+    pub fn data_to_bytes(&self, bitfielddata: T) -> Vec<u8> {
+        let raw = bitfielddata.to_raw();
+        let mut bytes: Vec<u8> = (0..T::BITS as usize / 8).map(|i| (raw >> (i * 8)) as u8).collect();
+        if self.byte_order == ByteOrder::BigEndian {
+            bytes.reverse();
+        }
+        bytes
+    }
+    /**
+     * @description: 将布局与一个数据字组合成轻量的BitfieldRef,不复制布局
+     * @param {*} self  不可变的自己
+     * @param {T} bitfielddata 需要做位域处理的数据
+     * @return {BitfieldRef} 引用本布局的数据视图
+     */
+    pub fn value(&self, bitfielddata: T) -> BitfieldRef<'_, T> {
+        BitfieldRef { layout: self, bitfielddata }
+    }
+    /**
+     * @description: 按位编号方式和字节序解析位域,并检查是否落在存储类型的位宽之内
+     * @param {u8} start    位域开始地址
+     * @param {u8} length   位域长度
+     * @return {Result}     合法，返回位域最低位在数据字中的位置，非法时，返回失败原因
+     */
+    fn locate(&self, start: u8, length: u8) -> Result<u8, BitfieldError> {
+        let width = T::BITS as usize;
+        if start as usize >= width {
+            return Err(BitfieldError::StartOutOfRange { start: start as usize, width });
+        }
+        let placement = Placement::resolve(start as usize, length as usize, self.bit_order, self.byte_order);
+        if placement.end() > width {
+            return Err(BitfieldError::LengthOverflow { start: start as usize, length: length as usize, width });
+        }
+        Ok(placement.offset(width) as u8)
+    }
+    /**
+     * @description: 查找与指定位范围重叠的第一个位域,别名位域不参与检查
+     * @param {&str} key    待插入的位域名字,仅用于生成错误信息
+     * @param {u8} offset   位域最低位在数据字中的位置
+     * @param {u8} length   位域长度
+     * @param {Option} except   不参与检查的位域名字,用于修改或替换已有位域
+     * @return {Result}     无重叠，无信息，有重叠时，返回Overlap错误
+     */
+    fn check_overlap(&self, key: &str, offset: u8, length: u8, except: Option<&str>) -> Result<(), BitfieldError> {
+        let mut conflicts: Vec<(&String, &BitfieldValue)> = self
+            .bitmap
+            .iter()
+            .filter(|(name, value)| Some(name.as_str()) != except && !value.alias && overlap(value.offset as usize, value.length as usize, offset as usize, length as usize).is_some())
+            .collect();
+        conflicts.sort_by_key(|(name, value)| (value.offset, *name));
+        match conflicts.first() {
+            Some((other, value)) => {
+                let (lo, hi) = overlap(value.offset as usize, value.length as usize, offset as usize, length as usize).unwrap_or_default();
+                Err(BitfieldError::Overlap { key: key.to_string(), other: other.to_string(), start: lo, end: hi })
+            }
+            None => Ok(()),
+        }
+    }
+    /**
+     * @description: 校验整个布局,报告所有互相重叠的非别名位域
+     * @param {*} self  不可变的自己
+     * @return {Result} 无冲突，无信息，有冲突时，按位置顺序返回每一对冲突,位置为数据字中的LSB-0位置
+     */
+    pub fn validate(&self) -> Result<(), Vec<BitfieldError>> {
+        let mut fields: Vec<(&String, &BitfieldValue)> = self.bitmap.iter().filter(|(_, value)| !value.alias).collect();
+        fields.sort_by_key(|(name, value)| (value.offset, *name));
+        let mut errors = Vec::new();
+        for (i, (key, first)) in fields.iter().enumerate() {
+            for (other, second) in &fields[i + 1..] {
+                if let Some((start, end)) = overlap(first.offset as usize, first.length as usize, second.offset as usize, second.length as usize) {
+                    errors.push(BitfieldError::Overlap { key: key.to_string(), other: other.to_string(), start, end });
+                }
+            }
+        }
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+    /**
+     * @description: 插入一个位域信息
+     * @param {*} mut self  可变的自己
+     * @param {String} key  位域名字
+     * @param {u8} start    位域开始地址
+     * @param {u8} length   位域长度
+     * @return {Result}     成功，无信息，失败时，返回失败原因
+     */
+    pub fn insert(&mut self, key: String, start: u8, length: u8) -> Result<(), BitfieldError> {
+        self.check_vacant(&key)?;
+        let offset = self.locate(start, length)?;
+        self.check_overlap(&key, offset, length, None)?;
+        let order = self.next_order();
+        self.bitmap.insert(key, BitfieldValue { start, offset, length, alias: false, signed: false, order, description: String::new(), scaling: None, enum_table: None, access: Access::ReadWrite });
+        Ok(())
+    }
+    /**
+     * @description: 插入一个有符号位域,按length位的二进制补码读写,与存储类型是否有符号无关
+     * @param {*} mut self  可变的自己
+     * @param {String} key  位域名字
+     * @param {u8} start    位域开始地址
+     * @param {u8} length   位域长度
+     * @return {Result}     成功，无信息，失败时，返回失败原因
+     */
+    pub fn insert_signed(&mut self, key: String, start: u8, length: u8) -> Result<(), BitfieldError> {
+        self.check_vacant(&key)?;
+        let offset = self.locate(start, length)?;
+        self.check_overlap(&key, offset, length, None)?;
+        let order = self.next_order();
+        self.bitmap.insert(key, BitfieldValue { start, offset, length, alias: false, signed: true, order, description: String::new(), scaling: None, enum_table: None, access: Access::ReadWrite });
+        Ok(())
+    }
+    /**
+     * @description: 插入一个别名位域,允许与其他位域重叠,例如用一个宽位域同时访问几个窄位域
+     * @param {*} mut self  可变的自己
+     * @param {String} key  位域名字
+     * @param {u8} start    位域开始地址
+     * @param {u8} length   位域长度
+     * @return {Result}     成功，无信息，失败时，返回失败原因
+     */
+    pub fn insert_alias(&mut self, key: String, start: u8, length: u8) -> Result<(), BitfieldError> {
+        self.check_vacant(&key)?;
+        let offset = self.locate(start, length)?;
+        let order = self.next_order();
+        self.bitmap.insert(key, BitfieldValue { start, offset, length, alias: true, signed: false, order, description: String::new(), scaling: None, enum_table: None, access: Access::ReadWrite });
+        Ok(())
+    }
+    /**
+     * @description: 插入或替换一个位域信息,已存在的同名位域被覆盖并保留其别名、符号属性、说明、换算信息、对照表和访问权限
+     * @param {*} mut self  可变的自己
+     * @param {String} key  位域名字
+     * @param {u8} start    位域开始地址
+     * @param {u8} length   位域长度
+     * @return {Result}     成功，返回被替换的旧位域(不存在时为None)，失败时，返回失败原因且布局保持不变
+     */
+    pub fn upsert(&mut self, key: String, start: u8, length: u8) -> Result<Option<BitfieldValue>, BitfieldError> {
+        let offset = self.locate(start, length)?;
+        let existing = self.bitmap.get(&key).cloned();
+        if !existing.as_ref().is_some_and(|value| value.alias) {
+            self.check_overlap(&key, offset, length, Some(&key))?;
+        }
+        let value = match existing {
+            Some(value) => BitfieldValue { start, offset, length, ..value },
+            None => {
+                let order = self.next_order();
+                BitfieldValue { start, offset, length, alias: false, signed: false, order, description: String::new(), scaling: None, enum_table: None, access: Access::ReadWrite }
+            }
+        };
+        Ok(self.bitmap.insert(key, value))
+    }
+    /**
+     * @description: 分配下一个插入序号
+     * @param {*} mut self  可变的自己
+     * @return {usize}  插入序号
+     */
+    fn next_order(&mut self) -> usize {
+        self.inserted += 1;
+        self.inserted - 1
+    }
+    /**
+     * @description: 按开始地址顺序遍历位域,开始地址相同时按名字排序
+     * @param {*} self  不可变的自己
+     * @return {Iterator}   (位域名字, 位域信息)
+     */
+    pub fn fields(&self) -> impl Iterator<Item = (&str, &BitfieldValue)> {
+        let mut fields: Vec<(&str, &BitfieldValue)> = self.bitmap.iter().map(|(name, value)| (name.as_str(), value)).collect();
+        fields.sort_by_key(|(name, value)| (value.start, *name));
+        fields.into_iter()
+    }
+    /**
+     * @description: 按插入顺序遍历位域,修改或替换位域时保留原来的位置
+     * @param {*} self  不可变的自己
+     * @return {Iterator}   (位域名字, 位域信息)
+     */
+    pub fn fields_by_insertion(&self) -> impl Iterator<Item = (&str, &BitfieldValue)> {
+        let mut fields: Vec<(&str, &BitfieldValue)> = self.bitmap.iter().map(|(name, value)| (name.as_str(), value)).collect();
+        fields.sort_by_key(|(_, value)| value.order);
+        fields.into_iter()
+    }
+    /**
+     * @description: 遍历没有被任何位域(包括别名位域)占用的位范围
+     * @param {*} self  不可变的自己
+     * @return {Iterator}   (最低位在数据字中的位置, 长度),从低位到高位
+     */
+    pub fn gaps(&self) -> impl Iterator<Item = (u8, u8)> {
+        let used = self.bitmap.values().fold(0u128, |used, value| used | T::mask(value.length) << value.offset);
+        let width = T::BITS as usize;
+        let mut gaps = Vec::new();
+        let mut bit = 0;
+        while bit < width {
+            if used >> bit & 1 == 1 {
+                bit += 1;
+                continue;
+            }
+            let start = bit;
+            while bit < width && used >> bit & 1 == 0 {
+                bit += 1;
+            }
+            gaps.push((start as u8, (bit - start) as u8));
+        }
+        gaps.into_iter()
+    }
+    /**
+     * @description: 检查位域名字是否未被占用
+     * @param {&str} key   位域名字
+     * @return {Result}     未占用，无信息，已占用时，返回DuplicateKey错误
+     */
+    fn check_vacant(&self, key: &str) -> Result<(), BitfieldError> {
+        if self.bitmap.contains_key(key) {
+            return Err(BitfieldError::DuplicateKey { key: key.to_string() });
+        }
+        Ok(())
+    }
+    /**
+     * @description:    修改一个位域信息
+     * @param {*} mut self  可变的自己
+     * @param {String} oldkey   旧位域名字
+     * @param {String} newkey   新位域名字
+     * @param {u8} start        位域开始地址
+     * @param {u8} length       位域长度
+     * @return {Result} 成功，无信息，失败时，返回失败原因且原位域保持不变
+     */
+    pub fn modify(&mut self, oldkey: String, newkey: String, start: u8, length: u8) -> Result<(), BitfieldError> {
+        let alias = self.get_param(&oldkey)?.alias;
+        if newkey != oldkey {
+            self.check_vacant(&newkey)?;
+        }
+        let offset = self.locate(start, length)?;
+        if !alias {
+            self.check_overlap(&newkey, offset, length, Some(&oldkey))?;
+        }
+        if let Some(value) = self.bitmap.remove(&oldkey) {
+            self.bitmap.insert(newkey, BitfieldValue { start, offset, length, ..value });
+        }
+        Ok(())
+    }
+    /**
+     * @description: 设置位域的说明文字,修改或替换位域时保留说明
+     * @param {*} mut self  可变的自己
+     * @param {&str} key   位域名字
+     * @param {String} description  说明文字
+     * @return {Result} 成功，无信息，失败时，返回失败原因
+     */
+    pub fn set_description(&mut self, key: &str, description: String) -> Result<(), BitfieldError> {
+        match self.bitmap.get_mut(key) {
+            Some(value) => {
+                value.description = description;
+                Ok(())
+            }
+            None => Err(BitfieldError::KeyNotFound { key: key.to_string() }),
+        }
+    }
+    /**
+     * @description: 设置或清除位域的物理量换算信息,修改或替换位域时保留
+     * @param {*} mut self  可变的自己
+     * @param {&str} key   位域名字
+     * @param {Option} scaling  换算信息,None表示清除,比例系数必须是非0的有限数,偏移量必须是有限数
+     * @return {Result} 成功，无信息，失败时，返回失败原因
+     */
+    pub fn set_scaling(&mut self, key: &str, scaling: Option<Scaling>) -> Result<(), BitfieldError> {
+        if let Some(scaling) = &scaling {
+            let (factor, offset) = (scaling.factor(), scaling.offset());
+            if factor == 0.0 || !factor.is_finite() || !offset.is_finite() {
+                return Err(BitfieldError::InvalidScaling { factor: factor.to_bits(), offset: offset.to_bits() });
+            }
+        }
+        match self.bitmap.get_mut(key) {
+            Some(value) => {
+                value.scaling = scaling;
+                Ok(())
+            }
+            None => Err(BitfieldError::KeyNotFound { key: key.to_string() }),
+        }
+    }
+    /**
+     * @description: 设置或清除位域的值到名字的对照表,修改或替换位域时保留
+     * @param {*} mut self  可变的自己
+     * @param {&str} key   位域名字
+     * @param {Option} table  对照表,None表示清除
+     * @return {Result} 成功，无信息，失败时，返回失败原因
+     */
+    pub fn set_enum_table(&mut self, key: &str, table: Option<EnumTable>) -> Result<(), BitfieldError> {
+        match self.bitmap.get_mut(key) {
+            Some(value) => {
+                value.enum_table = table;
+                Ok(())
+            }
+            None => Err(BitfieldError::KeyNotFound { key: key.to_string() }),
+        }
+    }
+    /**
+     * @description: 设置位域的访问权限,修改或替换位域时保留
+     * @param {*} mut self  可变的自己
+     * @param {&str} key   位域名字
+     * @param {Access} access  访问权限
+     * @return {Result} 成功，无信息，失败时，返回失败原因
+     */
+    pub fn set_access(&mut self, key: &str, access: Access) -> Result<(), BitfieldError> {
+        match self.bitmap.get_mut(key) {
+            Some(value) => {
+                value.access = access;
+                Ok(())
+            }
+            None => Err(BitfieldError::KeyNotFound { key: key.to_string() }),
+        }
+    }
+    /**
+     * @description: 获取位域设置信息
+     * @param {*} self  不可变的自己
+     * @param {&str} key   位域名字
+     * @return {Result} 成功时返回BitfieldValue结构体,失败时，返回失败原因
+     */
+    pub fn get_param(&self, key: &str) -> Result<&BitfieldValue, BitfieldError> {
+        match self.bitmap.get(key) {
+            Some(value) => Ok(value),
+            None => Err(BitfieldError::KeyNotFound { key: key.to_string() }),
+        }
+    }
+    /**
+     * @description: 删除位域信息
+     * @param {*}   可变的自己
+     * @param {&str} key   位域名字
+     * @return {Result} 成功，无信息，失败时，返回失败原因
+     */
+    pub fn del_param(&mut self, key: &str) -> Result<(), BitfieldError> {
+        match self.bitmap.remove(key) {
+            Some(_) => Ok(()),
+            None => Err(BitfieldError::KeyNotFound { key: key.to_string() }),
+        }
+    }
+    /**
+     * @description: 根据位域信息，从数据字中获取对应的值
+     * @param {*} self  不可变的自己
+     * @param {T} bitfielddata 需要做位域处理的数据
+     * @param {&str} key   位域名字
+     * @return {Result} 成功，返回位域对应的数据,有符号位域按存储类型的位宽做符号扩展,失败时，返回失败原因
+     */
+    pub fn get_value(&self, bitfielddata: T, key: &str) -> Result<T, BitfieldError> {
+        Ok(self.field(key)?.get(bitfielddata))
+    }
+    /**
+     * @description: 根据位域信息，把值写入数据字
+     * @param {*} self  不可变的自己
+     * @param {T} bitfielddata 需要做位域处理的数据
+     * @param {&str} key    位域名字
+     * @param {T} value     要改变位域的对应的值,有符号位域按存储类型位宽的补码解释
+     * @return {Result}     成功，返回写入后的数据字，失败，返回失败原因
+     */
+    pub fn set_value(&self, bitfielddata: T, key: &str, value: T) -> Result<T, BitfieldError> {
+        self.field(key)?.set(bitfielddata, value)
+    }
+    /**
+     * @description: 解析位域名字,返回缓存了移位量和掩码的句柄,供高频读写使用
+     * @param {*} self  不可变的自己
+     * @param {&str} key   位域名字
+     * @return {Result} 成功，返回位域句柄,失败时，返回失败原因
+     */
+    pub fn field(&self, key: &str) -> Result<FieldHandle<T>, BitfieldError> {
+        Ok(FieldHandle::new(self.get_param(key)?))
+    }
+    /**
+     * @description: 获取位域个数
+     * @param {*} self  不可变的自己
+     * @return {usize}  位域个数
+     */
+    pub fn len(&self) -> usize {
+        self.bitmap.len()
+    }
+    /**
+     * @description: 布局中是否没有任何位域
+     * @param {*} self  不可变的自己
+     * @return {bool}   没有位域时返回true
+     */
+    pub fn is_empty(&self) -> bool {
+        self.bitmap.is_empty()
+    }
+    /**
+     * @description: 获取位数
+     * @param {*} self  不可变的自己
+     * @return {usize}  返回对应的位数
+     */
+    pub fn get_totalbit(&self) -> usize {
+        T::BITS as usize
+    }
+}
+
+impl<T: BitStorage> Default for Layout<T> {
+    fn default() -> Self {
+        Layout::new()
+    }
+}
+
+/**
+ * 数据视图的读写,所有位域信息都来自引用的布局
+ */
+impl<'a, T: BitStorage> BitfieldRef<'a, T> {
+    /**
+     * @description: 获取引用的布局
+     * @param {*} self  不可变的自己
+     * @return {&Layout}    布局
+     */
+    pub fn layout(&self) -> &'a Layout<T> {
+        self.layout
+    }
+    /**
+     * @description:    设置需要做位域处理的数据
+     * @param {*} mut self  可变的自己
+     * @param {T} bitfielddata 需要做位域处理的数据
+     * @return {无} 无
+     */
+    pub fn set_data(&mut self, bitfielddata: T) {
+        self.bitfielddata = bitfielddata;
+    }
+    /**
+     * @description: 获取做位域处理的数据
+     * @param {*} self  不可变的自己
+     * @return {T} 原始数据
+     */
+    pub fn get_data(&self) -> T {
+        self.bitfielddata
+    }
+    /**
+     * @description: 根据位域信息，获取对应的值
+     * @param {*} self  不可变的自己
+     * @param {&str} key   位域名字
+     * @return {Result} 成功，返回位域对应的数据,失败时，返回失败原因
+     */
+    pub fn get_value(&self, key: &str) -> Result<T, BitfieldError> {
+        self.layout.get_value(self.bitfielddata, key)
+    }
+    /**
+     * @description: 根据位域信息，设置对应的值
+     * @param {*}    可变的自己
+     * @param {&str} key    位域名字
+     * @param {T} value     要改变位域的对应的值
+     * @return {Result}     成功，无信息，失败，返回失败原因
+     */
+    pub fn set_value(&mut self, key: &str, value: T) -> Result<(), BitfieldError> {
+        self.bitfielddata = self.layout.set_value(self.bitfielddata, key, value)?;
+        Ok(())
+    }
+    /**
+     * @description: 通过句柄获取位域的值,不查找哈希表
+     * @param {*} self  不可变的自己
+     * @param {FieldHandle} handle  位域句柄
+     * @return {T}  位域的值
+     */
+    #[inline]
+    pub fn get(&self, handle: FieldHandle<T>) -> T {
+        handle.get(self.bitfielddata)
+    }
+    /**
+     * @description: 按开始地址顺序遍历位域及其当前值
+     * @param {*} self  不可变的自己
+     * @return {Iterator}   (位域名字, 开始地址, 长度, 值)
+     */
+    pub fn iter(&self) -> impl Iterator<Item = (&'a str, u8, u8, T)> {
+        let bitfielddata = self.bitfielddata;
+        self.layout
+            .fields()
+            .map(move |(name, value)| (name, value.start, value.length, FieldHandle::new(value).get(bitfielddata)))
+    }
+    /**
+     * @description: 通过句柄设置位域的值,不查找哈希表
+     * @param {*}    可变的自己
+     * @param {FieldHandle} handle  位域句柄
+     * @param {T} value     要改变位域的对应的值
+     * @return {Result}     成功，无信息，失败，返回失败原因
+     */
+    #[inline]
+    pub fn set(&mut self, handle: FieldHandle<T>, value: T) -> Result<(), BitfieldError> {
+        self.bitfielddata = handle.set(self.bitfielddata, value)?;
+        Ok(())
+    }
+}
+
+/**
+ * @description: 计算两个位范围的重叠部分
+ * @param {usize} a_start   第一个位范围开始地址
+ * @param {usize} a_length  第一个位范围长度
+ * @param {usize} b_start   第二个位范围开始地址
+ * @param {usize} b_length  第二个位范围长度
+ * @return {Option}     有重叠时，返回重叠的[开始,结束)位置
+ */
+pub(crate) fn overlap(a_start: usize, a_length: usize, b_start: usize, b_length: usize) -> Option<(usize, usize)> {
+    let lo = a_start.max(b_start);
+    let hi = (a_start + a_length).min(b_start + b_length);
+    if lo < hi {
+        Some((lo, hi))
+    } else {
+        None
+    }
+}
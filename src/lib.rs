@@ -0,0 +1,41 @@
+pub mod access;
+pub mod bank;
+pub mod bitfield;
+pub mod bus;
+pub mod bytes;
+pub mod dbc;
+pub mod enumeration;
+pub mod error;
+pub mod flags;
+pub mod format;
+pub mod handle;
+#[cfg(feature = "json")]
+pub mod json;
+pub mod layout;
+#[doc(hidden)]
+pub mod macros;
+pub mod mmio;
+pub mod order;
+pub mod render;
+pub mod scaling;
+pub mod sim;
+pub mod svd;
+mod xml;
+
+pub use access::Access;
+pub use bank::RegisterBank;
+pub use bitfield::{BitStorage, Bitfield};
+pub use bus::{MockBus, RegisterBus, Transaction};
+pub use bytes::BitfieldBytes;
+pub use dbc::DbcDatabase;
+pub use enumeration::{EnumTable, EnumValue, FieldEnum};
+pub use error::BitfieldError;
+pub use format::{FieldDef, LayoutDef};
+pub use handle::FieldHandle;
+pub use layout::{BitfieldRef, BitfieldValue, Layout};
+pub use mmio::MmioRegister;
+pub use order::{BitOrder, ByteOrder};
+pub use render::Radix;
+pub use scaling::Scaling;
+pub use sim::SimDevice;
+pub use svd::SvdDevice;
@@ -0,0 +1,163 @@
+/**
+ * 编译期声明位域布局
+ * 在一个整数存储类型上生成新类型结构体,为每个位域生成读取和写入方法
+ * 位域超出存储位宽或互相重叠时,由常量断言在编译期报错
+ *
+ * ```
+ * use bitflags::bitfield_layout;
+ *
+ * bitfield_layout! {
+ *     pub struct Control(u32) {
+ *         mode, set_mode: 0, 3;
+ *         ready, set_ready: 3, 1;
+ *         channel, set_channel: 8, 8;
+ *     }
+ * }
+ *
+ * # fn main() -> Result<(), bitflags::BitfieldError> {
+ * let mut ctrl = Control::new(0);
+ * ctrl.set_mode(5)?;
+ * ctrl.set_channel(0x42)?;
+ * assert_eq!(ctrl.mode(), 5);
+ * assert_eq!(ctrl.get_data(), 0x4205);
+ * assert!(ctrl.set_ready(2).is_err());
+ * # Ok(())
+ * # }
+ * ```
+ *
+ * 重叠的位域无法通过编译:
+ *
+ * ```compile_fail
+ * bitflags::bitfield_layout! {
+ *     struct Overlapping(u8) {
+ *         low, set_low: 0, 4;
+ *         mid, set_mid: 2, 4;
+ *     }
+ * }
+ * ```
+ *
+ * 超出存储位宽的位域无法通过编译:
+ *
+ * ```compile_fail
+ * bitflags::bitfield_layout! {
+ *     struct TooWide(u8) {
+ *         high, set_high: 4, 5;
+ *     }
+ * }
+ * ```
+ */
+#[macro_export]
+macro_rules! bitfield_layout {
+    (
+        $(#[$meta:meta])*
+        $vis:vis struct $name:ident($t:ty) {
+            $($field:ident, $setter:ident : $start:expr, $length:expr);* $(;)?
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+        $vis struct $name($t);
+
+        const _: () = {
+            const FIELDS: &[(u32, u32)] = &[$(($start as u32, $length as u32)),*];
+            $(
+                assert!(
+                    $crate::macros::field_fits(<$t as $crate::BitStorage>::BITS as u32, $start as u32, $length as u32),
+                    concat!("bitfield_layout: field `", stringify!($field), "` does not fit in ", stringify!($t)),
+                );
+                assert!(
+                    $crate::macros::field_disjoint(FIELDS, $start as u32, $length as u32),
+                    concat!("bitfield_layout: field `", stringify!($field), "` overlaps another field"),
+                );
+            )*
+        };
+
+        #[allow(dead_code)]
+        impl $name {
+            /**
+             * @description: 由原始数据创建
+             * @param {T} bitfielddata 需要做位域处理的数据
+             * @return {Self}   结构体
+             */
+            pub const fn new(bitfielddata: $t) -> Self {
+                $name(bitfielddata)
+            }
+            /**
+             * @description: 获取做位域处理的数据
+             * @return {T} 原始数据
+             */
+            pub const fn get_data(&self) -> $t {
+                self.0
+            }
+            /**
+             * @description: 设置需要做位域处理的数据
+             * @param {T} bitfielddata 需要做位域处理的数据
+             */
+            pub fn set_data(&mut self, bitfielddata: $t) {
+                self.0 = bitfielddata;
+            }
+            /**
+             * @description: 生成等价的运行期布局,便于与Layout/Bitfield接口配合使用
+             * @return {Layout} 布局
+             */
+            pub fn layout() -> $crate::Layout<$t> {
+                let mut layout = $crate::Layout::new();
+                $(
+                    layout
+                        .insert(stringify!($field).to_string(), $start as u8, $length as u8)
+                        .expect("bitfield_layout: fields are checked at compile time");
+                )*
+                layout
+            }
+            $(
+                pub fn $field(&self) -> $t {
+                    <$t as $crate::BitStorage>::extract(self.0, $start as u8, $length as u8)
+                }
+                pub fn $setter(&mut self, value: $t) -> Result<(), $crate::BitfieldError> {
+                    let max = <$t as $crate::BitStorage>::mask($length as u8);
+                    let raw = <$t as $crate::BitStorage>::to_raw(value);
+                    if raw > max {
+                        return Err($crate::BitfieldError::ValueOutOfRange { value: raw, max });
+                    }
+                    self.0 = <$t as $crate::BitStorage>::deposit(self.0, $start as u8, $length as u8, value);
+                    Ok(())
+                }
+            )*
+        }
+    };
+}
+
+/**
+ * @description: 编译期检查位域是否落在存储位宽之内,供bitfield_layout!使用
+ * @param {u32} bits    存储类型位数
+ * @param {u32} start   位域开始地址
+ * @param {u32} length  位域长度
+ * @return {bool}   合法时返回true
+ */
+#[doc(hidden)]
+pub const fn field_fits(bits: u32, start: u32, length: u32) -> bool {
+    start < bits && start + length <= bits
+}
+
+/**
+ * @description: 编译期检查位域是否只与自己重叠,供bitfield_layout!使用
+ * @param {&[(u32, u32)]} fields    全部位域的(开始地址,长度)
+ * @param {u32} start   位域开始地址
+ * @param {u32} length  位域长度
+ * @return {bool}   没有与其他位域重叠时返回true
+ */
+#[doc(hidden)]
+pub const fn field_disjoint(fields: &[(u32, u32)], start: u32, length: u32) -> bool {
+    let mut hits = 0;
+    let mut i = 0;
+    while i < fields.len() {
+        let (other_start, other_length) = fields[i];
+        let lo = if start > other_start { start } else { other_start };
+        let hi = if start + length < other_start + other_length { start + length } else { other_start + other_length };
+        if lo < hi {
+            hits += 1;
+        }
+        i += 1;
+    }
+    hits <= 1
+}
@@ -1,47 +1,161 @@
-mod bitfield;
-use bitfield::Bitfield;
-fn main() {
-    let req = Bitfield::create_i32(String::from("mode"),0xff, 0, 8);
-    match req {
-        Ok(mut req) => {
-            match req.modify(String::from("mode"), String::from("mode1"), 0, 1) {
-                Ok(()) => println!("修改mode为mode1成功"),
-                Err(str) => println!("{}",str),
-            }
-            match req.insert(String::from("test"), 2, 3){
-                Ok(()) =>{},
-                Err(str) => println!("{}",str),
-            }
-            match req.get_param("test") {
-                Ok(str) => {
-                    println!("test的值为:\n{:#?}",str)
-                },
-                Err(err) => println!("{}",err),
-            }
-            
-            match req.get_value("mode1") {
-                Ok(str) => println!("mode1:{}",str),
-                Err(err) => {println!("{}",err)} ,
-            }
-            let str =req.get_value("test");
-            match str {
-                Ok(str) => println!("test:{}",str),
-                Err(err) => {println!("{}",err)} ,
+use std::env;
+use std::fs;
+use std::process::ExitCode;
+
+use bitflags::render::format_value;
+use bitflags::{BitStorage, Bitfield, BitfieldError, Layout, LayoutDef, Radix};
+
+const USAGE: &str = "用法: bitflags <布局文件> <命令> [参数...]
+
+命令:
+  decode <hex>                  解码数据字,打印每个位域的值
+  encode <位域=值>...           把位域的值打包成数据字,未给出的位域为0
+  diff <hex> <hex>              列出两个数据字中值不同的位域
+  render [hex] [--radix 进制]   绘制位域框图,进制为bin/oct/dec/hex,默认hex
+  format                        按规范格式重新输出布局文件
+
+布局文件每行一项,#之后为注释:
+  width <8|16|32|64|128>        存储位宽
+  order <lsb0|msb0> <little|big>    位编号方式和字节序,可省略
+  <名字> <开始地址> <长度> [signed|alias] [\"说明\"]";
+
+/**
+ * @description: 读取布局文件
+ * @param {&str} path   文件路径
+ * @return {Result} 成功，返回布局定义，失败时，返回失败原因
+ */
+fn load_layout(path: &str) -> Result<LayoutDef, String> {
+    let text = fs::read_to_string(path).map_err(|err| format!("{}: {}", path, err))?;
+    LayoutDef::parse(&text).map_err(|err| match err {
+        BitfieldError::Syntax { line, column, message } => format!("{}:{}:{}: {}", path, line, column, message),
+        err => format!("{}: {}", path, err),
+    })
+}
+
+/**
+ * @description: 解析数据字,允许0x前缀
+ * @param {&str} text   十六进制文本
+ * @return {Result} 成功，返回数据字，失败时，返回失败原因
+ */
+fn parse_data<T: BitStorage>(text: &str) -> Result<T, String> {
+    let digits = text.strip_prefix("0x").or_else(|| text.strip_prefix("0X")).unwrap_or(text).replace('_', "");
+    let raw = u128::from_str_radix(&digits, 16).map_err(|_| format!("无效的十六进制数: {}", text))?;
+    if raw > T::mask(T::BITS) {
+        return Err(format!("{} 超出{}位", text, T::BITS));
+    }
+    Ok(T::from_raw(raw))
+}
+
+/**
+ * @description: 解析位域的值,支持十进制(可为负数)以及0x、0o、0b前缀
+ * @param {&str} text   数值文本
+ * @return {Result} 成功，返回按存储位宽补码表示的值，失败时，返回失败原因
+ */
+fn parse_value<T: BitStorage>(text: &str) -> Result<T, String> {
+    let error = || format!("无效的数值: {}", text);
+    let (negative, digits) = match text.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, text),
+    };
+    let digits = digits.replace('_', "");
+    let magnitude = if let Some(hex) = digits.strip_prefix("0x") {
+        u128::from_str_radix(hex, 16)
+    } else if let Some(oct) = digits.strip_prefix("0o") {
+        u128::from_str_radix(oct, 8)
+    } else if let Some(bin) = digits.strip_prefix("0b") {
+        u128::from_str_radix(bin, 2)
+    } else {
+        digits.parse::<u128>()
+    }
+    .map_err(|_| error())?;
+    let limit = if negative { 1u128 << (T::BITS - 1) } else { T::mask(T::BITS) };
+    if magnitude > limit {
+        return Err(format!("{} 超出{}位", text, T::BITS));
+    }
+    Ok(T::from_raw(if negative { magnitude.wrapping_neg() } else { magnitude }))
+}
+
+/**
+ * @description: 解析进制参数
+ * @param {&str} text   进制名字
+ * @return {Result} 成功，返回进制，失败时，返回失败原因
+ */
+fn parse_radix(text: &str) -> Result<Radix, String> {
+    match text {
+        "bin" => Ok(Radix::Binary),
+        "oct" => Ok(Radix::Octal),
+        "dec" => Ok(Radix::Decimal),
+        "hex" => Ok(Radix::Hex),
+        _ => Err(format!("未知的进制: {}", text)),
+    }
+}
+
+/**
+ * @description: 按存储类型构建布局并执行命令
+ * @param {&LayoutDef} def   布局定义
+ * @param {&[String]} args  命令及其参数
+ * @return {Result} 成功，无信息，失败时，返回失败原因
+ */
+fn run<T: BitStorage>(def: &LayoutDef, args: &[String]) -> Result<(), String> {
+    let layout: Layout<T> = def.build().map_err(|err| err.to_string())?;
+    let (command, rest) = args.split_first().ok_or(USAGE)?;
+    match (command.as_str(), rest) {
+        ("decode", [data]) => {
+            print!("{}", Bitfield::from_layout(layout, parse_data::<T>(data)?));
+        }
+        ("encode", assignments) if !assignments.is_empty() => {
+            let mut bitfield = Bitfield::from_layout(layout, T::from_raw(0));
+            for assignment in assignments {
+                let (key, value) = assignment.split_once('=').ok_or_else(|| format!("应为 位域=值: {}", assignment))?;
+                bitfield.set_value(key, parse_value::<T>(value)?).map_err(|err| err.to_string())?;
             }
-            match req.set_value("mode1", 0) {
-                Ok(()) =>{},
-                Err(err) => {
-                    println!("{}",err)
+            println!("0x{:0digits$x}", bitfield.get_data().to_raw(), digits = T::BITS as usize / 4);
+        }
+        ("diff", [old, new]) => {
+            let (old, new) = (parse_data::<T>(old)?, parse_data::<T>(new)?);
+            for (name, value) in layout.fields() {
+                let (before, after) = (format_value(value, old, Radix::Decimal), format_value(value, new, Radix::Decimal));
+                if before != after {
+                    println!("{}: {} -> {}", name, before, after);
                 }
             }
-            match req.get_value("mode1") {
-                Ok(str) => println!("mode1:{}",str),
-                Err(err) => {println!("{}",err)} ,
+        }
+        ("render", rest) => {
+            let mut data = T::from_raw(0);
+            let mut radix = Radix::Hex;
+            let mut rest = rest.iter();
+            while let Some(arg) = rest.next() {
+                if arg == "--radix" {
+                    radix = parse_radix(rest.next().ok_or("--radix 缺少参数")?)?;
+                } else {
+                    data = parse_data::<T>(arg)?;
+                }
             }
-            let format = format!("0b{:0width$b}",req.get_data(), width = req.get_totalbit());
-            println!("{}",format);
+            print!("{}", layout.value(data).render(radix));
+        }
+        ("format", []) => print!("{}", def),
+        _ => return Err(USAGE.to_string()),
+    }
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let result = match args.split_first() {
+        Some((path, command)) if !command.is_empty() => load_layout(path).and_then(|def| match def.width() {
+            8 => run::<u8>(&def, command),
+            16 => run::<u16>(&def, command),
+            32 => run::<u32>(&def, command),
+            64 => run::<u64>(&def, command),
+            _ => run::<u128>(&def, command),
+        }),
+        _ => Err(USAGE.to_string()),
+    };
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("{}", err);
+            ExitCode::FAILURE
         }
-        Err(err) => println!("{}",err),
     }
-    
-}
\ No newline at end of file
+}
@@ -0,0 +1,139 @@
+use std::marker::PhantomData;
+use std::ptr;
+
+use crate::bitfield::BitStorage;
+use crate::error::BitfieldError;
+use crate::handle::FieldHandle;
+use crate::layout::{BitfieldRef, Layout};
+
+/**
+ * 内存映射寄存器的视图,不缓存寄存器的值,每次读写都通过read_volatile/write_volatile访问寄存器
+ * 位域信息来自引用的布局,读位域做一次读,写位域做一次读和一次写
+ */
+#[derive(Debug)]
+pub struct MmioRegister<'a, T> {
+    register: *mut T,
+    layout: &'a Layout<T>,
+    marker: PhantomData<&'a mut T>,
+}
+
+impl<'a, T: BitStorage> MmioRegister<'a, T> {
+    /**
+     * @description: 由可变引用创建视图,用于普通内存或测试
+     * @param {&mut T} register    寄存器
+     * @param {&Layout} layout  寄存器的位域布局
+     * @return {MmioRegister}   视图
+     */
+    pub fn new(register: &'a mut T, layout: &'a Layout<T>) -> MmioRegister<'a, T> {
+        MmioRegister { register, layout, marker: PhantomData }
+    }
+    /**
+     * @description: 由寄存器地址创建视图
+     * # Safety
+     * register必须非空、按T对齐,并且在视图存在期间可以读写,期间不能通过其他途径产生对它的引用
+     * @param {*mut T} register    寄存器地址
+     * @param {&Layout} layout  寄存器的位域布局
+     * @return {MmioRegister}   视图
+     */
+    pub unsafe fn from_ptr(register: *mut T, layout: &'a Layout<T>) -> MmioRegister<'a, T> {
+        MmioRegister { register, layout, marker: PhantomData }
+    }
+    /**
+     * @description: 获取引用的布局
+     * @param {*} self  不可变的自己
+     * @return {&Layout}    布局
+     */
+    pub fn layout(&self) -> &'a Layout<T> {
+        self.layout
+    }
+    /**
+     * @description: 读取整个寄存器
+     * @param {*} self  不可变的自己
+     * @return {T}  寄存器的值
+     */
+    #[inline]
+    pub fn read(&self) -> T {
+        //构造时保证了指针可以读写
+        unsafe { ptr::read_volatile(self.register) }
+    }
+    /**
+     * @description: 写入整个寄存器
+     * @param {*} mut self  可变的自己
+     * @param {T} value     写入的值
+     * @return {无} 无
+     */
+    #[inline]
+    pub fn write(&mut self, value: T) {
+        //构造时保证了指针可以读写
+        unsafe { ptr::write_volatile(self.register, value) }
+    }
+    /**
+     * @description: 读取寄存器一次,返回该时刻的数据视图
+     * @param {*} self  不可变的自己
+     * @return {BitfieldRef}    数据视图
+     */
+    pub fn snapshot(&self) -> BitfieldRef<'a, T> {
+        self.layout.value(self.read())
+    }
+    /**
+     * @description: 根据位域信息，读取寄存器并获取对应的值
+     * @param {*} self  不可变的自己
+     * @param {&str} key   位域名字
+     * @return {Result} 成功，返回位域对应的数据,失败时，返回失败原因
+     */
+    pub fn get_value(&self, key: &str) -> Result<T, BitfieldError> {
+        let handle = self.layout.field(key)?;
+        Ok(self.get(handle))
+    }
+    /**
+     * @description: 根据位域信息，读出寄存器、设置对应的值后写回
+     * @param {*} mut self  可变的自己
+     * @param {&str} key    位域名字
+     * @param {T} value     要改变位域的对应的值
+     * @return {Result}     成功，无信息，失败，返回失败原因且不访问寄存器
+     */
+    pub fn set_value(&mut self, key: &str, value: T) -> Result<(), BitfieldError> {
+        let handle = self.layout.field(key)?;
+        self.set(handle, value)
+    }
+    /**
+     * @description: 通过句柄读取寄存器并获取位域的值,不查找哈希表
+     * @param {*} self  不可变的自己
+     * @param {FieldHandle} handle  位域句柄
+     * @return {T}  位域的值
+     */
+    #[inline]
+    pub fn get(&self, handle: FieldHandle<T>) -> T {
+        handle.get(self.read())
+    }
+    /**
+     * @description: 通过句柄读出寄存器、设置位域的值后写回,不查找哈希表
+     * @param {*} mut self  可变的自己
+     * @param {FieldHandle} handle  位域句柄
+     * @param {T} value     要改变位域的对应的值
+     * @return {Result}     成功，无信息，失败，返回失败原因且不访问寄存器
+     */
+    #[inline]
+    pub fn set(&mut self, handle: FieldHandle<T>, value: T) -> Result<(), BitfieldError> {
+        //先校验再读,值超出范围时不产生读操作的副作用
+        handle.check(value)?;
+        let bitfielddata = handle.set(self.read(), value)?;
+        self.write(bitfielddata);
+        Ok(())
+    }
+    /**
+     * @description: 读出寄存器一次,在闭包中修改任意多个位域,闭包成功后写回一次
+     * @param {*} mut self  可变的自己
+     * @param {F} f     修改数据视图的闭包
+     * @return {Result}     成功，无信息，闭包失败时，返回失败原因且不写回
+     */
+    pub fn modify<F>(&mut self, f: F) -> Result<(), BitfieldError>
+    where
+        F: FnOnce(&mut BitfieldRef<'a, T>) -> Result<(), BitfieldError>,
+    {
+        let mut view = self.snapshot();
+        f(&mut view)?;
+        self.write(view.get_data());
+        Ok(())
+    }
+}
@@ -0,0 +1,160 @@
+/**
+ * 位编号方式,决定位域开始地址指向字节中的哪一位
+ * Lsb0: 第n位是第n/8个字节的第n%8位(最低位为0)
+ * Msb0: 第n位是第n/8个字节的第7-n%8位(最高位为0),与RFC报文图一致
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BitOrder {
+    #[default]
+    Lsb0,
+    Msb0,
+}
+
+/**
+ * 字节序,决定多字节位域的位如何排列
+ * LittleEndian: 开始地址是位域的最低位,越往后的位越高,跨字节时进入下一个字节的低位
+ * BigEndian: 开始地址是位域的最高位,越往后的位越低,跨字节时进入下一个字节的高位
+ * 对整数存储,LittleEndian对应to_le_bytes的字节映像,BigEndian对应to_be_bytes的字节映像
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ByteOrder {
+    #[default]
+    LittleEndian,
+    BigEndian,
+}
+
+/**
+ * 位域在字节映像中的实际位置
+ * 小端时anchor是最低位在LSB-0线性编号中的位置,大端时anchor是最高位在MSB-0线性编号中的位置
+ * 两种情况下位域都占据[anchor, anchor + length)
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Placement {
+    anchor: usize,
+    length: usize,
+    byte_order: ByteOrder,
+}
+
+/**
+ * 位域在一个字节中的片段:字节序号,字节内右移位数,位数,在位域值中的右移位数
+ */
+struct Chunk {
+    byte: usize,
+    shift: usize,
+    take: usize,
+    value_shift: usize,
+}
+
+impl Placement {
+    /**
+     * @description: 按位编号方式和字节序解析位域的实际位置
+     * @param {usize} start    位域开始地址
+     * @param {usize} length   位域长度
+     * @param {BitOrder} bit_order  位编号方式
+     * @param {ByteOrder} byte_order    字节序
+     * @return {Placement}  实际位置
+     */
+    pub(crate) fn resolve(start: usize, length: usize, bit_order: BitOrder, byte_order: ByteOrder) -> Placement {
+        let byte = start / 8;
+        let bit = match bit_order {
+            BitOrder::Lsb0 => start % 8,
+            BitOrder::Msb0 => 7 - start % 8,
+        };
+        let anchor = match byte_order {
+            ByteOrder::LittleEndian => byte * 8 + bit,
+            ByteOrder::BigEndian => byte * 8 + 7 - bit,
+        };
+        Placement { anchor, length, byte_order }
+    }
+    /**
+     * @description: 位域在其线性编号中的结束位置(不含),用于检查是否越界
+     * @param {*} self  不可变的自己
+     * @return {usize}  结束位置
+     */
+    pub(crate) fn end(&self) -> usize {
+        self.anchor + self.length
+    }
+    /**
+     * @description: 把位域映射到宽度为width位的整数上,返回最低位的位置
+     * @param {*} self  不可变的自己
+     * @param {usize} width    整数位宽,调用前需保证end() <= width
+     * @return {usize}  最低位在整数中的位置
+     */
+    pub(crate) fn offset(&self, width: usize) -> usize {
+        match self.byte_order {
+            ByteOrder::LittleEndian => self.anchor,
+            ByteOrder::BigEndian => width - self.end(),
+        }
+    }
+    /**
+     * @description: 按字节拆分位域
+     * @param {*} self  不可变的自己
+     * @return {Vec<Chunk>}  每个字节中的片段
+     */
+    fn chunks(&self) -> Vec<Chunk> {
+        let mut chunks = Vec::new();
+        let mut done = 0;
+        while done < self.length {
+            let pos = self.anchor + done;
+            let take = (8 - pos % 8).min(self.length - done);
+            let chunk = match self.byte_order {
+                ByteOrder::LittleEndian => Chunk { byte: pos / 8, shift: pos % 8, take, value_shift: done },
+                ByteOrder::BigEndian => Chunk {
+                    byte: pos / 8,
+                    shift: 8 - pos % 8 - take,
+                    take,
+                    value_shift: self.length - done - take,
+                },
+            };
+            chunks.push(chunk);
+            done += take;
+        }
+        chunks
+    }
+    /**
+     * @description: 从字节缓冲区读取位域,结果右对齐
+     * @param {*} self  不可变的自己
+     * @param {&[u8]} buffer    字节缓冲区,调用前需保证end()不超过缓冲区位数
+     * @return {u128}   位域的值
+     */
+    pub(crate) fn read(&self, buffer: &[u8]) -> u128 {
+        self.chunks().iter().fold(0u128, |value, chunk| {
+            let bits = (buffer[chunk.byte] >> chunk.shift) as u128 & ((1u128 << chunk.take) - 1);
+            value | bits << chunk.value_shift
+        })
+    }
+    /**
+     * @description: 把值写入字节缓冲区中的位域,其余位保持不变
+     * @param {*} self  不可变的自己
+     * @param {&mut [u8]} buffer    字节缓冲区,调用前需保证end()不超过缓冲区位数
+     * @param {u128} value  要写入的值,超出长度的部分被截断
+     * @return {无} 无
+     */
+    pub(crate) fn write(&self, buffer: &mut [u8], value: u128) {
+        for chunk in self.chunks() {
+            let mask = (((1u16 << chunk.take) - 1) << chunk.shift) as u8;
+            let bits = ((value >> chunk.value_shift) as u8) << chunk.shift;
+            buffer[chunk.byte] = (buffer[chunk.byte] & !mask) | (bits & mask);
+        }
+    }
+    /**
+     * @description: 计算两个位域在字节映像中共同占用的位
+     * @param {*} self  不可变的自己
+     * @param {&Placement} other   另一个位域
+     * @return {Option}     有重叠时，返回重叠部分在LSB-0线性编号中的[开始,结束)位置
+     */
+    pub(crate) fn overlap(&self, other: &Placement) -> Option<(usize, usize)> {
+        let mut range: Option<(usize, usize)> = None;
+        for a in self.chunks() {
+            for b in other.chunks().iter().filter(|b| b.byte == a.byte) {
+                let lo = a.shift.max(b.shift);
+                let hi = (a.shift + a.take).min(b.shift + b.take);
+                if lo < hi {
+                    let (lo, hi) = (a.byte * 8 + lo, a.byte * 8 + hi);
+                    range = Some(range.map_or((lo, hi), |(start, end)| (start.min(lo), end.max(hi))));
+                }
+            }
+        }
+        range
+    }
+}
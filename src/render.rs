@@ -0,0 +1,207 @@
+use std::fmt;
+
+use crate::bitfield::{BitStorage, Bitfield};
+use crate::layout::{BitfieldRef, BitfieldValue, Layout};
+
+/**
+ * 位域值的显示进制
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Radix {
+    Binary,
+    Octal,
+    #[default]
+    Decimal,
+    Hex,
+}
+
+/**
+ * 框图每行最多显示的位数,与RFC报文图一致
+ */
+const ROW_BITS: usize = 32;
+
+/**
+ * @description: 按进制格式化位域的值,有符号位域在十进制下显示为负数,其余进制显示位域宽度内的补码
+ * @param {&BitfieldValue} value  位域信息
+ * @param {T} bitfielddata 需要做位域处理的数据
+ * @param {Radix} radix 显示进制
+ * @return {String} 格式化后的值
+ */
+pub fn format_value<T: BitStorage>(value: &BitfieldValue, bitfielddata: T, radix: Radix) -> String {
+    let raw = (bitfielddata.to_raw() >> value.offset()) & T::mask(value.length());
+    let length = value.length() as usize;
+    match radix {
+        Radix::Binary => format!("0b{:0width$b}", raw, width = length.max(1)),
+        Radix::Octal => format!("0o{:o}", raw),
+        Radix::Decimal if value.is_signed() => format!("{}", T::sign_extend(raw, value.length()) as i128),
+        Radix::Decimal => format!("{}", raw),
+        Radix::Hex => format!("0x{:0width$x}", raw, width = length.div_ceil(4).max(1)),
+    }
+}
+
+/**
+ * @description: 把文字居中放进指定宽度,放不下时截断,只用于位域名字
+ * @param {&str} text   文字
+ * @param {usize} width    宽度
+ * @return {String} 居中后的文字
+ */
+fn center(text: &str, width: usize) -> String {
+    let text: String = text.chars().take(width).collect();
+    let len = text.chars().count();
+    let left = (width - len) / 2;
+    format!("{}{}{}", " ".repeat(left), text, " ".repeat(width - len - left))
+}
+
+/**
+ * @description: 把位域的值居中放进格子,从不截断数字:放不下时去掉进制前缀,仍然放不下时加宽格子
+ * @param {&str} text   格式化后的值
+ * @param {usize} width    格子宽度
+ * @return {String} 居中后的值
+ */
+fn fit_value(text: &str, width: usize) -> String {
+    let digits = match text.get(..2) {
+        Some("0b" | "0o" | "0x") if text.chars().count() > width => &text[2..],
+        _ => text,
+    };
+    center(digits, width.max(digits.chars().count()))
+}
+
+/**
+ * @description: 绘制RFC风格的位域框图:位标尺、跨越各自位范围的位域名字以及位域的当前值
+ * 每行最多32位,高位在左,位号为位域在数据字中的LSB-0位置,别名位域不画入框图
+ * 格子放不下位域名字时截断名字,放不下值时先去掉进制前缀,仍然放不下时加宽格子
+ * @param {&Layout} layout  位域布局
+ * @param {T} bitfielddata 需要做位域处理的数据
+ * @param {Radix} radix 显示进制
+ * @return {String} 框图
+ */
+pub fn render<T: BitStorage>(layout: &Layout<T>, bitfielddata: T, radix: Radix) -> String {
+    let width = T::BITS as usize;
+    let row_bits = width.min(ROW_BITS);
+    let fields: Vec<(&str, &BitfieldValue)> = layout.fields().filter(|(_, value)| !value.is_alias() && value.length() > 0).collect();
+    let owner = |bit: usize| {
+        fields
+            .iter()
+            .position(|(_, value)| (value.offset() as usize..value.offset() as usize + value.length() as usize).contains(&bit))
+    };
+    let digits = (width - 1).to_string().len();
+    let border = format!("+{}\n", "-+".repeat(row_bits));
+    let mut out = String::new();
+    for row in 0..width / row_bits {
+        let high = width - 1 - row * row_bits;
+        let bits: Vec<usize> = (0..row_bits).map(|i| high - i).collect();
+        for place in (0..digits).rev() {
+            let ruler: Vec<String> = bits
+                .iter()
+                .map(|bit| {
+                    let divisor = 10usize.pow(place as u32);
+                    if place == 0 || *bit >= divisor {
+                        ((bit / divisor) % 10).to_string()
+                    } else {
+                        " ".to_string()
+                    }
+                })
+                .collect();
+            out.push_str(&format!(" {}\n", ruler.join(" ").trim_end()));
+        }
+        out.push_str(&border);
+        //把一行按位域拆分成若干段,没有位域的位单独成段
+        let mut segments: Vec<(Option<usize>, usize)> = Vec::new();
+        for bit in &bits {
+            let field = owner(*bit);
+            match segments.last_mut() {
+                Some((last, count)) if field.is_some() && *last == field => *count += 1,
+                _ => segments.push((field, 1)),
+            }
+        }
+        let mut names = String::from("|");
+        let mut values = String::from("|");
+        for (field, count) in &segments {
+            let cell = count * 2 - 1;
+            match field {
+                Some(index) => {
+                    let (name, value) = fields[*index];
+                    names.push_str(&center(name, cell));
+                    values.push_str(&fit_value(&format_value(value, bitfielddata, radix), cell));
+                }
+                None => {
+                    names.push_str(&" ".repeat(cell));
+                    values.push_str(&" ".repeat(cell));
+                }
+            }
+            names.push('|');
+            values.push('|');
+        }
+        out.push_str(&format!("{}\n{}\n", names, values));
+        out.push_str(&border);
+    }
+    out
+}
+
+/**
+ * @description: 输出数据字以及每个位域的值,每行一个位域,有对照表的位域显示名字,不在表中的值显示为Unknown(n),有换算信息的位域显示带单位的物理值和原始值
+ * @param {&mut Formatter} f    格式化器
+ * @param {&Layout} layout  位域布局
+ * @param {T} bitfielddata 需要做位域处理的数据
+ * @return {fmt::Result}    格式化结果
+ */
+fn write_fields<T: BitStorage>(f: &mut fmt::Formatter<'_>, layout: &Layout<T>, bitfielddata: T) -> fmt::Result {
+    let width = T::BITS as usize;
+    writeln!(f, "0x{:0digits$x} ({} bits)", bitfielddata.to_raw(), width, digits = width / 4)?;
+    let pad = layout.fields().map(|(name, _)| name.chars().count()).max().unwrap_or(0);
+    for (name, value) in layout.fields() {
+        let range = format!("[{}..{}]", value.start(), value.start() as usize + value.length() as usize);
+        let raw = format_value(value, bitfielddata, Radix::Decimal);
+        if value.enum_table().is_some() {
+            let bits = (bitfielddata.to_raw() >> value.offset()) & T::mask(value.length());
+            match layout.get_enum(bitfielddata, name).ok().flatten() {
+                Some(label) => writeln!(f, "  {:<pad$} {} = {} ({})", name, range, label, raw, pad = pad)?,
+                None => writeln!(f, "  {:<pad$} {} = Unknown({})", name, range, bits, pad = pad)?,
+            }
+            continue;
+        }
+        match (value.scaling(), layout.get_physical(bitfielddata, name)) {
+            (Some(scaling), Ok(physical)) => {
+                writeln!(f, "  {:<pad$} {} = {} (raw {})", name, range, scaling.format(physical), raw, pad = pad)?
+            }
+            _ => writeln!(f, "  {:<pad$} {} = {}", name, range, raw, pad = pad)?,
+        }
+    }
+    Ok(())
+}
+
+impl<T: BitStorage> Bitfield<T> {
+    /**
+     * @description: 绘制位域框图
+     * @param {*} self  不可变的自己
+     * @param {Radix} radix 显示进制
+     * @return {String} 框图
+     */
+    pub fn render(&self, radix: Radix) -> String {
+        render(self.layout(), self.get_data(), radix)
+    }
+}
+
+impl<T: BitStorage> BitfieldRef<'_, T> {
+    /**
+     * @description: 绘制位域框图
+     * @param {*} self  不可变的自己
+     * @param {Radix} radix 显示进制
+     * @return {String} 框图
+     */
+    pub fn render(&self, radix: Radix) -> String {
+        render(self.layout(), self.get_data(), radix)
+    }
+}
+
+impl<T: BitStorage> fmt::Display for Bitfield<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_fields(f, self.layout(), self.get_data())
+    }
+}
+
+impl<T: BitStorage> fmt::Display for BitfieldRef<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_fields(f, self.layout(), self.get_data())
+    }
+}
@@ -0,0 +1,226 @@
+use crate::bitfield::{BitStorage, Bitfield};
+use crate::error::BitfieldError;
+use crate::handle::FieldHandle;
+use crate::layout::{BitfieldRef, Layout};
+
+/**
+ * 位域的线性换算信息:物理值 = 原始值 * factor + offset
+ * unit为工程单位,min和max为允许写入的物理值范围,不设置时不限制
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub struct Scaling {
+    factor: f64,
+    offset: f64,
+    unit: String,
+    min: Option<f64>,
+    max: Option<f64>,
+}
+
+impl Scaling {
+    /**
+     * @description: 创建换算信息,没有单位,不限制范围
+     * @param {f64} factor  比例系数
+     * @param {f64} offset  偏移量
+     * @return {Scaling}    换算信息
+     */
+    pub fn new(factor: f64, offset: f64) -> Scaling {
+        Scaling { factor, offset, unit: String::new(), min: None, max: None }
+    }
+    /**
+     * @description: 设置工程单位
+     * @param {*} self  自己
+     * @param {&str} unit   单位,例如°C
+     * @return {Scaling}    换算信息
+     */
+    pub fn with_unit(self, unit: &str) -> Scaling {
+        Scaling { unit: unit.to_string(), ..self }
+    }
+    /**
+     * @description: 设置允许写入的物理值范围
+     * @param {*} self  自己
+     * @param {f64} min 最小物理值
+     * @param {f64} max 最大物理值
+     * @return {Scaling}    换算信息
+     */
+    pub fn with_range(self, min: f64, max: f64) -> Scaling {
+        Scaling { min: Some(min), max: Some(max), ..self }
+    }
+    /**
+     * @description: 获取比例系数
+     * @param {*} self  不可变的自己
+     * @return {f64}    比例系数
+     */
+    pub fn factor(&self) -> f64 {
+        self.factor
+    }
+    /**
+     * @description: 获取偏移量
+     * @param {*} self  不可变的自己
+     * @return {f64}    偏移量
+     */
+    pub fn offset(&self) -> f64 {
+        self.offset
+    }
+    /**
+     * @description: 获取工程单位
+     * @param {*} self  不可变的自己
+     * @return {&str}   单位,没有单位时为空字符串
+     */
+    pub fn unit(&self) -> &str {
+        &self.unit
+    }
+    /**
+     * @description: 获取允许写入的物理值范围
+     * @param {*} self  不可变的自己
+     * @return {(Option, Option)}   (最小值, 最大值),不限制的一端为None
+     */
+    pub fn range(&self) -> (Option<f64>, Option<f64>) {
+        (self.min, self.max)
+    }
+    /**
+     * @description: 把物理值换算为原始值,按最近的整数取整,并检查物理值范围和位域能表示的原始值范围
+     * @param {*} self  不可变的自己
+     * @param {f64} physical    物理值
+     * @param {u8} length   位域长度
+     * @param {bool} signed 位域是否有符号
+     * @return {Result} 成功，返回原始值,失败时，返回失败原因
+     */
+    pub(crate) fn to_raw(&self, physical: f64, length: u8, signed: bool) -> Result<i128, BitfieldError> {
+        let (min, max) = (self.min.unwrap_or(f64::NEG_INFINITY), self.max.unwrap_or(f64::INFINITY));
+        let raw = ((physical - self.offset) / self.factor).round();
+        if !physical.is_finite() || !raw.is_finite() || physical < min || physical > max {
+            return Err(BitfieldError::physical_out_of_range(physical, min, max));
+        }
+        //先在i128上检查范围,避免from_raw截断
+        let mask = u128::mask(length);
+        let raw = raw as i128;
+        if signed {
+            let max = (mask >> 1) as i128;
+            let min = if length == 0 { 0 } else { -max - 1 };
+            if raw < min || raw > max {
+                return Err(BitfieldError::SignedValueOutOfRange { value: raw, min, max });
+            }
+        } else if raw < 0 {
+            return Err(BitfieldError::SignedValueOutOfRange { value: raw, min: 0, max: mask.min(i128::MAX as u128) as i128 });
+        } else if raw as u128 > mask {
+            return Err(BitfieldError::ValueOutOfRange { value: raw as u128, max: mask });
+        }
+        Ok(raw)
+    }
+    /**
+     * @description: 按系数和偏移量的小数位数格式化物理值,并附上单位
+     * @param {*} self  不可变的自己
+     * @param {f64} value   物理值
+     * @return {String} 格式化后的物理值
+     */
+    pub fn format(&self, value: f64) -> String {
+        let precision = decimals(self.factor).max(decimals(self.offset));
+        if self.unit.is_empty() {
+            format!("{:.*}", precision, value)
+        } else {
+            format!("{:.*} {}", precision, value, self.unit)
+        }
+    }
+}
+
+/**
+ * 十进制小数位数的上限,系数不是有限小数时按该位数显示
+ */
+const MAX_DECIMALS: usize = 9;
+
+/**
+ * @description: 计算一个数按十进制表示所需的小数位数
+ * @param {f64} value   数
+ * @return {usize}  小数位数,不超过MAX_DECIMALS
+ */
+fn decimals(value: f64) -> usize {
+    (0..MAX_DECIMALS)
+        .find(|places| {
+            let scaled = value * 10f64.powi(*places as i32);
+            (scaled - scaled.round()).abs() <= 1e-9 * scaled.abs().max(1.0)
+        })
+        .unwrap_or(MAX_DECIMALS)
+}
+
+impl<T: BitStorage> Layout<T> {
+    /**
+     * @description: 读取位域的原始值并换算为物理值,没有换算信息的位域按系数1、偏移0换算
+     * @param {*} self  不可变的自己
+     * @param {T} bitfielddata 需要做位域处理的数据
+     * @param {&str} key   位域名字
+     * @return {Result} 成功，返回物理值,失败时，返回失败原因
+     */
+    pub fn get_physical(&self, bitfielddata: T, key: &str) -> Result<f64, BitfieldError> {
+        let value = self.get_param(key)?;
+        let raw = (bitfielddata.to_raw() >> value.offset()) & T::mask(value.length());
+        let raw = if value.is_signed() { T::sign_extend(raw, value.length()) as i128 as f64 } else { raw as f64 };
+        Ok(match value.scaling() {
+            Some(scaling) => raw * scaling.factor + scaling.offset,
+            None => raw,
+        })
+    }
+    /**
+     * @description: 把物理值换算为原始值并写入数据字,原始值按最近的整数取整
+     * @param {*} self  不可变的自己
+     * @param {T} bitfielddata 需要做位域处理的数据
+     * @param {&str} key    位域名字
+     * @param {f64} physical    物理值
+     * @return {Result}     成功，返回写入后的数据字，物理值超出设置的范围或原始值超出位域能表示的范围时，返回失败原因
+     */
+    pub fn set_physical(&self, bitfielddata: T, key: &str, physical: f64) -> Result<T, BitfieldError> {
+        let value = self.get_param(key)?;
+        let raw = match value.scaling() {
+            Some(scaling) => scaling.to_raw(physical, value.length(), value.is_signed())?,
+            None => Scaling::new(1.0, 0.0).to_raw(physical, value.length(), value.is_signed())?,
+        };
+        FieldHandle::<T>::new(value).set(bitfielddata, T::from_raw(raw as u128))
+    }
+}
+
+impl<T: BitStorage> Bitfield<T> {
+    /**
+     * @description: 读取位域的物理值
+     * @param {*} self  不可变的自己
+     * @param {&str} key   位域名字
+     * @return {Result} 成功，返回物理值,失败时，返回失败原因
+     */
+    pub fn get_physical(&self, key: &str) -> Result<f64, BitfieldError> {
+        self.layout().get_physical(self.get_data(), key)
+    }
+    /**
+     * @description: 把物理值换算为原始值并写入位域
+     * @param {*} mut self  可变的自己
+     * @param {&str} key    位域名字
+     * @param {f64} physical    物理值
+     * @return {Result}     成功，无信息，失败，返回失败原因
+     */
+    pub fn set_physical(&mut self, key: &str, physical: f64) -> Result<(), BitfieldError> {
+        let bitfielddata = self.layout().set_physical(self.get_data(), key, physical)?;
+        self.set_data(bitfielddata);
+        Ok(())
+    }
+}
+
+impl<T: BitStorage> BitfieldRef<'_, T> {
+    /**
+     * @description: 读取位域的物理值
+     * @param {*} self  不可变的自己
+     * @param {&str} key   位域名字
+     * @return {Result} 成功，返回物理值,失败时，返回失败原因
+     */
+    pub fn get_physical(&self, key: &str) -> Result<f64, BitfieldError> {
+        self.layout().get_physical(self.get_data(), key)
+    }
+    /**
+     * @description: 把物理值换算为原始值并写入位域
+     * @param {*} mut self  可变的自己
+     * @param {&str} key    位域名字
+     * @param {f64} physical    物理值
+     * @return {Result}     成功，无信息，失败，返回失败原因
+     */
+    pub fn set_physical(&mut self, key: &str, physical: f64) -> Result<(), BitfieldError> {
+        let bitfielddata = self.layout().set_physical(self.get_data(), key, physical)?;
+        self.set_data(bitfielddata);
+        Ok(())
+    }
+}
@@ -0,0 +1,189 @@
+use std::fmt;
+
+use crate::access::Access;
+use crate::bank::{split_path, RegisterBank};
+use crate::bitfield::BitStorage;
+use crate::error::BitfieldError;
+
+/**
+ * 位域变化时调用的回调,参数为寄存器组、旧值和新值
+ * 回调可以通过寄存器组的register_mut模拟硬件一侧的修改,这些修改不会再次触发回调
+ */
+type Callback<T> = Box<dyn FnMut(&mut RegisterBank<T>, T, T)>;
+
+/**
+ * 注册在一个位域上的回调
+ */
+struct Hook<T> {
+    register: String,
+    field: String,
+    callback: Callback<T>,
+}
+
+/**
+ * 模拟的硬件设备,在寄存器组之上模拟真实的访问语义,用于在没有硬件时测试驱动
+ * 写1清零和写1置位位域只响应写1的位,读清零位域读出后清零,只写位域读出为0,只读位域和保留位忽略写入
+ * 软件访问使位域发生变化时,调用注册在该位域上的回调
+ */
+pub struct SimDevice<T> {
+    bank: RegisterBank<T>,
+    hooks: Vec<Hook<T>>,
+}
+
+impl<T: BitStorage> SimDevice<T> {
+    /**
+     * @description: 由寄存器组创建模拟设备
+     * @param {RegisterBank} bank   寄存器组,寄存器的当前值作为设备的初始状态
+     * @return {SimDevice}  模拟设备
+     */
+    pub fn new(bank: RegisterBank<T>) -> SimDevice<T> {
+        SimDevice { bank, hooks: Vec::new() }
+    }
+    /**
+     * @description: 获取寄存器组
+     * @param {*} self  不可变的自己
+     * @return {&RegisterBank}  寄存器组
+     */
+    pub fn bank(&self) -> &RegisterBank<T> {
+        &self.bank
+    }
+    /**
+     * @description: 获取可修改的寄存器组,用于模拟硬件一侧的修改,不执行访问语义也不触发回调
+     * @param {*} mut self  可变的自己
+     * @return {&mut RegisterBank}  寄存器组
+     */
+    pub fn bank_mut(&mut self) -> &mut RegisterBank<T> {
+        &mut self.bank
+    }
+    /**
+     * @description: 把全部寄存器恢复为复位值,不触发回调
+     * @param {*} mut self  可变的自己
+     * @return {无} 无
+     */
+    pub fn reset(&mut self) {
+        self.bank.reset();
+    }
+    /**
+     * @description: 注册位域变化时的回调,同一个位域可以注册多个回调,按注册顺序调用
+     * @param {*} mut self  可变的自己
+     * @param {&str} path   "寄存器.位域"路径
+     * @param {F} callback  回调,参数为寄存器组、旧值和新值
+     * @return {Result} 成功，无信息，路径不存在时，返回失败原因
+     */
+    pub fn on_change<F>(&mut self, path: &str, callback: F) -> Result<(), BitfieldError>
+    where
+        F: FnMut(&mut RegisterBank<T>, T, T) + 'static,
+    {
+        self.bank.get_param(path)?;
+        let (register, field) = split_path(path)?;
+        self.hooks.push(Hook { register: register.to_string(), field: field.to_string(), callback: Box::new(callback) });
+        Ok(())
+    }
+    /**
+     * @description: 比较寄存器访问前后的值,调用值发生变化的位域上的回调
+     * @param {*} mut self  可变的自己
+     * @param {&str} register   寄存器名字
+     * @param {T} before    访问前寄存器的值
+     * @return {Result} 成功，无信息，失败时，返回失败原因
+     */
+    fn notify(&mut self, register: &str, before: T) -> Result<(), BitfieldError> {
+        let after = self.bank.register(register)?.get_data();
+        if after == before {
+            return Ok(());
+        }
+        for hook in self.hooks.iter_mut().filter(|hook| hook.register == register) {
+            let layout = self.bank.register(register)?.layout();
+            let old = layout.get_value(before, &hook.field)?;
+            let new = layout.get_value(after, &hook.field)?;
+            if old != new {
+                (hook.callback)(&mut self.bank, old, new);
+            }
+        }
+        Ok(())
+    }
+    /**
+     * @description: 计算寄存器的保留位,即不属于任何位域的位,别名位域不计入
+     * @param {*} self  不可变的自己
+     * @param {&str} register   寄存器名字
+     * @return {Result} 成功，返回保留位掩码,失败时，返回失败原因
+     */
+    fn reserved(&self, register: &str) -> Result<u128, BitfieldError> {
+        let layout = self.bank.register(register)?.layout();
+        let owned = layout
+            .fields()
+            .filter(|(_, value)| !value.is_alias())
+            .fold(0, |owned, (_, value)| owned | (T::mask(value.length()) << value.offset()));
+        Ok(T::mask(T::BITS) & !owned)
+    }
+    /**
+     * @description: 软件读取整个寄存器
+     * @param {*} mut self  可变的自己
+     * @param {u64} offset  地址偏移
+     * @return {Result} 成功，返回读出的值,失败时，返回失败原因
+     */
+    pub fn read(&mut self, offset: u64) -> Result<T, BitfieldError> {
+        let register = self.bank.name_at(offset)?.to_string();
+        let before = self.bank.register(&register)?.get_data();
+        let value = self.bank.read(offset)?;
+        self.notify(&register, before)?;
+        Ok(value)
+    }
+    /**
+     * @description: 软件写入整个寄存器,保留位保持原值
+     * @param {*} mut self  可变的自己
+     * @param {u64} offset  地址偏移
+     * @param {T} value     写入的值
+     * @return {Result} 成功，无信息，失败时，返回失败原因
+     */
+    pub fn write(&mut self, offset: u64, value: T) -> Result<(), BitfieldError> {
+        let register = self.bank.name_at(offset)?.to_string();
+        let reserved = self.reserved(&register)?;
+        let before = self.bank.register(&register)?.get_data();
+        self.bank.write(offset, value)?;
+        let bitfield = self.bank.register_mut(&register)?;
+        let after = (bitfield.get_data().to_raw() & !reserved) | (before.to_raw() & reserved);
+        bitfield.set_data(T::from_raw(after));
+        self.notify(&register, before)
+    }
+    /**
+     * @description: 软件读取位域,只写位域读出为0,读清零位域读出后清零
+     * @param {*} mut self  可变的自己
+     * @param {&str} path   "寄存器.位域"路径
+     * @return {Result} 成功，返回读出的值,失败时，返回失败原因
+     */
+    pub fn get_value(&mut self, path: &str) -> Result<T, BitfieldError> {
+        if self.bank.get_param(path)?.access() == Access::WriteOnly {
+            return Ok(T::from_raw(0));
+        }
+        let (register, _) = split_path(path)?;
+        let before = self.bank.register(register)?.get_data();
+        let value = self.bank.get_value(path)?;
+        self.notify(register, before)?;
+        Ok(value)
+    }
+    /**
+     * @description: 软件写入位域,只读和读清零位域忽略写入,超出位域范围的值仍然返回失败原因
+     * @param {*} mut self  可变的自己
+     * @param {&str} path   "寄存器.位域"路径
+     * @param {T} value     要写入的值
+     * @return {Result} 成功，无信息，失败时，返回失败原因
+     */
+    pub fn set_value(&mut self, path: &str, value: T) -> Result<(), BitfieldError> {
+        let (register, field) = split_path(path)?;
+        let bitfield = self.bank.register(register)?;
+        let before = bitfield.get_data();
+        bitfield.layout().set_value(before, field, value)?;
+        if !bitfield.get_param(field)?.access().is_writable() {
+            return Ok(());
+        }
+        self.bank.set_value(path, value)?;
+        self.notify(register, before)
+    }
+}
+
+impl<T: BitStorage> fmt::Debug for SimDevice<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let hooks: Vec<String> = self.hooks.iter().map(|hook| format!("{}.{}", hook.register, hook.field)).collect();
+        f.debug_struct("SimDevice").field("bank", &self.bank).field("hooks", &hooks).finish()
+    }
+}
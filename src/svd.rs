@@ -0,0 +1,617 @@
+use crate::bitfield::{BitStorage, Bitfield};
+use crate::enumeration::EnumTable;
+use crate::error::BitfieldError;
+use crate::format::{FieldDef, LayoutDef};
+use crate::layout::Layout;
+use crate::order::{BitOrder, ByteOrder};
+use crate::xml::{self, Element};
+
+/**
+ * dim数组展开后的最大元素个数,防止畸形文档占用大量内存
+ */
+const MAX_DIM: usize = 4096;
+
+/**
+ * 位域的一个枚举值
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SvdEnumValue {
+    name: String,
+    value: u128,
+    description: String,
+}
+
+/**
+ * 寄存器中被接受的位域的附加信息,位置和说明保存在寄存器的布局定义中
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SvdField {
+    name: String,
+    access: Option<String>,
+    enumerated_values: Vec<SvdEnumValue>,
+}
+
+/**
+ * 一个寄存器,布局按LSB-0编号,宽度等于寄存器的size
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SvdRegister {
+    name: String,
+    description: String,
+    address_offset: u64,
+    reset_value: u128,
+    access: Option<String>,
+    layout: LayoutDef,
+    fields: Vec<SvdField>,
+    rejected: Vec<(String, BitfieldError)>,
+}
+
+/**
+ * 一个外设
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SvdPeripheral {
+    name: String,
+    base_address: u64,
+    registers: Vec<SvdRegister>,
+}
+
+/**
+ * CMSIS-SVD器件描述,读取<peripheral>/<register>/<field>
+ * 支持bitOffset/bitWidth、lsb/msb和bitRange三种位置写法,size、access、resetValue按器件、外设、寄存器逐级继承
+ * 支持外设的derivedFrom和寄存器的dim数组,不支持cluster
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SvdDevice {
+    name: String,
+    peripherals: Vec<SvdPeripheral>,
+}
+
+/**
+ * 可以逐级继承的寄存器属性
+ */
+#[derive(Debug, Clone, Copy)]
+struct Properties<'a> {
+    size: Option<&'a str>,
+    access: Option<&'a str>,
+    reset_value: Option<&'a str>,
+}
+
+impl<'a> Properties<'a> {
+    /**
+     * @description: 用元素中出现的属性覆盖上一级的属性
+     * @param {*} self  上一级的属性
+     * @param {&Element} element    当前元素
+     * @return {Properties}     当前级别的属性
+     */
+    fn inherit(self, element: &'a Element) -> Properties<'a> {
+        Properties {
+            size: element.child_text("size").or(self.size),
+            access: element.child_text("access").or(self.access),
+            reset_value: element.child_text("resetValue").or(self.reset_value),
+        }
+    }
+}
+
+/**
+ * @description: 生成文档结构错误
+ * @param {&str} path   出错的位置
+ * @param {String} message    错误信息
+ * @return {BitfieldError}  InvalidDocument错误
+ */
+fn invalid(path: &str, message: String) -> BitfieldError {
+    BitfieldError::InvalidDocument { path: path.to_string(), message }
+}
+
+/**
+ * @description: 解析SVD中的数字,支持十进制、0x十六进制和#二进制
+ * @param {&str} text   数字文本
+ * @return {Option}     合法时返回数字
+ */
+fn number(text: &str) -> Option<u128> {
+    let text = text.trim();
+    if let Some(hex) = text.strip_prefix("0x").or_else(|| text.strip_prefix("0X")) {
+        u128::from_str_radix(hex, 16).ok()
+    } else if let Some(bin) = text.strip_prefix('#') {
+        u128::from_str_radix(bin, 2).ok()
+    } else {
+        text.parse::<u128>().ok()
+    }
+}
+
+/**
+ * @description: 读取必需的数字子元素
+ * @param {&Element} element    元素
+ * @param {&str} name   子元素名字
+ * @param {&str} path   元素的位置
+ * @return {Result} 成功，返回数字，失败时，返回失败原因
+ */
+fn required(element: &Element, name: &str, path: &str) -> Result<u128, BitfieldError> {
+    let path = format!("{}.{}", path, name);
+    let text = element.child_text(name).ok_or_else(|| invalid(&path, "missing".to_string()))?;
+    number(text).ok_or_else(|| invalid(&path, format!("invalid number: {}", text)))
+}
+
+/**
+ * @description: 读取必需的数字子元素并转换为目标类型
+ * @param {&Element} element    元素
+ * @param {&str} name   子元素名字
+ * @param {&str} path   元素的位置
+ * @return {Result} 成功，返回数字，超出目标类型的范围时，返回失败原因
+ */
+fn required_as<N: TryFrom<u128>>(element: &Element, name: &str, path: &str) -> Result<N, BitfieldError> {
+    let value = required(element, name, path)?;
+    N::try_from(value).map_err(|_| invalid(&format!("{}.{}", path, name), format!("number out of range: {:#x}", value)))
+}
+
+/**
+ * @description: 读取可省略的数字属性
+ * @param {Option} text 属性文本
+ * @param {&str} path   属性的位置
+ * @param {u128} default    省略时的值
+ * @return {Result} 成功，返回数字，失败时，返回失败原因
+ */
+fn optional(text: Option<&str>, path: &str, default: u128) -> Result<u128, BitfieldError> {
+    match text {
+        Some(text) => number(text).ok_or_else(|| invalid(path, format!("invalid number: {}", text))),
+        None => Ok(default),
+    }
+}
+
+/**
+ * @description: 把多行说明合并为一行
+ * @param {&Element} element    元素
+ * @return {String} 说明文字,没有说明时为空字符串
+ */
+fn description(element: &Element) -> String {
+    element.child_text("description").unwrap_or("").split_whitespace().collect::<Vec<&str>>().join(" ")
+}
+
+/**
+ * @description: 读取位域的位置,三种写法按bitOffset/bitWidth、lsb/msb、bitRange的顺序查找
+ * @param {&Element} field  位域元素
+ * @param {&str} path   位域的位置
+ * @return {Result} 成功，返回(最低位, 长度)，失败时，返回失败原因
+ */
+fn bit_range(field: &Element, path: &str) -> Result<(u128, u128), BitfieldError> {
+    if field.child("bitOffset").is_some() {
+        let offset = required(field, "bitOffset", path)?;
+        let width = optional(field.child_text("bitWidth"), &format!("{}.bitWidth", path), 1)?;
+        return Ok((offset, width));
+    }
+    if field.child("lsb").is_some() || field.child("msb").is_some() {
+        let lsb = required(field, "lsb", path)?;
+        let msb = required(field, "msb", path)?;
+        if msb < lsb {
+            return Err(invalid(&format!("{}.msb", path), format!("msb {} is below lsb {}", msb, lsb)));
+        }
+        return Ok((lsb, msb - lsb + 1));
+    }
+    if let Some(text) = field.child_text("bitRange") {
+        let range = text.strip_prefix('[').and_then(|text| text.strip_suffix(']')).and_then(|text| text.split_once(':'));
+        let bounds = range.and_then(|(msb, lsb)| Some((number(msb)?, number(lsb)?)));
+        return match bounds {
+            Some((msb, lsb)) if msb >= lsb => Ok((lsb, msb - lsb + 1)),
+            _ => Err(invalid(&format!("{}.bitRange", path), format!("expected [msb:lsb], found {}", text))),
+        };
+    }
+    Err(invalid(path, "missing bitOffset, lsb/msb or bitRange".to_string()))
+}
+
+/**
+ * @description: 读取位域的枚举值,带x的无关位写法和isDefault项无法表示为单个值,被跳过
+ * @param {&Element} field  位域元素
+ * @return {Vec}    枚举值
+ */
+fn enumerated_values(field: &Element) -> Vec<SvdEnumValue> {
+    field
+        .children("enumeratedValues")
+        .flat_map(|values| values.children("enumeratedValue"))
+        .filter_map(|value| {
+            Some(SvdEnumValue {
+                name: value.child_text("name")?.to_string(),
+                value: number(value.child_text("value")?)?,
+                description: description(value),
+            })
+        })
+        .collect()
+}
+
+/**
+ * @description: 按寄存器宽度逐个插入位域,被insert拒绝的位域记录下来后跳过
+ * @param {&[(FieldDef, SvdField)]} candidates  候选位域
+ * @return {(Vec, Vec, Vec)}    被接受的位域定义,被接受的位域附加信息,被拒绝的位域及原因
+ */
+fn accept<T: BitStorage>(candidates: Vec<(FieldDef, SvdField)>) -> (Vec<FieldDef>, Vec<SvdField>, Vec<(String, BitfieldError)>) {
+    let mut layout: Layout<T> = Layout::new();
+    let (mut defs, mut fields, mut rejected) = (Vec::new(), Vec::new(), Vec::new());
+    for (def, field) in candidates {
+        match layout.insert(def.name().to_string(), def.start(), def.length()) {
+            Ok(()) => {
+                defs.push(def);
+                fields.push(field);
+            }
+            Err(err) => rejected.push((field.name, err)),
+        }
+    }
+    (defs, fields, rejected)
+}
+
+/**
+ * @description: 读取一个寄存器,dim数组在调用前已经展开
+ * @param {&Element} register   寄存器元素
+ * @param {String} name     展开后的寄存器名字
+ * @param {u64} address_offset  展开后的地址偏移
+ * @param {Properties} properties   继承的寄存器属性
+ * @param {&str} path   寄存器的位置
+ * @return {Result} 成功，返回寄存器，失败时，返回失败原因
+ */
+fn register(register: &Element, name: String, address_offset: u64, properties: Properties, path: &str) -> Result<SvdRegister, BitfieldError> {
+    let properties = properties.inherit(register);
+    let size_path = format!("{}.size", path);
+    let size = optional(properties.size, &size_path, 32)?;
+    let size = usize::try_from(size).map_err(|_| invalid(&size_path, format!("unsupported register size {}", size)))?;
+    let reset_value = optional(properties.reset_value, &format!("{}.resetValue", path), 0)?;
+    let mut candidates = Vec::new();
+    let mut rejected = Vec::new();
+    for field in register.child("fields").map(|fields| fields.children("field").collect::<Vec<&Element>>()).unwrap_or_default() {
+        let field_name = field.child_text("name").unwrap_or("").to_string();
+        let field_path = format!("{}.{}", path, field_name);
+        let (lsb, length) = match bit_range(field, &field_path) {
+            Ok(range) => range,
+            Err(err) => {
+                rejected.push((field_name, err));
+                continue;
+            }
+        };
+        if lsb >= size as u128 || lsb > u8::MAX as u128 {
+            rejected.push((field_name, BitfieldError::StartOutOfRange { start: lsb.min(usize::MAX as u128) as usize, width: size }));
+            continue;
+        }
+        if length > u8::MAX as u128 {
+            rejected.push((field_name, BitfieldError::LengthOverflow { start: lsb as usize, length: length.min(usize::MAX as u128) as usize, width: size }));
+            continue;
+        }
+        let def = FieldDef::new(field_name.clone(), lsb as u8, length as u8, false, false, description(field));
+        let access = field.child_text("access").or(properties.access).map(str::to_string);
+        candidates.push((def, SvdField { name: field_name, access, enumerated_values: enumerated_values(field) }));
+    }
+    let (defs, fields, mut refused) = match size {
+        8 => accept::<u8>(candidates),
+        16 => accept::<u16>(candidates),
+        32 => accept::<u32>(candidates),
+        64 => accept::<u64>(candidates),
+        128 => accept::<u128>(candidates),
+        _ => return Err(invalid(&format!("{}.size", path), format!("unsupported register size {}", size))),
+    };
+    rejected.append(&mut refused);
+    Ok(SvdRegister {
+        name,
+        description: description(register),
+        address_offset,
+        reset_value: reset_value & u128::MAX >> (128 - size),
+        access: properties.access.map(str::to_string),
+        layout: LayoutDef::new(size, BitOrder::Lsb0, ByteOrder::LittleEndian, defs),
+        fields,
+        rejected,
+    })
+}
+
+/**
+ * @description: 展开寄存器的dim数组,名字中的%s依次替换为dimIndex中的索引
+ * @param {&Element} element    寄存器元素
+ * @param {&str} name   寄存器名字
+ * @param {&str} path   寄存器的位置
+ * @return {Result} 成功，返回(名字, 地址偏移)列表，失败时，返回失败原因
+ */
+fn expand(element: &Element, name: &str, path: &str) -> Result<Vec<(String, u64)>, BitfieldError> {
+    let offset: u64 = required_as(element, "addressOffset", path)?;
+    if element.child("dim").is_none() {
+        return Ok(vec![(name.to_string(), offset)]);
+    }
+    let dim: usize = required_as(element, "dim", path)?;
+    if dim == 0 || dim > MAX_DIM {
+        return Err(invalid(&format!("{}.dim", path), format!("dim must be between 1 and {}, found {}", MAX_DIM, dim)));
+    }
+    let increment: u64 = required_as(element, "dimIncrement", path)?;
+    let index_path = format!("{}.dimIndex", path);
+    let indices: Vec<String> = match element.child_text("dimIndex") {
+        Some(text) => match text.split_once('-') {
+            Some((first, last)) if !text.contains(',') => {
+                let range = first.trim().parse::<usize>().ok().zip(last.trim().parse::<usize>().ok());
+                let (first, last) = range.ok_or_else(|| invalid(&index_path, format!("invalid range: {}", text)))?;
+                //先比较个数再展开,防止超大的范围占用内存
+                if last.checked_sub(first).and_then(|count| count.checked_add(1)) != Some(dim) {
+                    return Err(invalid(&index_path, format!("expected {} indices, found range {}", dim, text)));
+                }
+                (first..=last).map(|index| index.to_string()).collect()
+            }
+            _ => text.split(',').map(|index| index.trim().to_string()).collect(),
+        },
+        None => (0..dim).map(|index| index.to_string()).collect(),
+    };
+    if indices.len() != dim {
+        return Err(invalid(&index_path, format!("expected {} indices, found {}", dim, indices.len())));
+    }
+    indices
+        .iter()
+        .enumerate()
+        .map(|(i, index)| {
+            let address = increment.checked_mul(i as u64).and_then(|step| offset.checked_add(step));
+            let address = address.ok_or_else(|| invalid(&format!("{}.dimIncrement", path), format!("address of element {} overflows", index)))?;
+            Ok((name.replace("[%s]", index).replace("%s", index), address))
+        })
+        .collect()
+}
+
+impl SvdDevice {
+    /**
+     * @description: 静态函数，解析SVD文档,每个寄存器生成一个布局定义
+     * 格式错误使整个解析失败,位置非法或与其他位域重叠的位域只记录在所属寄存器的rejected中
+     * @param {&str} text   SVD文档
+     * @return {Result} 成功，返回器件描述，失败时，返回失败原因
+     */
+    pub fn parse(text: &str) -> Result<SvdDevice, BitfieldError> {
+        let root = xml::parse(text)?;
+        if root.name != "device" {
+            return Err(invalid(&root.name, "expected <device> root element".to_string()));
+        }
+        let defaults = Properties { size: None, access: None, reset_value: None }.inherit(&root);
+        let mut peripherals = Vec::new();
+        let mut derived = Vec::new();
+        let elements: Vec<&Element> = root.child("peripherals").map(|list| list.children("peripheral").collect()).unwrap_or_default();
+        for element in elements {
+            let name = element.child_text("name").ok_or_else(|| invalid("peripheral", "missing name".to_string()))?.to_string();
+            let base_address: u64 = required_as(element, "baseAddress", &name)?;
+            let properties = defaults.inherit(element);
+            let mut registers = Vec::new();
+            for item in element.child("registers").map(|list| list.children("register").collect::<Vec<&Element>>()).unwrap_or_default() {
+                let register_name = item.child_text("name").ok_or_else(|| invalid(&name, "register is missing name".to_string()))?;
+                let path = format!("{}.{}", name, register_name);
+                for (expanded, offset) in expand(item, register_name, &path)? {
+                    registers.push(register(item, expanded, offset, properties, &path)?);
+                }
+            }
+            if let (Some(base), true) = (element.attribute("derivedFrom"), registers.is_empty()) {
+                derived.push((peripherals.len(), base.to_string()));
+            }
+            peripherals.push(SvdPeripheral { name, base_address, registers });
+        }
+        for (index, base) in derived {
+            let registers = peripherals
+                .iter()
+                .find(|peripheral| peripheral.name == base)
+                .map(|peripheral| peripheral.registers.clone())
+                .ok_or_else(|| invalid(&peripherals[index].name, format!("derivedFrom unknown peripheral {}", base)))?;
+            peripherals[index].registers = registers;
+        }
+        Ok(SvdDevice { name: root.child_text("name").unwrap_or("").to_string(), peripherals })
+    }
+    /**
+     * @description: 获取器件名字
+     * @param {*} self  不可变的自己
+     * @return {&str}   器件名字
+     */
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    /**
+     * @description: 获取全部外设,按文档中的顺序排列
+     * @param {*} self  不可变的自己
+     * @return {&[SvdPeripheral]}   外设
+     */
+    pub fn peripherals(&self) -> &[SvdPeripheral] {
+        &self.peripherals
+    }
+    /**
+     * @description: 按名字查找外设
+     * @param {*} self  不可变的自己
+     * @param {&str} name   外设名字
+     * @return {Option}     找到时返回外设
+     */
+    pub fn peripheral(&self, name: &str) -> Option<&SvdPeripheral> {
+        self.peripherals.iter().find(|peripheral| peripheral.name == name)
+    }
+    /**
+     * @description: 遍历所有被拒绝的位域
+     * @param {*} self  不可变的自己
+     * @return {Iterator}   (外设名字, 寄存器名字, 位域名字, 原因)
+     */
+    pub fn rejected(&self) -> impl Iterator<Item = (&str, &str, &str, &BitfieldError)> {
+        self.peripherals.iter().flat_map(|peripheral| {
+            peripheral.registers.iter().flat_map(move |register| {
+                register
+                    .rejected
+                    .iter()
+                    .map(move |(field, err)| (peripheral.name.as_str(), register.name.as_str(), field.as_str(), err))
+            })
+        })
+    }
+}
+
+impl SvdPeripheral {
+    /**
+     * @description: 获取外设名字
+     * @param {*} self  不可变的自己
+     * @return {&str}   外设名字
+     */
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    /**
+     * @description: 获取外设基地址
+     * @param {*} self  不可变的自己
+     * @return {u64}    基地址
+     */
+    pub fn base_address(&self) -> u64 {
+        self.base_address
+    }
+    /**
+     * @description: 获取全部寄存器,dim数组已经展开
+     * @param {*} self  不可变的自己
+     * @return {&[SvdRegister]}     寄存器
+     */
+    pub fn registers(&self) -> &[SvdRegister] {
+        &self.registers
+    }
+    /**
+     * @description: 按名字查找寄存器
+     * @param {*} self  不可变的自己
+     * @param {&str} name   寄存器名字
+     * @return {Option}     找到时返回寄存器
+     */
+    pub fn register(&self, name: &str) -> Option<&SvdRegister> {
+        self.registers.iter().find(|register| register.name == name)
+    }
+}
+
+impl SvdRegister {
+    /**
+     * @description: 获取寄存器名字
+     * @param {*} self  不可变的自己
+     * @return {&str}   寄存器名字
+     */
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    /**
+     * @description: 获取寄存器说明
+     * @param {*} self  不可变的自己
+     * @return {&str}   说明文字,没有说明时为空字符串
+     */
+    pub fn description(&self) -> &str {
+        &self.description
+    }
+    /**
+     * @description: 获取寄存器相对外设基地址的偏移
+     * @param {*} self  不可变的自己
+     * @return {u64}    地址偏移
+     */
+    pub fn address_offset(&self) -> u64 {
+        self.address_offset
+    }
+    /**
+     * @description: 获取寄存器位宽
+     * @param {*} self  不可变的自己
+     * @return {usize}  位宽
+     */
+    pub fn size(&self) -> usize {
+        self.layout.width()
+    }
+    /**
+     * @description: 获取寄存器复位值
+     * @param {*} self  不可变的自己
+     * @return {u128}   复位值
+     */
+    pub fn reset_value(&self) -> u128 {
+        self.reset_value
+    }
+    /**
+     * @description: 获取寄存器的访问权限,例如read-write
+     * @param {*} self  不可变的自己
+     * @return {Option}     文档中给出或继承了访问权限时返回
+     */
+    pub fn access(&self) -> Option<&str> {
+        self.access.as_deref()
+    }
+    /**
+     * @description: 获取寄存器的布局定义,只包含被接受的位域
+     * @param {*} self  不可变的自己
+     * @return {&LayoutDef}     布局定义
+     */
+    pub fn layout(&self) -> &LayoutDef {
+        &self.layout
+    }
+    /**
+     * @description: 获取被接受的位域的附加信息,顺序与布局定义一致
+     * @param {*} self  不可变的自己
+     * @return {&[SvdField]}    位域附加信息
+     */
+    pub fn fields(&self) -> &[SvdField] {
+        &self.fields
+    }
+    /**
+     * @description: 按名字查找被接受的位域
+     * @param {*} self  不可变的自己
+     * @param {&str} name   位域名字
+     * @return {Option}     找到时返回位域附加信息
+     */
+    pub fn field(&self, name: &str) -> Option<&SvdField> {
+        self.fields.iter().find(|field| field.name == name)
+    }
+    /**
+     * @description: 获取被拒绝的位域
+     * @param {*} self  不可变的自己
+     * @return {&[(String, BitfieldError)]}     (位域名字, 原因)
+     */
+    pub fn rejected(&self) -> &[(String, BitfieldError)] {
+        &self.rejected
+    }
+    /**
+     * @description: 构建位域处理结构体,数据为寄存器复位值,位域的枚举值作为对照表
+     * @param {*} self  不可变的自己
+     * @return {Result} 成功，返回位域处理结构体，存储类型与寄存器位宽不符时，返回失败原因
+     */
+    pub fn bitfield<T: BitStorage>(&self) -> Result<Bitfield<T>, BitfieldError> {
+        let mut layout = self.layout.build()?;
+        for field in self.fields.iter().filter(|field| !field.enumerated_values.is_empty()) {
+            let table = field.enumerated_values.iter().fold(EnumTable::new(), |table, value| table.with_value(value.value, &value.name));
+            layout.set_enum_table(&field.name, Some(table))?;
+        }
+        Ok(Bitfield::from_layout(layout, T::from_raw(self.reset_value)))
+    }
+}
+
+impl SvdField {
+    /**
+     * @description: 获取位域名字
+     * @param {*} self  不可变的自己
+     * @return {&str}   位域名字
+     */
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    /**
+     * @description: 获取位域的访问权限,没有给出时继承寄存器的访问权限
+     * @param {*} self  不可变的自己
+     * @return {Option}     有访问权限时返回
+     */
+    pub fn access(&self) -> Option<&str> {
+        self.access.as_deref()
+    }
+    /**
+     * @description: 获取位域的枚举值
+     * @param {*} self  不可变的自己
+     * @return {&[SvdEnumValue]}    枚举值
+     */
+    pub fn enumerated_values(&self) -> &[SvdEnumValue] {
+        &self.enumerated_values
+    }
+}
+
+impl SvdEnumValue {
+    /**
+     * @description: 获取枚举值名字
+     * @param {*} self  不可变的自己
+     * @return {&str}   名字
+     */
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    /**
+     * @description: 获取枚举值
+     * @param {*} self  不可变的自己
+     * @return {u128}   值
+     */
+    pub fn value(&self) -> u128 {
+        self.value
+    }
+    /**
+     * @description: 获取枚举值说明
+     * @param {*} self  不可变的自己
+     * @return {&str}   说明文字,没有说明时为空字符串
+     */
+    pub fn description(&self) -> &str {
+        &self.description
+    }
+}
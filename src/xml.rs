@@ -0,0 +1,272 @@
+use crate::error::BitfieldError;
+
+/**
+ * 简单的XML元素,只保留名字、属性、子元素和文字,足够读取SVD之类的描述文件
+ * 不支持DTD实体定义和命名空间,注释和处理指令被忽略
+ */
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub(crate) struct Element {
+    pub(crate) name: String,
+    pub(crate) attributes: Vec<(String, String)>,
+    pub(crate) children: Vec<Element>,
+    pub(crate) text: String,
+}
+
+impl Element {
+    /**
+     * @description: 查找第一个指定名字的子元素
+     * @param {*} self  不可变的自己
+     * @param {&str} name   子元素名字
+     * @return {Option}     找到时返回子元素
+     */
+    pub(crate) fn child(&self, name: &str) -> Option<&Element> {
+        self.children.iter().find(|child| child.name == name)
+    }
+    /**
+     * @description: 遍历指定名字的子元素
+     * @param {*} self  不可变的自己
+     * @param {&str} name   子元素名字
+     * @return {Iterator}   子元素
+     */
+    pub(crate) fn children<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a Element> + 'a {
+        self.children.iter().filter(move |child| child.name == name)
+    }
+    /**
+     * @description: 获取指定名字的子元素的文字,去掉首尾空白
+     * @param {*} self  不可变的自己
+     * @param {&str} name   子元素名字
+     * @return {Option}     子元素存在时返回文字
+     */
+    pub(crate) fn child_text(&self, name: &str) -> Option<&str> {
+        self.child(name).map(|child| child.text.trim())
+    }
+    /**
+     * @description: 获取属性值
+     * @param {*} self  不可变的自己
+     * @param {&str} name   属性名字
+     * @return {Option}     属性存在时返回属性值
+     */
+    pub(crate) fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes.iter().find(|(key, _)| key == name).map(|(_, value)| value.as_str())
+    }
+}
+
+/**
+ * XML解析器,位置按字符计数,出错时换算为行号和列号
+ */
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl Parser {
+    /**
+     * @description: 生成当前位置的语法错误
+     * @param {*} self  不可变的自己
+     * @param {String} message  错误信息
+     * @return {BitfieldError}  Syntax错误
+     */
+    fn error(&self, message: String) -> BitfieldError {
+        let before = &self.chars[..self.pos.min(self.chars.len())];
+        let line = before.iter().filter(|c| **c == '\n').count() + 1;
+        let column = before.iter().rev().take_while(|c| **c != '\n').count() + 1;
+        BitfieldError::Syntax { line, column, message }
+    }
+    /**
+     * @description: 当前位置是否以指定文字开头
+     * @param {*} self  不可变的自己
+     * @param {&str} text   文字
+     * @return {bool}   是时返回true
+     */
+    fn starts_with(&self, text: &str) -> bool {
+        (self.pos..).zip(text.chars()).all(|(pos, c)| self.chars.get(pos) == Some(&c))
+    }
+    /**
+     * @description: 跳过指定文字,不匹配时报错
+     * @param {*} mut self  可变的自己
+     * @param {&str} text   文字
+     * @return {Result} 成功，无信息，失败时，返回失败原因
+     */
+    fn expect(&mut self, text: &str) -> Result<(), BitfieldError> {
+        if !self.starts_with(text) {
+            return Err(self.error(format!("expected {}", text)));
+        }
+        self.pos += text.chars().count();
+        Ok(())
+    }
+    /**
+     * @description: 读取到指定结束标记为止的文字,并跳过结束标记
+     * @param {*} mut self  可变的自己
+     * @param {&str} end    结束标记
+     * @return {Result} 成功，返回结束标记之前的文字，失败时，返回失败原因
+     */
+    fn until(&mut self, end: &str) -> Result<String, BitfieldError> {
+        let start = self.pos;
+        while self.pos < self.chars.len() {
+            if self.starts_with(end) {
+                let text = self.chars[start..self.pos].iter().collect();
+                self.pos += end.chars().count();
+                return Ok(text);
+            }
+            self.pos += 1;
+        }
+        self.pos = start;
+        Err(self.error(format!("missing {}", end)))
+    }
+    /**
+     * @description: 跳过空白
+     * @param {*} mut self  可变的自己
+     * @return {无} 无
+     */
+    fn skip_whitespace(&mut self) {
+        while self.chars.get(self.pos).is_some_and(|c| c.is_whitespace()) {
+            self.pos += 1;
+        }
+    }
+    /**
+     * @description: 跳过空白、注释、处理指令和DOCTYPE
+     * @param {*} mut self  可变的自己
+     * @return {Result} 成功，无信息，失败时，返回失败原因
+     */
+    fn skip_misc(&mut self) -> Result<(), BitfieldError> {
+        loop {
+            self.skip_whitespace();
+            if self.starts_with("<!--") {
+                self.until("-->")?;
+            } else if self.starts_with("<?") {
+                self.until("?>")?;
+            } else if self.starts_with("<!DOCTYPE") {
+                self.until(">")?;
+            } else {
+                return Ok(());
+            }
+        }
+    }
+    /**
+     * @description: 读取元素名或属性名
+     * @param {*} mut self  可变的自己
+     * @return {Result} 成功，返回名字，失败时，返回失败原因
+     */
+    fn name(&mut self) -> Result<String, BitfieldError> {
+        let start = self.pos;
+        while self.chars.get(self.pos).is_some_and(|c| !c.is_whitespace() && !"/>=<\"'".contains(*c)) {
+            self.pos += 1;
+        }
+        if self.pos == start {
+            return Err(self.error("expected name".to_string()));
+        }
+        Ok(self.chars[start..self.pos].iter().collect())
+    }
+    /**
+     * @description: 替换文字中的实体引用
+     * @param {*} self  不可变的自己
+     * @param {&str} text   原始文字
+     * @return {Result} 成功，返回替换后的文字，失败时，返回失败原因
+     */
+    fn unescape(&self, text: &str) -> Result<String, BitfieldError> {
+        let mut out = String::new();
+        let mut rest = text;
+        while let Some(index) = rest.find('&') {
+            out.push_str(&rest[..index]);
+            let end = rest[index..].find(';').ok_or_else(|| self.error("unterminated entity".to_string()))?;
+            let entity = &rest[index + 1..index + end];
+            let c = match entity {
+                "lt" => Some('<'),
+                "gt" => Some('>'),
+                "amp" => Some('&'),
+                "quot" => Some('"'),
+                "apos" => Some('\''),
+                _ => match entity.strip_prefix("#x").or_else(|| entity.strip_prefix("#X")) {
+                    Some(hex) => u32::from_str_radix(hex, 16).ok().and_then(char::from_u32),
+                    None => entity.strip_prefix('#').and_then(|dec| dec.parse::<u32>().ok()).and_then(char::from_u32),
+                },
+            };
+            out.push(c.ok_or_else(|| self.error(format!("unknown entity &{};", entity)))?);
+            rest = &rest[index + end + 1..];
+        }
+        out.push_str(rest);
+        Ok(out)
+    }
+    /**
+     * @description: 读取一个元素及其全部子元素
+     * @param {*} mut self  可变的自己
+     * @return {Result} 成功，返回元素，失败时，返回失败原因
+     */
+    fn element(&mut self) -> Result<Element, BitfieldError> {
+        self.expect("<")?;
+        let mut element = Element { name: self.name()?, ..Element::default() };
+        loop {
+            self.skip_whitespace();
+            if self.starts_with("/>") {
+                self.pos += 2;
+                return Ok(element);
+            }
+            if self.starts_with(">") {
+                self.pos += 1;
+                break;
+            }
+            let key = self.name()?;
+            self.skip_whitespace();
+            self.expect("=")?;
+            self.skip_whitespace();
+            let quote = match self.chars.get(self.pos) {
+                Some('"') => "\"",
+                Some('\'') => "'",
+                _ => return Err(self.error("expected quoted attribute value".to_string())),
+            };
+            self.pos += 1;
+            let value = self.until(quote)?;
+            element.attributes.push((key, self.unescape(&value)?));
+        }
+        loop {
+            if self.pos >= self.chars.len() {
+                return Err(self.error(format!("missing </{}>", element.name)));
+            }
+            if self.starts_with("</") {
+                let tag = self.pos;
+                self.pos += 2;
+                let name = self.name()?;
+                if name != element.name {
+                    self.pos = tag;
+                    return Err(self.error(format!("expected </{}>, found </{}>", element.name, name)));
+                }
+                self.skip_whitespace();
+                self.expect(">")?;
+                return Ok(element);
+            } else if self.starts_with("<!--") {
+                self.until("-->")?;
+            } else if self.starts_with("<![CDATA[") {
+                self.pos += 9;
+                let text = self.until("]]>")?;
+                element.text.push_str(&text);
+            } else if self.starts_with("<?") {
+                self.until("?>")?;
+            } else if self.starts_with("<") {
+                element.children.push(self.element()?);
+            } else {
+                let start = self.pos;
+                while self.chars.get(self.pos).is_some_and(|c| *c != '<') {
+                    self.pos += 1;
+                }
+                let text: String = self.chars[start..self.pos].iter().collect();
+                element.text.push_str(&self.unescape(&text)?);
+            }
+        }
+    }
+}
+
+/**
+ * @description: 解析XML文档
+ * @param {&str} text   XML文本
+ * @return {Result} 成功，返回根元素，失败时，返回带行号列号的失败原因
+ */
+pub(crate) fn parse(text: &str) -> Result<Element, BitfieldError> {
+    let mut parser = Parser { chars: text.chars().collect(), pos: 0 };
+    parser.skip_misc()?;
+    let root = parser.element()?;
+    parser.skip_misc()?;
+    if parser.pos < parser.chars.len() {
+        return Err(parser.error("unexpected content after root element".to_string()));
+    }
+    Ok(root)
+}
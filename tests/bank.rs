@@ -0,0 +1,72 @@
+use bitflags::{Access, BitfieldError, Layout, RegisterBank};
+
+fn uart() -> RegisterBank<u32> {
+    let mut ctrl = Layout::<u32>::new();
+    ctrl.insert("EN".to_string(), 0, 1).unwrap();
+    ctrl.insert("BAUD".to_string(), 8, 8).unwrap();
+    ctrl.insert("KEY".to_string(), 24, 8).unwrap();
+    ctrl.set_access("KEY", Access::WriteOnly).unwrap();
+
+    let mut status = Layout::<u32>::new();
+    status.insert("RXNE".to_string(), 0, 1).unwrap();
+    status.insert("OVR".to_string(), 1, 1).unwrap();
+    status.insert("IRQ".to_string(), 2, 1).unwrap();
+    status.insert("ERRCNT".to_string(), 8, 8).unwrap();
+    status.insert("VERSION".to_string(), 24, 8).unwrap();
+    status.set_access("RXNE", Access::ReadOnly).unwrap();
+    status.set_access("OVR", Access::WriteOneToClear).unwrap();
+    status.set_access("IRQ", Access::WriteOneToSet).unwrap();
+    status.set_access("ERRCNT", Access::ReadToClear).unwrap();
+    status.set_access("VERSION", Access::ReadOnly).unwrap();
+
+    let mut bank = RegisterBank::new();
+    bank.add("STATUS".to_string(), 0x04, status, 0x1200_0000).unwrap();
+    bank.add("CTRL".to_string(), 0x00, ctrl, 0x0000_1000).unwrap();
+    bank
+}
+
+#[test]
+fn registers_are_ordered_and_reset() {
+    let mut bank = uart();
+    let names: Vec<(&str, u64)> = bank.registers().map(|(name, offset, _)| (name, offset)).collect();
+    assert_eq!(names, [("CTRL", 0), ("STATUS", 4)]);
+    assert_eq!(bank.get_value("CTRL.BAUD").unwrap(), 0x10);
+    bank.set_value("CTRL.BAUD", 0x20).unwrap();
+    bank.reset();
+    assert_eq!(bank.register("CTRL").unwrap().get_data(), 0x1000);
+    assert_eq!(bank.get_param("STATUS.OVR").unwrap().access(), Access::WriteOneToClear);
+    assert!(matches!(bank.add("UART.X".to_string(), 8, Layout::new(), 0), Err(BitfieldError::DuplicateKey { .. })));
+    assert_eq!(bank.add("DATA".to_string(), 4, Layout::new(), 0), Err(BitfieldError::AddressInUse { offset: 4, key: "STATUS".to_string() }));
+}
+
+#[test]
+fn field_paths_enforce_access() {
+    let mut bank = uart();
+    assert_eq!(bank.get_value("CTRL.KEY"), Err(BitfieldError::AccessDenied { path: "CTRL.KEY".to_string(), access: Access::WriteOnly }));
+    bank.set_value("CTRL.KEY", 0xa5).unwrap();
+    assert_eq!(bank.set_value("STATUS.VERSION", 1), Err(BitfieldError::AccessDenied { path: "STATUS.VERSION".to_string(), access: Access::ReadOnly }));
+
+    bank.register_mut("STATUS").unwrap().set_data(0x1200_0503);
+    bank.set_value("STATUS.OVR", 1).unwrap();
+    bank.set_value("STATUS.IRQ", 1).unwrap();
+    assert_eq!(bank.register("STATUS").unwrap().get_data(), 0x1200_0505);
+    assert_eq!(bank.get_value("STATUS.ERRCNT").unwrap(), 5);
+    assert_eq!(bank.get_value("STATUS.ERRCNT").unwrap(), 0);
+    assert!(matches!(bank.get_value("STATUS"), Err(BitfieldError::KeyNotFound { .. })));
+    assert!(matches!(bank.get_value("TIMER.EN"), Err(BitfieldError::KeyNotFound { .. })));
+}
+
+#[test]
+fn register_access_applies_policies_per_field() {
+    let mut bank = uart();
+    bank.write(0x00, 0xa5_00_20_01).unwrap();
+    assert_eq!(bank.read(0x00).unwrap(), 0x0000_2001);
+    assert_eq!(bank.register("CTRL").unwrap().get_data(), 0xa500_2001);
+
+    bank.register_mut("STATUS").unwrap().set_data(0x1200_0303);
+    bank.write(0x04, 0xffff_fffe).unwrap();
+    assert_eq!(bank.register("STATUS").unwrap().get_data(), 0x12ff_03fd);
+    assert_eq!(bank.read(0x04).unwrap(), 0x12ff_03fd);
+    assert_eq!(bank.read(0x04).unwrap(), 0x12ff_00fd);
+    assert_eq!(bank.read(0x08), Err(BitfieldError::AddressNotFound { offset: 8 }));
+}
@@ -0,0 +1,62 @@
+use bitflags::{Bitfield, BitfieldError, Layout, MockBus, RegisterBus, Transaction};
+
+const CTRL: u64 = 0x4000_0000;
+
+fn ctrl() -> Layout<u32> {
+    let mut layout = Layout::new();
+    layout.insert("EN".to_string(), 0, 1).unwrap();
+    layout.insert("MODE".to_string(), 1, 3).unwrap();
+    layout.insert("DIV".to_string(), 8, 8).unwrap();
+    layout
+}
+
+#[test]
+fn read_modify_write_is_one_read_and_one_write() {
+    let mut bus = MockBus::new();
+    bus.set(CTRL, 0xff00_0000);
+    let written = ctrl().read_modify_write(&mut bus, CTRL, &[("EN", 1), ("DIV", 0x40)]).unwrap();
+    assert_eq!(written, 0xff00_4001);
+    assert_eq!(bus.take_transactions(), [Transaction::Read { addr: CTRL, value: 0xff00_0000 }, Transaction::Write { addr: CTRL, value: 0xff00_4001 }]);
+    assert!(bus.transactions().is_empty());
+    assert_eq!(bus.get(CTRL), 0xff00_4001);
+}
+
+#[test]
+fn failed_update_skips_the_bus() {
+    let mut bus = MockBus::new();
+    let result = ctrl().read_modify_write(&mut bus, CTRL, &[("EN", 1), ("MODE", 8)]);
+    assert_eq!(result, Err(BitfieldError::ValueOutOfRange { value: 8, max: 7 }));
+    assert!(bus.transactions().is_empty());
+}
+
+#[test]
+fn unknown_key_skips_the_read() {
+    let mut bus = MockBus::new();
+    let result = ctrl().read_modify_write(&mut bus, CTRL, &[("EN", 1), ("NOPE", 1)]);
+    assert_eq!(result, Err(BitfieldError::KeyNotFound { key: "NOPE".to_string() }));
+    let mut bitfield = Bitfield::from_layout(ctrl(), 0);
+    assert!(bitfield.read_modify_write(&mut bus, CTRL, &[("DIV", 0x100)]).is_err());
+    assert!(bus.transactions().is_empty());
+}
+
+#[test]
+fn bitfield_loads_and_stores_through_the_bus() {
+    let mut bus = MockBus::new();
+    bus.write(CTRL, 0x0000_0302);
+    let mut bitfield = Bitfield::from_layout(ctrl(), 0);
+    bitfield.read_from(&mut bus, CTRL);
+    assert_eq!(bitfield.get_value("DIV").unwrap(), 3);
+    bitfield.read_modify_write(&mut bus, CTRL, &[("MODE", 5)]).unwrap();
+    assert_eq!(bitfield.get_data(), 0x0000_030a);
+    bitfield.set_value("EN", 1).unwrap();
+    bitfield.write_to(&mut bus, CTRL);
+    let writes: Vec<u32> = bus
+        .transactions()
+        .iter()
+        .filter_map(|transaction| match transaction {
+            Transaction::Write { value, .. } => Some(*value),
+            Transaction::Read { .. } => None,
+        })
+        .collect();
+    assert_eq!(writes, [0x0302, 0x030a, 0x030b]);
+}
@@ -0,0 +1,86 @@
+use bitflags::{BitfieldBytes, BitfieldError, ByteOrder};
+
+#[test]
+fn accessors_describe_the_inserted_field() {
+    let mut bytes = BitfieldBytes::new(vec![0u8; 4]);
+    bytes.insert("plain".to_string(), 0, 4).unwrap();
+    bytes.insert_signed("signed".to_string(), 4, 12).unwrap();
+    bytes.insert_alias("alias".to_string(), 0, 16).unwrap();
+    bytes.insert_with_order("motorola".to_string(), 23, 16, false, ByteOrder::BigEndian).unwrap();
+
+    let plain = bytes.get_param("plain").unwrap();
+    assert_eq!((plain.start(), plain.length(), plain.is_signed(), plain.is_alias(), plain.byte_order()), (0, 4, false, false, None));
+    let signed = bytes.get_param("signed").unwrap();
+    assert_eq!((signed.start(), signed.length(), signed.is_signed(), signed.is_alias()), (4, 12, true, false));
+    assert!(bytes.get_param("alias").unwrap().is_alias());
+    assert_eq!(bytes.get_param("motorola").unwrap().byte_order(), Some(ByteOrder::BigEndian));
+}
+
+#[test]
+fn fields_straddle_byte_boundaries() {
+    let mut bytes = BitfieldBytes::new(vec![0u8; 3]);
+    bytes.insert("low".to_string(), 0, 5).unwrap();
+    bytes.insert("mid".to_string(), 5, 7).unwrap();
+    bytes.insert("high".to_string(), 12, 12).unwrap();
+
+    bytes.set_value("mid", 0x7f).unwrap();
+    assert_eq!(bytes.get_data(), &[0xe0, 0x0f, 0x00]);
+    bytes.set_value("high", 0xabc).unwrap();
+    bytes.set_value("low", 0x15).unwrap();
+    assert_eq!(bytes.get_data(), &[0xf5, 0xcf, 0xab]);
+    assert_eq!((bytes.get_value("low").unwrap(), bytes.get_value("mid").unwrap(), bytes.get_value("high").unwrap()), (0x15, 0x7f, 0xabc));
+}
+
+#[test]
+fn buffers_wider_than_128_bits_hold_128_bit_fields() {
+    let mut bytes = BitfieldBytes::new(vec![0u8; 24]);
+    bytes.insert("head".to_string(), 0, 4).unwrap();
+    bytes.insert("wide".to_string(), 4, 128).unwrap();
+    bytes.insert("tail".to_string(), 132, 60).unwrap();
+    assert_eq!(bytes.get_totalbit(), 192);
+
+    bytes.set_value("wide", u128::MAX).unwrap();
+    bytes.set_value("tail", (1 << 60) - 1).unwrap();
+    assert_eq!(bytes.get_value("head").unwrap(), 0);
+    assert_eq!(bytes.get_value("wide").unwrap(), u128::MAX);
+    assert_eq!(bytes.get_data()[0], 0xf0);
+    assert!(bytes.get_data()[1..].iter().all(|byte| *byte == 0xff));
+
+    bytes.set_value("wide", 1 << 127).unwrap();
+    assert_eq!(bytes.get_value("wide").unwrap(), 1 << 127);
+    assert_eq!(bytes.get_data()[16], 0xf8);
+    assert_eq!(bytes.get_value("tail").unwrap(), (1 << 60) - 1);
+}
+
+#[test]
+fn signed_fields_sign_extend_to_128_bits() {
+    let mut bytes = BitfieldBytes::new(vec![0u8; 20]);
+    bytes.insert_signed("nibble".to_string(), 4, 4).unwrap();
+    bytes.insert_signed("bit".to_string(), 8, 1).unwrap();
+    bytes.insert_signed("full".to_string(), 16, 128).unwrap();
+
+    bytes.set_value("nibble", -8i128 as u128).unwrap();
+    bytes.set_value("bit", -1i128 as u128).unwrap();
+    assert_eq!(&bytes.get_data()[..2], &[0x80, 0x01]);
+    assert_eq!(bytes.get_value("nibble").unwrap() as i128, -8);
+    assert_eq!(bytes.get_value("bit").unwrap() as i128, -1);
+    assert_eq!(bytes.set_value("nibble", 8), Err(BitfieldError::SignedValueOutOfRange { value: 8, min: -8, max: 7 }));
+    assert_eq!(bytes.set_value("bit", 1), Err(BitfieldError::SignedValueOutOfRange { value: 1, min: -1, max: 0 }));
+
+    for value in [i128::MIN, -1, 0, i128::MAX] {
+        bytes.set_value("full", value as u128).unwrap();
+        assert_eq!(bytes.get_value("full").unwrap() as i128, value);
+    }
+}
+
+#[test]
+fn fields_outside_the_buffer_are_rejected() {
+    let mut bytes = BitfieldBytes::new([0u8; 2]);
+    assert_eq!(bytes.insert("a".to_string(), 16, 1), Err(BitfieldError::StartOutOfRange { start: 16, width: 16 }));
+    assert_eq!(bytes.insert("a".to_string(), 12, 5), Err(BitfieldError::LengthOverflow { start: 12, length: 5, width: 16 }));
+    assert_eq!(bytes.insert("a".to_string(), 0, 129), Err(BitfieldError::FieldTooWide { length: 129, max: 128 }));
+    assert_eq!(bytes.get_value("a"), Err(BitfieldError::KeyNotFound { key: "a".to_string() }));
+    bytes.insert("a".to_string(), 12, 4).unwrap();
+    assert_eq!(bytes.set_value("a", 16), Err(BitfieldError::ValueOutOfRange { value: 16, max: 15 }));
+    assert_eq!(bytes.get_data(), &[0, 0]);
+}
@@ -0,0 +1,56 @@
+use std::process::{Command, Output};
+
+/**
+ * @description: 对layouts/demo.layout运行命令行工具
+ * @param {&[&str]} args    命令及其参数
+ * @return {Output} 进程的输出
+ */
+fn run(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_bitflags"))
+        .arg(concat!(env!("CARGO_MANIFEST_DIR"), "/layouts/demo.layout"))
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+/**
+ * @description: 运行命令并检查成功退出
+ * @param {&[&str]} args    命令及其参数
+ * @return {String} 标准输出
+ */
+fn stdout(args: &[&str]) -> String {
+    let output = run(args);
+    assert!(output.status.success(), "{:?}: {}", args, String::from_utf8_lossy(&output.stderr));
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[test]
+fn decode_and_encode_round_trip() {
+    assert_eq!(stdout(&["decode", "0x00000f05"]), "0x00000f05 (32 bits)\n  mode1 [0..1] = 1\n  test  [2..5] = 1\n  level [8..12] = -1\n");
+    assert_eq!(stdout(&["decode", "0X0000_0F05"]), stdout(&["decode", "f05"]));
+    assert_eq!(stdout(&["encode", "mode1=1", "test=0b101", "level=-3"]), "0x00000d15\n");
+}
+
+#[test]
+fn diff_lists_changed_fields() {
+    assert_eq!(stdout(&["diff", "0x0", "0x00000f04"]), "test: 0 -> 1\nlevel: 0 -> -1\n");
+    assert_eq!(stdout(&["diff", "0x5", "0x5"]), "");
+}
+
+#[test]
+fn render_and_format() {
+    let diagram = stdout(&["render", "0x00000f05", "--radix", "bin"]);
+    assert!(diagram.contains("| level |"), "{}", diagram);
+    assert!(diagram.contains("|0b1111 |"), "{}", diagram);
+    assert!(diagram.contains("|0b001|"), "{}", diagram);
+    assert_eq!(stdout(&["format"]), "width 32\nmode1 0 1\ntest 2 3\nlevel 8 4 signed\n");
+}
+
+#[test]
+fn bad_input_fails_with_a_message() {
+    for args in [&["decode", "0x0x12"][..], &["encode", "level=8"], &["render", "--radix", "base3"], &["frobnicate"]] {
+        let output = run(args);
+        assert!(!output.status.success(), "{:?}", args);
+        assert!(!output.stderr.is_empty(), "{:?}", args);
+    }
+}
@@ -0,0 +1,129 @@
+use bitflags::dbc::Multiplex;
+use bitflags::{BitfieldError, ByteOrder, DbcDatabase, Layout};
+
+const DBC: &str = r#"VERSION ""
+
+NS_ :
+    CM_
+    BA_
+
+BU_: ECU GATEWAY
+
+BO_ 256 ENGINE: 8 ECU
+ SG_ RPM : 7|16@0+ (0.25,0) [0|16383.75] "rpm" GATEWAY
+ SG_ TEMP : 16|8@1- (1,-40) [-168|87] "degC" GATEWAY
+ SG_ FLAGS : 24|4@1+ (1,0) [0|15] "" GATEWAY
+
+BO_ 2147484160 FD_STATUS: 64 ECU
+ SG_ MODE M : 0|8@1+ (1,0) [0|255] "" GATEWAY
+ SG_ SPEED m1 : 8|16@1+ (0.01,0) [0|655.35] "km/h" GATEWAY
+ SG_ ANGLE m2 : 8|16@1- (0.1,0) [-3276.8|3276.7] "deg" GATEWAY
+ SG_ TAIL : 511|8@0+ (1,0) [0|255] "" GATEWAY
+
+CM_ SG_ 256 RPM "Engine speed";
+VAL_ 256 FLAGS 0 "None" 1 "Warm" ;
+"#;
+
+#[test]
+fn messages_and_signals_are_parsed() {
+    let db = DbcDatabase::parse(DBC).unwrap();
+    assert_eq!(db.messages().len(), 2);
+    let engine = db.message_by_id(256).unwrap();
+    assert_eq!((engine.name(), engine.size(), engine.transmitter()), ("ENGINE", 8, "ECU"));
+    let rpm = engine.signal("RPM").unwrap();
+    assert_eq!((rpm.start(), rpm.length(), rpm.byte_order(), rpm.is_signed()), (7, 16, ByteOrder::BigEndian, false));
+    assert_eq!((rpm.scale(), rpm.range(), rpm.unit()), ((0.25, 0.0), (0.0, 16383.75), "rpm"));
+    assert_eq!(rpm.receivers(), ["GATEWAY"]);
+    let fd = db.message("FD_STATUS").unwrap();
+    assert_eq!((fd.id(), fd.size()), (0x8000_0200, 64));
+    assert_eq!(fd.signal("ANGLE").unwrap().multiplex(), Multiplex::Multiplexed(2));
+}
+
+#[test]
+fn classic_frame_decodes_motorola_and_intel_signals() {
+    let db = DbcDatabase::parse(DBC).unwrap();
+    let engine = db.message("ENGINE").unwrap();
+    let frame = [0x1f, 0x40, 0xe2, 0x05, 0, 0, 0, 0];
+    assert_eq!(engine.decode(&frame).unwrap(), [("RPM", 2000.0), ("TEMP", -70.0), ("FLAGS", 5.0)]);
+    assert_eq!(engine.encode(&[("RPM", 2000.0), ("TEMP", -70.0), ("FLAGS", 5.0)]).unwrap(), frame);
+}
+
+#[test]
+fn fd_frame_decodes_selected_multiplexed_signals() {
+    let db = DbcDatabase::parse(DBC).unwrap();
+    let fd = db.message("FD_STATUS").unwrap();
+    let mut frame = [0u8; 64];
+    frame[0] = 2;
+    frame[1..3].copy_from_slice(&(-123i16).to_le_bytes());
+    frame[63] = 0xa5;
+    assert_eq!(fd.decode(&frame).unwrap(), [("MODE", 2.0), ("ANGLE", -12.3), ("TAIL", 165.0)]);
+    assert_eq!(fd.encode(&[("MODE", 2.0), ("ANGLE", -12.3), ("TAIL", 165.0)]).unwrap(), frame);
+
+    frame[0] = 1;
+    assert_eq!(fd.decode(&frame).unwrap()[1], ("SPEED", 654.13));
+}
+
+#[test]
+fn encode_rejects_values_outside_the_signal() {
+    let db = DbcDatabase::parse(DBC).unwrap();
+    let engine = db.message("ENGINE").unwrap();
+    assert_eq!(engine.encode(&[("FLAGS", 16.0)]), Err(BitfieldError::physical_out_of_range(16.0, 0.0, 15.0)));
+    assert_eq!(engine.encode(&[("FLAGS", -1.0)]), Err(BitfieldError::physical_out_of_range(-1.0, 0.0, 15.0)));
+    assert_eq!(engine.encode(&[("TEMP", 100.0)]), Err(BitfieldError::physical_out_of_range(100.0, -168.0, 87.0)));
+    assert_eq!(engine.encode(&[("TEMP", f64::INFINITY)]), Err(BitfieldError::physical_out_of_range(f64::INFINITY, -168.0, 87.0)));
+    assert!(engine.encode(&[("RPM", f64::NAN)]).unwrap_err().physical_range().is_some_and(|(value, _, _)| value.is_nan()));
+    assert_eq!(engine.encode(&[("TEMP", -168.0)]).unwrap()[2], 0x80);
+    assert!(matches!(engine.decode(&[0u8; 4]), Err(BitfieldError::BufferSize { expected: 8, actual: 4 })));
+}
+
+#[test]
+fn raw_conversion_matches_layout_scaling() {
+    let db = DbcDatabase::parse("BO_ 1 RAW: 2 ECU\n SG_ U : 0|4@1+ (0.5,1) [0|0] \"\" ECU\n SG_ S : 8|4@1- (2,0) [-100|100] \"\" ECU\n").unwrap();
+    let message = db.message("RAW").unwrap();
+    let mut layout = Layout::<u16>::new();
+    layout.insert("U".to_string(), 0, 4).unwrap();
+    layout.insert_signed("S".to_string(), 8, 4).unwrap();
+    for signal in message.signals() {
+        layout.set_scaling(signal.name(), Some(signal.scaling())).unwrap();
+    }
+    for (name, value) in [("U", 1.0), ("U", 8.5), ("U", 9.0), ("U", 0.0), ("S", -16.0), ("S", 14.0), ("S", 16.0), ("S", -100.0), ("S", f64::NAN)] {
+        let signal = message.signal(name).unwrap();
+        let expected = layout.set_physical(0, name, value).map(|bitfielddata| layout.get_value(bitfielddata, name).unwrap());
+        let raw = signal.raw(value).map(|raw| raw as u16 & 0xf);
+        assert_eq!(raw, expected.map(|raw| raw & 0xf), "{} {}", name, value);
+    }
+    assert_eq!(message.signal("U").unwrap().raw(9.0), Err(BitfieldError::ValueOutOfRange { value: 16, max: 15 }));
+    assert_eq!(message.signal("U").unwrap().raw(0.0), Err(BitfieldError::SignedValueOutOfRange { value: -2, min: 0, max: 15 }));
+    assert_eq!(message.signal("S").unwrap().raw(16.0), Err(BitfieldError::SignedValueOutOfRange { value: 8, min: -8, max: 7 }));
+}
+
+#[test]
+fn zero_factor_is_a_syntax_error() {
+    let err = DbcDatabase::parse("BO_ 1 A: 8 ECU\n SG_ X : 0|8@1+ (0,5) [0|255] \"\" ECU\n").unwrap_err();
+    assert_eq!(err, BitfieldError::Syntax { line: 2, column: 18, message: "factor must be finite and non-zero, found 0".to_string() });
+    assert!(DbcDatabase::parse("BO_ 1 A: 8 ECU\n SG_ X : 0|8@1+ (1e999,5) [0|255] \"\" ECU\n").is_err());
+}
+
+#[test]
+fn syntax_errors_carry_line_and_column() {
+    let err = DbcDatabase::parse("BO_ 1 A: 8 ECU\n SG_ X : 0|8@2+ (1,0) [0|255] \"\" ECU\n").unwrap_err();
+    assert_eq!(err, BitfieldError::Syntax { line: 2, column: 14, message: "expected byte order 0 or 1".to_string() });
+}
+
+#[test]
+fn signals_must_fit_in_the_message() {
+    let err = DbcDatabase::parse("BO_ 1 A: 8 ECU\n SG_ X : 100|8@1+ (1,0) [0|255] \"\" ECU\n").unwrap_err();
+    assert!(matches!(err, BitfieldError::Syntax { line: 2, ref message, .. } if message.starts_with("signal X does not fit in 8 bytes")));
+    assert!(DbcDatabase::parse("BO_ 1 A: 2 ECU\n SG_ X : 15|16@0+ (1,0) [0|0] \"\" ECU\n").is_err());
+    assert!(DbcDatabase::parse("BO_ 1 A: 2 ECU\n SG_ X : 7|16@0+ (1,0) [0|0] \"\" ECU\n").is_ok());
+    assert!(DbcDatabase::parse("BO_ 1 A: 2 ECU\n SG_ X : 8|16@1+ (1,0) [0|0] \"\" ECU\n").is_err());
+}
+
+#[test]
+fn decode_ignores_bytes_after_the_message() {
+    let db = DbcDatabase::parse("BO_ 1 A: 1 ECU\n SG_ X : 0|8@1+ (1,0) [0|0] \"\" ECU\n").unwrap();
+    let message = db.message("A").unwrap();
+    assert_eq!(message.decode(&[0x12, 0xff, 0xff]).unwrap(), [("X", 18.0)]);
+    assert_eq!(message.decode(&[]), Err(BitfieldError::BufferSize { expected: 1, actual: 0 }));
+    assert_eq!(message.encode(&[("X", 18.0)]).unwrap(), [0x12]);
+}
@@ -0,0 +1,102 @@
+use bitflags::{Bitfield, Layout, MockBus, RegisterBus, Transaction};
+
+const ADDR: u64 = 0x10;
+
+/**
+ * 带保留位的影子寄存器:EN为第0位,第1到3位保留,MODE为第4、5位,第6、7位保留,
+ * PRESC为第8到15位,PRESC_HI是PRESC高4位的别名,第16到31位保留
+ */
+fn shadow(bitfielddata: u32) -> Bitfield<u32> {
+    let mut layout = Layout::new();
+    layout.insert("EN".to_string(), 0, 1).unwrap();
+    layout.insert("MODE".to_string(), 4, 2).unwrap();
+    layout.insert("PRESC".to_string(), 8, 8).unwrap();
+    layout.insert_alias("PRESC_HI".to_string(), 12, 4).unwrap();
+    Bitfield::from_layout(layout, bitfielddata)
+}
+
+#[test]
+fn changed_fields_are_tracked_until_commit() {
+    let mut bitfield = shadow(0x0000_0120);
+    assert!(!bitfield.is_dirty());
+    bitfield.set_value("MODE", 2).unwrap();
+    bitfield.set_value("PRESC", 0x01).unwrap();
+    bitfield.set_value("EN", 1).unwrap();
+    assert_eq!(bitfield.dirty_fields().collect::<Vec<&str>>(), ["EN"]);
+    bitfield.set_value("PRESC", 0x31).unwrap();
+    assert_eq!(bitfield.dirty_fields().collect::<Vec<&str>>(), ["EN", "PRESC", "PRESC_HI"]);
+    assert_eq!(bitfield.commit(), (0x0000_ff01, 0x0000_3121));
+    assert!(!bitfield.is_dirty());
+    assert_eq!(bitfield.take_changes(), None);
+}
+
+#[test]
+fn revert_restores_the_committed_value() {
+    let mut bitfield = shadow(0x0000_0120);
+    bitfield.set_value("MODE", 3).unwrap();
+    bitfield.revert();
+    assert_eq!(bitfield.get_data(), 0x0000_0120);
+    bitfield.set_value("MODE", 3).unwrap();
+    assert_eq!(bitfield.take_changes(), Some((0x0000_0030, 0x0000_0130)));
+    bitfield.set_value("EN", 1).unwrap();
+    bitfield.revert();
+    assert_eq!(bitfield.get_committed(), 0x0000_0130);
+    assert_eq!(bitfield.get_data(), 0x0000_0130);
+}
+
+#[test]
+fn changes_confined_to_reserved_bits_are_not_changes() {
+    let mut bitfield = shadow(0x0000_0120);
+    bitfield.set_data(0xffff_01ee);
+    assert!(!bitfield.is_dirty());
+    assert_eq!(bitfield.dirty_fields().count(), 0);
+    assert_eq!(bitfield.take_changes(), None);
+    assert_eq!(bitfield.get_committed(), 0x0000_0120);
+
+    bitfield.set_value("EN", 1).unwrap();
+    assert_eq!(bitfield.take_changes(), Some((0x0000_0001, 0xffff_01ef)));
+    assert_eq!(bitfield.get_committed(), 0xffff_01ef);
+}
+
+#[test]
+fn flush_writes_only_changed_fields_once() {
+    let mut bus = MockBus::new();
+    bus.set(ADDR, 0x0000_0520);
+    let mut bitfield = shadow(0);
+    bitfield.read_from(&mut bus, ADDR);
+    assert!(!bitfield.flush(&mut bus, ADDR));
+    bitfield.set_value("EN", 1).unwrap();
+    bitfield.set_value("MODE", 3).unwrap();
+    //硬件在两次访问之间改变了保留位和PRESC
+    bus.set(ADDR, 0xa500_0a2e);
+    assert!(bitfield.flush(&mut bus, ADDR));
+    assert_eq!(
+        bus.take_transactions(),
+        [
+            Transaction::Read { addr: ADDR, value: 0x0000_0520 },
+            Transaction::Read { addr: ADDR, value: 0xa500_0a2e },
+            Transaction::Write { addr: ADDR, value: 0xa500_0a3f },
+        ]
+    );
+    assert_eq!((bitfield.get_data(), bitfield.get_committed()), (0xa500_0a3f, 0xa500_0a3f));
+    assert!(!bitfield.is_dirty());
+    assert_eq!(bitfield.get_value("PRESC").unwrap(), 0x0a);
+
+    bitfield.set_value("PRESC", 9).unwrap();
+    bitfield.write_to(&mut bus, ADDR);
+    assert!(!bitfield.is_dirty());
+    assert_eq!(bus.read(ADDR), 0xa500_093f);
+}
+
+#[test]
+fn flush_skips_the_bus_when_only_reserved_bits_changed() {
+    let mut bus = MockBus::new();
+    bus.set(ADDR, 0x0000_0520);
+    let mut bitfield = shadow(0);
+    bitfield.read_from(&mut bus, ADDR);
+    bus.take_transactions();
+    bitfield.set_data(0x00ff_052e);
+    assert!(!bitfield.flush(&mut bus, ADDR));
+    assert!(bus.transactions().is_empty());
+    assert_eq!(bus.get(ADDR), 0x0000_0520);
+}
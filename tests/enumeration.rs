@@ -0,0 +1,65 @@
+use bitflags::{Bitfield, BitfieldError, EnumTable, EnumValue, FieldEnum, Layout, SvdDevice};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Mode {
+    Idle,
+    Run,
+    Fault,
+}
+
+impl FieldEnum for Mode {
+    fn from_raw(raw: u128) -> Option<Mode> {
+        match raw {
+            0 => Some(Mode::Idle),
+            1 => Some(Mode::Run),
+            2 => Some(Mode::Fault),
+            _ => None,
+        }
+    }
+    fn to_raw(self) -> u128 {
+        self as u128
+    }
+}
+
+fn control() -> Bitfield<u8> {
+    let mut layout = Layout::<u8>::new();
+    layout.insert("MODE".to_string(), 0, 2).unwrap();
+    layout.insert("LEVEL".to_string(), 2, 3).unwrap();
+    layout.set_enum_table("MODE", Some(EnumTable::new().with_value(0, "Idle").with_value(1, "Run").with_value(2, "Fault"))).unwrap();
+    Bitfield::from_layout(layout, 0)
+}
+
+#[test]
+fn names_map_to_raw_values() {
+    let mut bitfield = control();
+    assert_eq!(bitfield.get_enum("MODE").unwrap(), Some("Idle"));
+    bitfield.set_enum("MODE", "Fault").unwrap();
+    assert_eq!(bitfield.get_data(), 2);
+    assert_eq!(bitfield.get_enum("LEVEL").unwrap(), None);
+    assert_eq!(bitfield.set_enum("MODE", "Sleep"), Err(BitfieldError::UnknownEnumName { key: "MODE".to_string(), name: "Sleep".to_string() }));
+    assert!(matches!(bitfield.get_enum("NOPE"), Err(BitfieldError::KeyNotFound { .. })));
+}
+
+#[test]
+fn unknown_raw_values_are_preserved() {
+    let mut bitfield = control();
+    bitfield.set_value("MODE", 3).unwrap();
+    assert_eq!(bitfield.get_enum("MODE").unwrap(), None);
+    assert_eq!(bitfield.get_enum_as::<Mode>("MODE").unwrap(), EnumValue::Unknown(3));
+    assert_eq!(bitfield.get_enum_as::<Mode>("MODE").unwrap().to_string(), "Unknown(3)");
+    assert!(bitfield.to_string().contains("MODE  [0..2] = Unknown(3)\n"));
+    assert_eq!(bitfield.get_data(), 3);
+
+    bitfield.set_enum_as("MODE", Mode::Run).unwrap();
+    assert_eq!(bitfield.get_enum_as::<Mode>("MODE").unwrap(), EnumValue::Known(Mode::Run));
+    assert!(bitfield.to_string().contains("MODE  [0..2] = Run (1)\n"));
+}
+
+#[test]
+fn svd_enumerated_values_become_tables() {
+    let device = SvdDevice::parse(include_str!("fixtures/timer.svd")).unwrap();
+    let mut cr: Bitfield<u32> = device.peripheral("TIMER0").unwrap().register("CR").unwrap().bitfield().unwrap();
+    assert_eq!(cr.get_enum("MODE").unwrap(), Some("OneShot"));
+    cr.set_enum("MODE", "Periodic").unwrap();
+    assert_eq!(cr.get_value("MODE").unwrap(), 2);
+}
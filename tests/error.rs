@@ -0,0 +1,32 @@
+use std::error::Error;
+
+use bitflags::{Bitfield, BitfieldError};
+
+#[test]
+fn errors_display_their_details() {
+    let mut bitfield = Bitfield::create_u8("a".to_string(), 0, 0, 4).unwrap();
+    assert_eq!(bitfield.get_value("b").unwrap_err().to_string(), "Not found key:b");
+    assert_eq!(bitfield.insert("b".to_string(), 2, 4).unwrap_err().to_string(), "Overlapping fields;b and a both use bits 2..4");
+    assert_eq!(bitfield.insert("a".to_string(), 4, 4).unwrap_err().to_string(), "Key already exists:a");
+    assert_eq!(bitfield.insert("b".to_string(), 8, 1).unwrap_err().to_string(), "Illegal starting position;start:8,width:8");
+    assert_eq!(bitfield.insert("b".to_string(), 6, 4).unwrap_err().to_string(), "Illegal total length;start:6,length:4,width:8");
+    assert_eq!(bitfield.set_value("a", 16).unwrap_err().to_string(), "The value is out of range,the maximum value is:15,value:16");
+}
+
+/**
+ * @description: 用?把位域错误转换为Box<dyn Error>
+ * @param {&str} key    位域名字
+ * @return {Result} 成功，返回位域的值，失败时，返回转换后的错误
+ */
+fn read(key: &str) -> Result<u8, Box<dyn Error>> {
+    let bitfield = Bitfield::create_u8("a".to_string(), 0x0f, 0, 4)?;
+    Ok(bitfield.get_value(key)?)
+}
+
+#[test]
+fn errors_convert_into_boxed_errors() {
+    assert_eq!(read("a").unwrap(), 0x0f);
+    let err = read("b").unwrap_err();
+    assert_eq!(err.to_string(), "Not found key:b");
+    assert_eq!(err.downcast_ref::<BitfieldError>(), Some(&BitfieldError::KeyNotFound { key: "b".to_string() }));
+}
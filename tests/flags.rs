@@ -0,0 +1,62 @@
+use bitflags::{Bitfield, BitfieldError, Layout};
+
+fn status() -> Bitfield<u8> {
+    let mut layout = Layout::<u8>::new();
+    layout.insert("READY".to_string(), 0, 1).unwrap();
+    layout.insert("ERROR".to_string(), 1, 1).unwrap();
+    layout.insert("BUSY".to_string(), 2, 1).unwrap();
+    layout.insert("COUNT".to_string(), 4, 4).unwrap();
+    layout.insert_alias("FAULT".to_string(), 1, 1).unwrap();
+    Bitfield::from_layout(layout, 0x30)
+}
+
+#[test]
+fn single_bit_fields_work_as_flags() {
+    let mut bitfield = status();
+    assert!(!bitfield.is_set("READY").unwrap());
+    bitfield.set_flag("READY").unwrap();
+    bitfield.toggle("ERROR").unwrap();
+    assert!(bitfield.is_set("ERROR").unwrap());
+    bitfield.clear_flag("READY").unwrap();
+    assert_eq!(bitfield.get_data(), 0x32);
+    assert_eq!(bitfield.is_set("COUNT"), Err(BitfieldError::NotAFlag { key: "COUNT".to_string(), length: 4 }));
+}
+
+#[test]
+fn flag_sets_parse_and_format() {
+    let mut bitfield = status();
+    assert_eq!(bitfield.format_flags(), "");
+    let flags = bitfield.parse_flags("BUSY | READY").unwrap();
+    assert_eq!(flags, 0x05);
+    bitfield.union_with(flags);
+    assert_eq!(bitfield.format_flags(), "READY | BUSY");
+    assert_eq!(bitfield.parse_flags(" ERROR ").unwrap(), 0x02);
+    assert_eq!(bitfield.parse_flags("").unwrap(), 0);
+    assert!(matches!(bitfield.parse_flags("READY | IDLE"), Err(BitfieldError::KeyNotFound { .. })));
+}
+
+#[test]
+fn set_operations_only_touch_defined_flags() {
+    let mut bitfield = status();
+    bitfield.union_with(0xff);
+    assert_eq!(bitfield.get_data(), 0x37);
+    bitfield.intersection_with(0x03);
+    assert_eq!((bitfield.get_data(), bitfield.flags()), (0x33, 0x03));
+    bitfield.difference_with(0x01);
+    assert_eq!(bitfield.get_data(), 0x32);
+    bitfield.complement_flags();
+    assert_eq!(bitfield.get_data(), 0x35);
+    assert_eq!(bitfield.layout().flags_mask(), 0x07);
+}
+
+#[test]
+fn aliases_are_not_flags() {
+    let mut bitfield = status();
+    let not_a_flag = Err(BitfieldError::NotAFlag { key: "FAULT".to_string(), length: 1 });
+    assert_eq!(bitfield.is_set("FAULT"), not_a_flag.clone().map(|_| false));
+    assert_eq!(bitfield.set_flag("FAULT"), not_a_flag.clone());
+    assert_eq!(bitfield.toggle("FAULT"), not_a_flag);
+    assert!(matches!(bitfield.parse_flags("READY | FAULT"), Err(BitfieldError::NotAFlag { .. })));
+    assert_eq!(bitfield.get_data(), 0x30);
+    assert!(bitfield.layout().flags().all(|(name, _)| name != "FAULT"));
+}
@@ -0,0 +1,76 @@
+use bitflags::{BitfieldError, Layout, LayoutDef};
+
+const TEXT: &str = r#"
+# 各种写法混在一起
+width   32
+order msb0 big
+
+ctrl  0   4            # 普通位域
+level 4 4 signed "电平, 有符号"
+"with space" 8 8 "含 \"引号\" 和 \\ 以及\n换行"
+word 0 16 alias
+"width" 16 16
+"#;
+
+/**
+ * @description: 检查解析结果是否为指定行列的语法错误
+ * @param {&str} text   布局定义文本
+ * @param {usize} line  期望的行号
+ * @param {usize} column    期望的列号
+ * @return {String} 错误信息
+ */
+fn syntax_at(text: &str, line: usize, column: usize) -> String {
+    match LayoutDef::parse(text) {
+        Err(BitfieldError::Syntax { line: l, column: c, message }) => {
+            assert_eq!((l, c), (line, column), "{}", message);
+            message
+        }
+        other => panic!("expected a syntax error, found {:?}", other),
+    }
+}
+
+#[test]
+fn parse_write_parse_round_trips() {
+    let def = LayoutDef::parse(TEXT).unwrap();
+    let text = def.to_string();
+    let again = LayoutDef::parse(&text).unwrap();
+    assert_eq!(again.to_string(), text);
+    assert_eq!((again.width(), again.bit_order(), again.byte_order()), (def.width(), def.bit_order(), def.byte_order()));
+    let summary = |def: &LayoutDef| -> Vec<(String, u8, u8, bool, bool, String)> {
+        def.fields()
+            .iter()
+            .map(|field| (field.name().to_string(), field.start(), field.length(), field.is_signed(), field.is_alias(), field.description().to_string()))
+            .collect()
+    };
+    assert_eq!(summary(&again), summary(&def));
+    assert_eq!(summary(&def)[2].5, "含 \"引号\" 和 \\ 以及\n换行");
+    assert_eq!(LayoutDef::parse(&again.to_string()).unwrap(), again);
+
+    let layout: Layout<u32> = Layout::parse(TEXT).unwrap();
+    assert_eq!(Layout::<u32>::parse(&layout.to_text()).unwrap().to_text(), layout.to_text());
+}
+
+#[test]
+fn syntax_errors_point_at_the_offending_token() {
+    syntax_at("width 32\nctrl x 4\n", 2, 6);
+    syntax_at("width 32\nctrl 0 4 bogus\n", 2, 10);
+    syntax_at("width 32\nctrl 0 4 \"doc\" extra\n", 2, 16);
+    syntax_at("width 32\n  \"open 0 4\n", 2, 3);
+    syntax_at("width 32\nctrl 0\n", 2, 5);
+    syntax_at("width 12\n", 1, 7);
+    syntax_at("width 8\nwidth 8\n", 2, 1);
+    syntax_at("order lsb0 sideways\nwidth 8\n", 1, 12);
+    syntax_at("# 没有位宽\nctrl 0 4\n", 3, 1);
+}
+
+#[test]
+fn duplicate_names_and_overlaps_point_at_the_field() {
+    let message = syntax_at("width 16\na 0 4\nb 4 4\n\n  a 8 4\n", 5, 5);
+    assert!(message.starts_with("a: "), "{}", message);
+    let message = syntax_at("width 16\na 0 4\nb 4 4\nc   6 4\n", 4, 5);
+    assert!(message.starts_with("c: "), "{}", message);
+    assert!(message.contains('b'), "{}", message);
+    let message = syntax_at("width 8\na 0 4\nb 6 4\n", 3, 3);
+    assert!(message.starts_with("b: "), "{}", message);
+    assert!(LayoutDef::parse("width 16\na 0 8\nall 0 16 alias\n").is_ok());
+}
@@ -0,0 +1,67 @@
+use bitflags::{BitOrder, Bitfield, ByteOrder, Layout};
+
+/**
+ * 有符号位域、MSB0编号的位域和占满存储位宽的位域各一个布局,每个布局配一组要写入的值
+ */
+fn layouts() -> Vec<(Layout<u16>, &'static str, Vec<u16>)> {
+    let mut signed = Layout::<u16>::new();
+    signed.insert("lo".to_string(), 0, 3).unwrap();
+    signed.insert_signed("s".to_string(), 3, 5).unwrap();
+    signed.insert_signed("bit".to_string(), 8, 1).unwrap();
+
+    let mut msb0 = Layout::<u16>::with_order(BitOrder::Msb0, ByteOrder::BigEndian);
+    msb0.insert("head".to_string(), 0, 3).unwrap();
+    msb0.insert_signed("tail".to_string(), 10, 6).unwrap();
+
+    let mut full = Layout::<u16>::new();
+    full.insert_signed("word".to_string(), 0, 16).unwrap();
+
+    vec![
+        (signed, "s", vec![0, 1, 15, -16i16 as u16, -1i16 as u16, 16, -17i16 as u16]),
+        (msb0, "tail", vec![0, 31, -32i16 as u16, -1i16 as u16, 32, -33i16 as u16]),
+        (full, "word", vec![0, 0x7fff, 0x8000, 0xffff]),
+    ]
+}
+
+#[test]
+fn shared_layout_views_match_an_owned_bitfield() {
+    for (layout, key, values) in layouts() {
+        let mut owned = Bitfield::from_layout(layout.clone(), 0xa5a5);
+        let mut view = layout.value(0xa5a5);
+        let mut other = layout.value(0);
+        for value in values {
+            assert_eq!(view.set_value(key, value), owned.set_value(key, value));
+            assert_eq!(view.get_data(), owned.get_data());
+            assert_eq!(view.get_value(key), owned.get_value(key));
+        }
+        other.set_value(key, 1).unwrap();
+        assert_eq!(other.get_value(key).unwrap(), 1);
+        assert_eq!(view.get_data(), owned.get_data());
+    }
+}
+
+#[test]
+fn handle_access_matches_name_based_access() {
+    for (layout, key, values) in layouts() {
+        let handle = layout.field(key).unwrap();
+        let param = layout.get_param(key).unwrap();
+        assert_eq!((handle.offset(), handle.length()), (param.offset(), param.length()));
+
+        let mut owned = Bitfield::from_layout(layout.clone(), 0x5a5a);
+        let mut by_name = Bitfield::from_layout(layout.clone(), 0x5a5a);
+        let mut view = layout.value(0x5a5a);
+        let mut view_by_name = layout.value(0x5a5a);
+        for value in values {
+            let bitfielddata = owned.get_data();
+            assert_eq!(handle.set(bitfielddata, value), layout.set_value(bitfielddata, key, value));
+            assert_eq!(handle.check(value), handle.set(bitfielddata, value).map(|_| ()));
+            assert_eq!(owned.set(handle, value), by_name.set_value(key, value));
+            assert_eq!(view.set(handle, value), view_by_name.set_value(key, value));
+            assert_eq!(owned.get_data(), by_name.get_data());
+            assert_eq!(view.get_data(), view_by_name.get_data());
+            assert_eq!(handle.get(owned.get_data()), layout.get_value(owned.get_data(), key).unwrap());
+            assert_eq!(owned.get(handle), by_name.get_value(key).unwrap());
+            assert_eq!(view.get(handle), view_by_name.get_value(key).unwrap());
+        }
+    }
+}
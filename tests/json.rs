@@ -0,0 +1,123 @@
+#![cfg(feature = "json")]
+
+use bitflags::{BitOrder, Bitfield, BitfieldError, ByteOrder, Layout};
+use serde_json::{json, Value};
+
+fn invalid(path: &str, message: &str) -> BitfieldError {
+    BitfieldError::InvalidDocument { path: path.to_string(), message: message.to_string() }
+}
+
+#[test]
+fn layout_round_trip_keeps_signed_alias_order_and_description() {
+    let mut bitfield = Bitfield::from_layout(Layout::<u32>::with_order(BitOrder::Msb0, ByteOrder::BigEndian), 0);
+    bitfield.insert("zeta".to_string(), 16, 16).unwrap();
+    bitfield.insert_signed("level".to_string(), 4, 4).unwrap();
+    bitfield.insert("mode".to_string(), 0, 4).unwrap();
+    bitfield.insert_alias("head".to_string(), 0, 8).unwrap();
+    bitfield.set_description("level", "电平 \"有符号\"".to_string()).unwrap();
+    bitfield.set_value("level", -3i32 as u32).unwrap();
+    bitfield.set_value("zeta", 0xbeef).unwrap();
+
+    let document = bitfield.to_json();
+    let names: Vec<&str> = document["fields"].as_array().unwrap().iter().map(|field| field["name"].as_str().unwrap()).collect();
+    assert_eq!(names, vec!["zeta", "level", "mode", "head"]);
+    assert_eq!(document["values"]["level"], json!(-3));
+
+    let text = serde_json::to_string(&document).unwrap();
+    let restored = Bitfield::<u32>::from_json(&serde_json::from_str::<Value>(&text).unwrap()).unwrap();
+    assert_eq!(restored.get_data(), bitfield.get_data());
+    assert_eq!((restored.layout().bit_order(), restored.layout().byte_order()), (BitOrder::Msb0, ByteOrder::BigEndian));
+    let summary = |bitfield: &Bitfield<u32>| -> Vec<(String, u8, u8, bool, bool, String)> {
+        bitfield
+            .layout()
+            .fields_by_insertion()
+            .map(|(name, value)| (name.to_string(), value.start(), value.length(), value.is_signed(), value.is_alias(), value.description().to_string()))
+            .collect()
+    };
+    assert_eq!(summary(&restored), summary(&bitfield));
+    assert_eq!(restored.to_json(), document);
+}
+
+#[test]
+fn wide_values_round_trip_as_strings() {
+    let mut bitfield = Bitfield::from_layout(Layout::<u128>::new(), 0);
+    bitfield.insert_signed("big".to_string(), 0, 100).unwrap();
+    bitfield.insert("top".to_string(), 100, 28).unwrap();
+    bitfield.set_value("big", (-1i128 << 90) as u128).unwrap();
+    let document = bitfield.to_json();
+    assert_eq!(document["values"]["big"], json!((-1i128 << 90).to_string()));
+    assert_eq!(Bitfield::<u128>::from_json(&document).unwrap().get_data(), bitfield.get_data());
+}
+
+#[test]
+fn malformed_documents_are_rejected_with_the_path() {
+    let cases = [
+        (json!([]), invalid("", "expected object")),
+        (json!({}), invalid("width", "missing")),
+        (json!({"width": 16, "bit_order": "msb1"}), invalid("bit_order", "expected lsb0 or msb0")),
+        (json!({"width": 16, "fields": {}}), invalid("fields", "expected array")),
+        (json!({"width": 16, "fields": [{"start": 0, "length": 1}]}), invalid("fields[0].name", "missing")),
+        (json!({"width": 16, "fields": [{"name": "a", "start": -1, "length": 1}]}), invalid("fields[0].start", "expected integer 0..=255")),
+        (json!({"width": 16, "fields": [{"name": "a", "start": 0, "length": 1, "signed": "yes"}]}), invalid("fields[0].signed", "expected boolean")),
+        (json!({"width": 16, "fields": [{"name": "a", "start": 0, "length": 1, "signed": true, "alias": true}]}), invalid("fields[0]", "alias fields cannot be signed")),
+        (json!({"width": 32}), BitfieldError::WidthMismatch { expected: 32, actual: 16 }),
+        (json!({"width": 16, "fields": [{"name": "a", "start": 12, "length": 8}]}), BitfieldError::LengthOverflow { start: 12, length: 8, width: 16 }),
+    ];
+    for (document, expected) in cases {
+        assert_eq!(Layout::<u16>::from_json(&document).unwrap_err(), expected, "{}", document);
+    }
+}
+
+#[test]
+fn overlapping_and_duplicate_fields_are_rejected() {
+    let overlapping = json!({"width": 16, "fields": [
+        {"name": "a", "start": 0, "length": 8},
+        {"name": "b", "start": 4, "length": 8},
+    ]});
+    assert_eq!(
+        Layout::<u16>::from_json(&overlapping).unwrap_err(),
+        BitfieldError::Overlap { key: "b".to_string(), other: "a".to_string(), start: 4, end: 8 }
+    );
+    let duplicate = json!({"width": 16, "fields": [
+        {"name": "a", "start": 0, "length": 4},
+        {"name": "a", "start": 4, "length": 4},
+    ]});
+    assert_eq!(Layout::<u16>::from_json(&duplicate).unwrap_err(), BitfieldError::DuplicateKey { key: "a".to_string() });
+}
+
+#[test]
+fn bad_values_are_rejected() {
+    let layout = json!({"width": 8, "fields": [
+        {"name": "u", "start": 0, "length": 4},
+        {"name": "s", "start": 4, "length": 4, "signed": true},
+    ]});
+    let with_values = |values: Value| {
+        let mut document = layout.clone();
+        document["values"] = values;
+        Bitfield::<u8>::from_json(&document).map(|bitfield| bitfield.get_data())
+    };
+    assert_eq!(with_values(json!({"u": 5, "s": -8})), Ok(0x85));
+    assert_eq!(with_values(json!({"u": 16})), Err(BitfieldError::ValueOutOfRange { value: 16, max: 15 }));
+    assert_eq!(with_values(json!({"u": -1})), Err(BitfieldError::SignedValueOutOfRange { value: -1, min: 0, max: 15 }));
+    assert_eq!(with_values(json!({"s": -9})), Err(BitfieldError::SignedValueOutOfRange { value: -9, min: -8, max: 7 }));
+    assert_eq!(with_values(json!({"s": 8})), Err(BitfieldError::SignedValueOutOfRange { value: 8, min: -8, max: 7 }));
+    assert_eq!(with_values(json!({"s": "340282366920938463463374607431768211455"})), Err(BitfieldError::SignedValueOutOfRange { value: i128::MAX, min: -8, max: 7 }));
+    assert_eq!(with_values(json!({"u": 1.5})), Err(invalid("values.u", "expected integer")));
+    assert_eq!(with_values(json!({"x": 1})), Err(BitfieldError::KeyNotFound { key: "x".to_string() }));
+    assert_eq!(with_values(json!([1])), Err(invalid("values", "expected object")));
+}
+
+#[test]
+fn range_errors_match_handle_checks() {
+    let document = json!({"width": 8, "fields": [
+        {"name": "u", "start": 0, "length": 4},
+        {"name": "s", "start": 4, "length": 4, "signed": true},
+    ]});
+    let layout = Layout::<i8>::from_json(&document).unwrap();
+    for (key, value) in [("u", 16), ("s", 8), ("s", -9)] {
+        let mut with_value = document.clone();
+        with_value["values"] = json!({ key: value });
+        let expected = layout.field(key).unwrap().check(value as i8).unwrap_err();
+        assert_eq!(Bitfield::<i8>::from_json(&with_value).unwrap_err(), expected);
+    }
+}
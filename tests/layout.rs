@@ -0,0 +1,176 @@
+use bitflags::{Bitfield, BitfieldError, Layout};
+
+/**
+ * 按插入顺序列出布局中的(名字, 开始地址, 长度, 有符号, 别名, 说明),用于比较失败前后布局是否相同
+ */
+fn snapshot(layout: &Layout<u16>) -> Vec<(String, u8, u8, bool, bool, String)> {
+    layout
+        .fields_by_insertion()
+        .map(|(name, value)| (name.to_string(), value.start(), value.length(), value.is_signed(), value.is_alias(), value.description().to_string()))
+        .collect()
+}
+
+fn overlap(key: &str, other: &str, start: usize, end: usize) -> BitfieldError {
+    BitfieldError::Overlap { key: key.to_string(), other: other.to_string(), start, end }
+}
+
+#[test]
+fn insert_rejects_out_of_range_fields() {
+    let mut layout = Layout::<u16>::new();
+    assert_eq!(layout.insert("a".to_string(), 16, 1), Err(BitfieldError::StartOutOfRange { start: 16, width: 16 }));
+    assert_eq!(layout.insert("a".to_string(), 10, 7), Err(BitfieldError::LengthOverflow { start: 10, length: 7, width: 16 }));
+    layout.insert("a".to_string(), 10, 6).unwrap();
+    layout.insert("full".to_string(), 0, 0).unwrap();
+    assert_eq!(layout.insert("a".to_string(), 0, 1), Err(BitfieldError::DuplicateKey { key: "a".to_string() }));
+    assert_eq!(layout.get_param("b").unwrap_err(), BitfieldError::KeyNotFound { key: "b".to_string() });
+    assert_eq!(layout.del_param("b"), Err(BitfieldError::KeyNotFound { key: "b".to_string() }));
+}
+
+#[test]
+fn overlapping_fields_are_rejected_with_the_shared_bits() {
+    let mut layout = Layout::<u32>::new();
+    layout.insert("low".to_string(), 0, 8).unwrap();
+    layout.insert("high".to_string(), 16, 8).unwrap();
+    assert_eq!(layout.insert("mid".to_string(), 4, 16), Err(overlap("mid", "low", 4, 8)));
+    assert_eq!(layout.insert_signed("mid".to_string(), 20, 8), Err(overlap("mid", "high", 20, 24)));
+    assert_eq!(layout.upsert("low".to_string(), 12, 8).unwrap_err(), overlap("low", "high", 16, 20));
+    assert_eq!(layout.modify("high".to_string(), "top".to_string(), 6, 4), Err(overlap("top", "low", 6, 8)));
+    layout.insert("mid".to_string(), 8, 8).unwrap();
+    assert!(layout.validate().is_ok());
+}
+
+#[test]
+fn aliases_may_overlap_but_are_not_overlapped_against() {
+    let mut bitfield = Bitfield::from_layout(Layout::<u16>::new(), 0);
+    bitfield.insert("lo".to_string(), 0, 4).unwrap();
+    bitfield.insert("hi".to_string(), 4, 4).unwrap();
+    bitfield.insert_alias("byte".to_string(), 0, 8).unwrap();
+    bitfield.upsert("byte".to_string(), 2, 8).unwrap();
+    bitfield.insert("top".to_string(), 8, 8).unwrap();
+    assert!(bitfield.validate().is_ok());
+
+    bitfield.set_value("byte", 0xff).unwrap();
+    assert_eq!((bitfield.get_value("lo").unwrap(), bitfield.get_value("hi").unwrap(), bitfield.get_value("top").unwrap()), (0xc, 0xf, 0x3));
+    assert!(bitfield.get_param("byte").unwrap().is_alias());
+}
+
+#[test]
+fn failed_insert_and_modify_leave_the_layout_unchanged() {
+    let mut layout = Layout::<u16>::new();
+    layout.insert("a".to_string(), 0, 4).unwrap();
+    layout.insert_signed("b".to_string(), 4, 4).unwrap();
+    layout.insert_alias("ab".to_string(), 0, 8).unwrap();
+    layout.set_description("a", "low nibble".to_string()).unwrap();
+    let before = snapshot(&layout);
+
+    assert!(layout.insert("a".to_string(), 8, 4).is_err());
+    assert!(layout.insert_signed("b".to_string(), 8, 4).is_err());
+    assert!(layout.insert_alias("ab".to_string(), 8, 4).is_err());
+    assert!(layout.insert("c".to_string(), 2, 4).is_err());
+    assert!(layout.upsert("a".to_string(), 2, 4).is_err());
+    assert!(layout.upsert("c".to_string(), 12, 8).is_err());
+    assert!(layout.modify("a".to_string(), "b".to_string(), 8, 4).is_err());
+    assert!(layout.modify("a".to_string(), "c".to_string(), 14, 4).is_err());
+    assert!(layout.modify("a".to_string(), "c".to_string(), 3, 2).is_err());
+    assert!(layout.modify("missing".to_string(), "c".to_string(), 8, 4).is_err());
+    assert_eq!(snapshot(&layout), before);
+    assert_eq!(layout.len(), 3);
+}
+
+#[test]
+fn upsert_and_modify_keep_metadata_and_insertion_position() {
+    let mut layout = Layout::<u16>::new();
+    layout.insert("a".to_string(), 0, 4).unwrap();
+    layout.insert_signed("b".to_string(), 4, 4).unwrap();
+    layout.set_description("b", "signed".to_string()).unwrap();
+
+    let replaced = layout.upsert("b".to_string(), 8, 8).unwrap().unwrap();
+    assert_eq!((replaced.start(), replaced.length()), (4, 4));
+    assert_eq!(layout.upsert("c".to_string(), 4, 4).unwrap().map(|value| value.start()), None);
+    layout.modify("a".to_string(), "d".to_string(), 0, 2).unwrap();
+    layout.modify("d".to_string(), "d".to_string(), 0, 3).unwrap();
+    assert_eq!(
+        snapshot(&layout),
+        vec![
+            ("d".to_string(), 0, 3, false, false, String::new()),
+            ("b".to_string(), 8, 8, true, false, "signed".to_string()),
+            ("c".to_string(), 4, 4, false, false, String::new()),
+        ]
+    );
+}
+
+#[test]
+fn one_bit_signed_field_holds_zero_and_minus_one() {
+    let mut bitfield = Bitfield::from_layout(Layout::<i8>::new(), 0);
+    bitfield.insert_signed("s".to_string(), 3, 1).unwrap();
+    bitfield.insert("u".to_string(), 4, 1).unwrap();
+
+    bitfield.set_value("s", -1).unwrap();
+    assert_eq!(bitfield.get_data(), 0b1000);
+    assert_eq!(bitfield.get_value("s").unwrap(), -1);
+    bitfield.set_value("s", 0).unwrap();
+    assert_eq!(bitfield.get_value("s").unwrap(), 0);
+    assert_eq!(bitfield.set_value("s", 1), Err(BitfieldError::SignedValueOutOfRange { value: 1, min: -1, max: 0 }));
+    assert_eq!(bitfield.set_value("s", -2), Err(BitfieldError::SignedValueOutOfRange { value: -2, min: -1, max: 0 }));
+    assert_eq!(bitfield.set_value("u", -1), Err(BitfieldError::ValueOutOfRange { value: 0xff, max: 1 }));
+    assert_eq!(bitfield.get_data(), 0);
+}
+
+#[test]
+fn full_width_signed_field_covers_the_whole_storage_range() {
+    let mut narrow = Bitfield::from_layout(Layout::<u8>::new(), 0);
+    narrow.insert_signed("s".to_string(), 0, 8).unwrap();
+    for (value, raw) in [(i8::MIN, 0x80u8), (-1, 0xff), (0, 0), (i8::MAX, 0x7f)] {
+        narrow.set_value("s", value as u8).unwrap();
+        assert_eq!(narrow.get_data(), raw);
+        assert_eq!(narrow.get_value("s").unwrap() as i8, value);
+    }
+
+    let mut wide = Bitfield::from_layout(Layout::<i128>::new(), 0);
+    wide.insert_signed("s".to_string(), 0, 128).unwrap();
+    for value in [i128::MIN, -1, 0, i128::MAX] {
+        wide.set_value("s", value).unwrap();
+        assert_eq!(wide.get_value("s").unwrap(), value);
+    }
+}
+
+#[test]
+fn signed_fields_sign_extend_below_the_storage_width() {
+    let mut bitfield = Bitfield::from_layout(Layout::<i32>::new(), 0);
+    bitfield.insert_signed("s".to_string(), 4, 12).unwrap();
+    bitfield.insert_signed("top".to_string(), 16, 16).unwrap();
+
+    bitfield.set_value("s", -2048).unwrap();
+    bitfield.set_value("top", -1).unwrap();
+    assert_eq!(bitfield.get_data() as u32, 0xffff_8000);
+    assert_eq!((bitfield.get_value("s").unwrap(), bitfield.get_value("top").unwrap()), (-2048, -1));
+    bitfield.set_value("s", 2047).unwrap();
+    assert_eq!(bitfield.get_value("s").unwrap(), 2047);
+    assert_eq!(bitfield.set_value("s", 2048), Err(BitfieldError::SignedValueOutOfRange { value: 2048, min: -2048, max: 2047 }));
+    assert_eq!(bitfield.set_value("s", -2049), Err(BitfieldError::SignedValueOutOfRange { value: -2049, min: -2048, max: 2047 }));
+    assert_eq!(bitfield.get_data() as u32, 0xffff_7ff0);
+}
+
+#[test]
+fn fields_iterate_by_start_regardless_of_insertion_order() {
+    let mut bitfield = Bitfield::from_layout(Layout::<u16>::new(), 0x1234);
+    bitfield.insert("high".to_string(), 12, 4).unwrap();
+    bitfield.insert("low".to_string(), 0, 4).unwrap();
+    bitfield.insert_alias("word".to_string(), 0, 16).unwrap();
+    bitfield.insert("mid".to_string(), 4, 4).unwrap();
+
+    let starts: Vec<(&str, u8)> = bitfield.layout().fields().map(|(name, value)| (name, value.start())).collect();
+    assert_eq!(starts, vec![("low", 0), ("word", 0), ("mid", 4), ("high", 12)]);
+    let inserted: Vec<&str> = bitfield.layout().fields_by_insertion().map(|(name, _)| name).collect();
+    assert_eq!(inserted, vec!["high", "low", "word", "mid"]);
+    let values: Vec<(&str, u8, u8, u16)> = bitfield.iter().collect();
+    assert_eq!(values, vec![("low", 0, 4, 0x4), ("word", 0, 16, 0x1234), ("mid", 4, 4, 0x3), ("high", 12, 4, 0x1)]);
+    assert_eq!(bitfield.gaps().collect::<Vec<_>>(), vec![]);
+
+    bitfield.del_param("word").unwrap();
+    bitfield.upsert("high".to_string(), 8, 2).unwrap();
+    bitfield.modify("low".to_string(), "low".to_string(), 14, 2).unwrap();
+    let starts: Vec<(&str, u8)> = bitfield.layout().fields().map(|(name, value)| (name, value.start())).collect();
+    assert_eq!(starts, vec![("mid", 4), ("high", 8), ("low", 14)]);
+    assert_eq!(bitfield.gaps().collect::<Vec<_>>(), vec![(0, 4), (10, 4)]);
+}
@@ -0,0 +1,42 @@
+use bitflags::{bitfield_layout, Bitfield, BitfieldError};
+
+bitfield_layout! {
+    /**
+     * 测试用的控制寄存器
+     */
+    pub struct Control(u32) {
+        enable, set_enable: 0, 1;
+        mode, set_mode: 1, 3;
+        channel, set_channel: 8, 8;
+        top, set_top: 24, 8;
+    }
+}
+
+#[test]
+fn generated_accessors_read_and_write_fields() {
+    let mut ctrl = Control::new(0);
+    ctrl.set_enable(1).unwrap();
+    ctrl.set_mode(0b101).unwrap();
+    ctrl.set_channel(0x5a).unwrap();
+    ctrl.set_top(0xff).unwrap();
+    assert_eq!(ctrl.get_data(), 0xff00_5a0b);
+    assert_eq!((ctrl.enable(), ctrl.mode(), ctrl.channel(), ctrl.top()), (1, 5, 0x5a, 0xff));
+
+    assert_eq!(ctrl.set_mode(8), Err(BitfieldError::ValueOutOfRange { value: 8, max: 7 }));
+    assert_eq!(ctrl.get_data(), 0xff00_5a0b);
+    ctrl.set_data(0x1234_5678);
+    assert_eq!((ctrl.enable(), ctrl.mode(), ctrl.channel(), ctrl.top()), (0, 0b100, 0x56, 0x12));
+}
+
+#[test]
+fn generated_layout_matches_the_declaration() {
+    let layout = Control::layout();
+    let fields: Vec<(&str, u8, u8)> = layout.fields().map(|(name, value)| (name, value.start(), value.length())).collect();
+    assert_eq!(fields, vec![("enable", 0, 1), ("mode", 1, 3), ("channel", 8, 8), ("top", 24, 8)]);
+
+    let ctrl = Control::new(0xa5c3_e7f1);
+    let bitfield = Bitfield::from_layout(layout, ctrl.get_data());
+    assert_eq!(bitfield.get_value("mode").unwrap(), ctrl.mode());
+    assert_eq!(bitfield.get_value("channel").unwrap(), ctrl.channel());
+    assert_eq!(bitfield.get_value("top").unwrap(), ctrl.top());
+}
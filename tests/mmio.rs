@@ -0,0 +1,69 @@
+use std::ptr::NonNull;
+
+use bitflags::{BitfieldError, Layout, MmioRegister};
+
+/**
+ * 串口状态和控制寄存器:RXNE和TXE由硬件更新,BAUD和DATA由软件写入,LEVEL为有符号位域
+ */
+fn uart() -> Layout<u32> {
+    let mut layout = Layout::new();
+    layout.insert("RXNE".to_string(), 0, 1).unwrap();
+    layout.insert("TXE".to_string(), 1, 1).unwrap();
+    layout.insert("BAUD".to_string(), 8, 4).unwrap();
+    layout.insert_signed("LEVEL".to_string(), 12, 4).unwrap();
+    layout.insert("DATA".to_string(), 16, 8).unwrap();
+    layout
+}
+
+#[test]
+fn every_access_sees_hardware_updates() {
+    let layout = uart();
+    let mut regs = vec![0u32; 4];
+    regs[1] = 0x0000_0302;
+    let base = regs.as_mut_ptr();
+    {
+        let mut reg = unsafe { MmioRegister::from_ptr(base.add(1), &layout) };
+        assert_eq!(reg.get_value("BAUD").unwrap(), 3);
+        //模拟硬件在两次访问之间收到数据
+        unsafe { base.add(1).write_volatile(0x0041_0301) };
+        assert_eq!(reg.get_value("RXNE").unwrap(), 1);
+        assert_eq!(reg.get_value("DATA").unwrap(), 0x41);
+        reg.set_value("BAUD", 9).unwrap();
+        assert_eq!(reg.read(), 0x0041_0901);
+        let level = layout.field("LEVEL").unwrap();
+        reg.set(level, -2i32 as u32).unwrap();
+        assert_eq!(reg.get(level) as i32, -2);
+        assert_eq!(reg.set_value("BAUD", 16), Err(BitfieldError::ValueOutOfRange { value: 16, max: 15 }));
+    }
+    assert_eq!(regs, [0, 0x0041_e901, 0, 0]);
+}
+
+#[test]
+fn out_of_range_values_never_touch_the_register() {
+    let layout = uart();
+    //指向未映射的低地址,任何读写都会使测试进程崩溃,所以测试通过即说明没有访问寄存器
+    let mut reg = unsafe { MmioRegister::from_ptr(NonNull::<u32>::dangling().as_ptr(), &layout) };
+    assert_eq!(reg.set_value("BAUD", 16), Err(BitfieldError::ValueOutOfRange { value: 16, max: 15 }));
+    assert_eq!(reg.set_value("LEVEL", 8), Err(BitfieldError::SignedValueOutOfRange { value: 8, min: -8, max: 7 }));
+    assert_eq!(reg.set(layout.field("DATA").unwrap(), 0x100), Err(BitfieldError::ValueOutOfRange { value: 0x100, max: 0xff }));
+    assert_eq!(reg.set_value("NOPE", 0), Err(BitfieldError::KeyNotFound { key: "NOPE".to_string() }));
+}
+
+#[test]
+fn modify_is_a_single_read_modify_write() {
+    let layout = uart();
+    let mut word = 0x0000_0003u32;
+    let mut reg = MmioRegister::new(&mut word, &layout);
+    reg.modify(|r| {
+        r.set_value("BAUD", 5)?;
+        r.set_value("DATA", 0x20)
+    })
+    .unwrap();
+    assert_eq!(reg.snapshot().get_value("DATA").unwrap(), 0x20);
+    let result = reg.modify(|r| {
+        r.set_value("BAUD", 2)?;
+        r.set_value("DATA", 0x100)
+    });
+    assert!(matches!(result, Err(BitfieldError::ValueOutOfRange { .. })));
+    assert_eq!(word, 0x0020_0503);
+}
@@ -0,0 +1,113 @@
+use bitflags::{BitOrder, BitfieldBytes, BitfieldError, ByteOrder, Layout};
+
+const FRAME: [u8; 4] = [0x12, 0x34, 0x56, 0x78];
+
+const ORDERS: [(BitOrder, ByteOrder); 4] = [
+    (BitOrder::Lsb0, ByteOrder::LittleEndian),
+    (BitOrder::Msb0, ByteOrder::LittleEndian),
+    (BitOrder::Lsb0, ByteOrder::BigEndian),
+    (BitOrder::Msb0, ByteOrder::BigEndian),
+];
+
+/**
+ * 每种组合下的(名字, 开始地址, 长度, 期望值, 占用的物理位掩码)
+ * byte和word在四种组合下占用相同的物理位,nibbles在小端时占用第0字节高4位,大端时占用低4位
+ */
+fn cases(bit_order: BitOrder, byte_order: ByteOrder) -> Vec<(&'static str, usize, u8, u128, [u8; 4])> {
+    const BYTE: [u8; 4] = [0x00, 0xff, 0x00, 0x00];
+    const WORD: [u8; 4] = [0x00, 0x00, 0xff, 0xff];
+    const LE_NIBBLES: [u8; 4] = [0xf0, 0xff, 0x00, 0x00];
+    const BE_NIBBLES: [u8; 4] = [0x0f, 0xff, 0x00, 0x00];
+    match (bit_order, byte_order) {
+        (BitOrder::Lsb0, ByteOrder::LittleEndian) => {
+            vec![("byte", 8, 8, 0x34, BYTE), ("word", 16, 16, 0x7856, WORD), ("nibbles", 4, 12, 0x341, LE_NIBBLES)]
+        }
+        (BitOrder::Msb0, ByteOrder::LittleEndian) => {
+            vec![("byte", 15, 8, 0x34, BYTE), ("word", 23, 16, 0x7856, WORD), ("nibbles", 3, 12, 0x341, LE_NIBBLES)]
+        }
+        (BitOrder::Lsb0, ByteOrder::BigEndian) => {
+            vec![("byte", 15, 8, 0x34, BYTE), ("word", 23, 16, 0x5678, WORD), ("nibbles", 3, 12, 0x234, BE_NIBBLES)]
+        }
+        (BitOrder::Msb0, ByteOrder::BigEndian) => {
+            vec![("byte", 8, 8, 0x34, BYTE), ("word", 16, 16, 0x5678, WORD), ("nibbles", 4, 12, 0x234, BE_NIBBLES)]
+        }
+    }
+}
+
+fn masked(mask: [u8; 4]) -> Vec<u8> {
+    FRAME.iter().zip(mask).map(|(byte, mask)| byte & mask).collect()
+}
+
+#[test]
+fn bytes_decode_same_frame_under_all_orders() {
+    for (bit_order, byte_order) in ORDERS {
+        let mut bytes = BitfieldBytes::with_order(&FRAME[..], bit_order, byte_order);
+        for (name, start, length, expected, _) in cases(bit_order, byte_order) {
+            bytes.insert_alias(name.to_string(), start, length).unwrap();
+            assert_eq!(bytes.get_value(name).unwrap(), expected, "{:?} {:?} {}", bit_order, byte_order, name);
+        }
+    }
+}
+
+#[test]
+fn bytes_round_trip_under_all_orders() {
+    for (bit_order, byte_order) in ORDERS {
+        for (name, start, length, expected, mask) in cases(bit_order, byte_order) {
+            let mut bytes = BitfieldBytes::with_order(vec![0u8; 4], bit_order, byte_order);
+            bytes.insert(name.to_string(), start, length).unwrap();
+            bytes.set_value(name, expected).unwrap();
+            assert_eq!(bytes.get_value(name).unwrap(), expected);
+            assert_eq!(bytes.get_data(), &masked(mask)[..], "{:?} {:?} {}", bit_order, byte_order, name);
+        }
+    }
+}
+
+#[test]
+fn integer_layout_decodes_same_frame_under_all_orders() {
+    for (bit_order, byte_order) in ORDERS {
+        let mut layout: Layout<u32> = Layout::with_order(bit_order, byte_order);
+        let mut mask = [0u8; 4];
+        for (name, start, length, _, bits) in cases(bit_order, byte_order) {
+            layout.insert_alias(name.to_string(), start as u8, length).unwrap();
+            mask.iter_mut().zip(bits).for_each(|(mask, bits)| *mask |= bits);
+        }
+        let data = layout.data_from_bytes(&FRAME).unwrap();
+        let mut value = layout.value(0);
+        for (name, _, _, expected, _) in cases(bit_order, byte_order) {
+            assert_eq!(layout.get_value(data, name).unwrap() as u128, expected, "{:?} {:?} {}", bit_order, byte_order, name);
+            value.set_value(name, expected as u32).unwrap();
+        }
+        assert_eq!(layout.data_to_bytes(value.get_data()), masked(mask));
+    }
+}
+
+#[test]
+fn overlap_is_detected_on_physical_bits() {
+    let mut bytes = BitfieldBytes::with_order(vec![0u8; 4], BitOrder::Lsb0, ByteOrder::BigEndian);
+    bytes.insert("motorola".to_string(), 7, 16).unwrap();
+    assert!(bytes.insert("inside".to_string(), 12, 2).is_err());
+    bytes.insert("after".to_string(), 23, 8).unwrap();
+}
+
+#[test]
+fn set_order_reports_the_first_failing_field_by_start() {
+    for _ in 0..32 {
+        let mut layout = Layout::<u32>::new();
+        layout.insert("a".to_string(), 26, 4).unwrap();
+        layout.insert("z".to_string(), 24, 2).unwrap();
+        layout.insert("m".to_string(), 0, 8).unwrap();
+        assert_eq!(
+            layout.set_order(BitOrder::Msb0, ByteOrder::LittleEndian),
+            Err(BitfieldError::LengthOverflow { start: 24, length: 2, width: 32 })
+        );
+        assert_eq!((layout.bit_order(), layout.byte_order()), (BitOrder::Lsb0, ByteOrder::LittleEndian));
+
+        let mut bytes = BitfieldBytes::new(vec![0u8; 4]);
+        bytes.insert("a".to_string(), 26, 4).unwrap();
+        bytes.insert("z".to_string(), 24, 2).unwrap();
+        assert_eq!(
+            bytes.set_order(BitOrder::Msb0, ByteOrder::LittleEndian),
+            Err(BitfieldError::LengthOverflow { start: 24, length: 2, width: 32 })
+        );
+    }
+}
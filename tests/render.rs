@@ -0,0 +1,68 @@
+use bitflags::{Bitfield, Layout, Radix};
+
+const RADIXES: [Radix; 4] = [Radix::Binary, Radix::Octal, Radix::Decimal, Radix::Hex];
+
+/**
+ * @description: 取出框图中每一行的位域值,按从高位到低位的顺序
+ * @param {&str} diagram    框图
+ * @return {Vec} 每个格子中去掉空白后的值,没有位域的格子为空字符串
+ */
+fn cells(diagram: &str) -> Vec<String> {
+    diagram
+        .lines()
+        .filter(|line| line.starts_with('|'))
+        .skip(1)
+        .step_by(2)
+        .flat_map(|line| line.trim_matches('|').split('|').map(|cell| cell.trim().to_string()).collect::<Vec<_>>())
+        .collect()
+}
+
+#[test]
+fn narrow_fields_render_all_digits() {
+    let mut bitfield = Bitfield::from_layout(Layout::<u8>::new(), 0);
+    bitfield.insert("f".to_string(), 0, 1).unwrap();
+    bitfield.insert_signed("s".to_string(), 1, 2).unwrap();
+    bitfield.insert("n".to_string(), 3, 5).unwrap();
+    bitfield.set_value("f", 1).unwrap();
+    bitfield.set_value("s", -2i8 as u8).unwrap();
+    bitfield.set_value("n", 22).unwrap();
+
+    let expected = [
+        ["0b10110", "10", "1"],
+        ["0o26", "0o2", "1"],
+        ["22", "-2", "1"],
+        ["0x16", "0x2", "1"],
+    ];
+    for (radix, expected) in RADIXES.into_iter().zip(expected) {
+        assert_eq!(cells(&bitfield.render(radix)), expected, "{:?}", radix);
+    }
+}
+
+#[test]
+fn one_bit_signed_field_widens_its_cell() {
+    let mut bitfield = Bitfield::from_layout(Layout::<u8>::new(), 0);
+    bitfield.insert_signed("s".to_string(), 7, 1).unwrap();
+    bitfield.insert("rest".to_string(), 0, 7).unwrap();
+    bitfield.set_value("s", -1i8 as u8).unwrap();
+    let expected = [["1", "0b0000000"], ["1", "0o0"], ["-1", "0"], ["1", "0x00"]];
+    for (radix, expected) in RADIXES.into_iter().zip(expected) {
+        assert_eq!(cells(&bitfield.render(radix)), expected, "{:?}", radix);
+    }
+}
+
+#[test]
+fn full_width_fields_render_all_digits() {
+    let mut byte = Bitfield::from_layout(Layout::<u8>::new(), 0xb5);
+    byte.insert_signed("w".to_string(), 0, 8).unwrap();
+    let expected = ["0b10110101", "0o265", "-75", "0xb5"];
+    for (radix, expected) in RADIXES.into_iter().zip(expected) {
+        assert_eq!(cells(&byte.render(radix)), [expected], "{:?}", radix);
+    }
+
+    let mut wide = Bitfield::from_layout(Layout::<u128>::new(), u128::MAX);
+    wide.insert("w".to_string(), 0, 128).unwrap();
+    let expected = ["1".repeat(128), format!("0o3{}", "7".repeat(42)), u128::MAX.to_string(), format!("0x{}", "f".repeat(32))];
+    for (radix, expected) in RADIXES.into_iter().zip(expected) {
+        assert_eq!(cells(&wide.render(radix)), vec![expected; 4], "{:?}", radix);
+    }
+}
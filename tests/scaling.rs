@@ -0,0 +1,69 @@
+use bitflags::{Bitfield, BitfieldError, Layout, Scaling};
+
+fn sensor() -> Bitfield<u16> {
+    let mut layout = Layout::<u16>::new();
+    layout.insert("TEMP".to_string(), 0, 8).unwrap();
+    layout.insert_signed("TRIM".to_string(), 8, 4).unwrap();
+    layout.insert("FLAGS".to_string(), 12, 4).unwrap();
+    layout.set_scaling("TEMP", Some(Scaling::new(0.5, -40.0).with_unit("°C").with_range(-40.0, 85.0))).unwrap();
+    layout.set_scaling("TRIM", Some(Scaling::new(0.1, 0.0).with_unit("%"))).unwrap();
+    Bitfield::from_layout(layout, 0)
+}
+
+#[test]
+fn physical_values_round_trip_with_rounding() {
+    let mut bitfield = sensor();
+    bitfield.set_physical("TEMP", 25.3).unwrap();
+    assert_eq!(bitfield.get_value("TEMP").unwrap(), 131);
+    assert_eq!(bitfield.get_physical("TEMP").unwrap(), 25.5);
+    bitfield.set_physical("TRIM", -0.3).unwrap();
+    assert_eq!(bitfield.get_value("TRIM").unwrap(), 0xfffd);
+    assert!((bitfield.get_physical("TRIM").unwrap() + 0.3).abs() < 1e-9);
+    bitfield.set_physical("FLAGS", 9.0).unwrap();
+    assert_eq!(bitfield.get_physical("FLAGS").unwrap(), 9.0);
+}
+
+#[test]
+fn physical_and_raw_ranges_are_checked() {
+    let mut bitfield = sensor();
+    assert_eq!(bitfield.set_physical("TEMP", 90.0), Err(BitfieldError::physical_out_of_range(90.0, -40.0, 85.0)));
+    assert_eq!(bitfield.set_physical("TEMP", 90.0).unwrap_err().physical_range(), Some((90.0, -40.0, 85.0)));
+    assert!(bitfield.set_physical("TEMP", f64::NAN).unwrap_err().physical_range().is_some_and(|(value, _, _)| value.is_nan()));
+    assert_eq!(bitfield.set_physical("TRIM", 0.8), Err(BitfieldError::SignedValueOutOfRange { value: 8, min: -8, max: 7 }));
+    assert_eq!(bitfield.set_physical("FLAGS", 16.0), Err(BitfieldError::ValueOutOfRange { value: 16, max: 15 }));
+    assert!(matches!(bitfield.set_physical("FLAGS", -1.0), Err(BitfieldError::SignedValueOutOfRange { value: -1, .. })));
+    assert_eq!(bitfield.get_data(), 0);
+}
+
+#[test]
+fn scaling_survives_upsert_and_modify() {
+    let mut bitfield = sensor();
+    bitfield.upsert("TEMP".to_string(), 0, 7).unwrap();
+    bitfield.modify("TEMP".to_string(), "T".to_string(), 0, 8).unwrap();
+    assert_eq!(bitfield.get_param("T").unwrap().scaling().unwrap().unit(), "°C");
+}
+
+#[test]
+fn display_shows_units() {
+    let mut bitfield = sensor();
+    bitfield.set_physical("TEMP", 25.0).unwrap();
+    bitfield.set_physical("TRIM", 0.5).unwrap();
+    let text = bitfield.to_string();
+    assert!(text.contains("TEMP  [0..8] = 25.0 °C (raw 130)"), "{}", text);
+    assert!(text.contains("TRIM  [8..12] = 0.5 % (raw 5)"), "{}", text);
+    assert!(text.contains("FLAGS [12..16] = 0\n"), "{}", text);
+}
+
+#[test]
+fn invalid_factors_are_rejected() {
+    let mut bitfield = sensor();
+    for (factor, offset) in [(0.0, 0.0), (-0.0, 1.0), (f64::NAN, 0.0), (f64::INFINITY, 0.0), (1.0, f64::NEG_INFINITY)] {
+        let err = bitfield.set_scaling("FLAGS", Some(Scaling::new(factor, offset))).unwrap_err();
+        assert!(matches!(err, BitfieldError::InvalidScaling { .. }), "{:?}", err);
+    }
+    assert_eq!(bitfield.set_scaling("FLAGS", Some(Scaling::new(0.0, 0.0))).unwrap_err().to_string(), "Invalid scaling;factor:0,offset:0");
+    assert!(bitfield.get_param("FLAGS").unwrap().scaling().is_none());
+    bitfield.set_scaling("FLAGS", Some(Scaling::new(-2.0, 0.0))).unwrap();
+    bitfield.set_physical("FLAGS", -6.0).unwrap();
+    assert_eq!(bitfield.get_value("FLAGS").unwrap(), 3);
+}
@@ -0,0 +1,76 @@
+use std::cell::Cell;
+use std::rc::Rc;
+
+use bitflags::{Access, BitfieldError, Layout, RegisterBank, SimDevice};
+
+fn adc() -> SimDevice<u16> {
+    let mut ctrl = Layout::<u16>::new();
+    ctrl.insert("START".to_string(), 0, 1).unwrap();
+    ctrl.insert("CHANNEL".to_string(), 4, 4).unwrap();
+    ctrl.insert("KEY".to_string(), 8, 8).unwrap();
+    ctrl.set_access("KEY", Access::WriteOnly).unwrap();
+
+    let mut status = Layout::<u16>::new();
+    status.insert("DONE".to_string(), 0, 1).unwrap();
+    status.insert("OVR".to_string(), 1, 1).unwrap();
+    status.insert("MISSED".to_string(), 8, 4).unwrap();
+    status.set_access("DONE", Access::WriteOneToClear).unwrap();
+    status.set_access("OVR", Access::WriteOneToClear).unwrap();
+    status.set_access("MISSED", Access::ReadToClear).unwrap();
+
+    let mut data = Layout::<u16>::new();
+    data.insert("VALUE".to_string(), 0, 12).unwrap();
+    data.set_access("VALUE", Access::ReadOnly).unwrap();
+
+    let mut bank = RegisterBank::new();
+    bank.add("CTRL".to_string(), 0x0, ctrl, 0).unwrap();
+    bank.add("STATUS".to_string(), 0x2, status, 0).unwrap();
+    bank.add("DATA".to_string(), 0x4, data, 0).unwrap();
+    SimDevice::new(bank)
+}
+
+#[test]
+fn writes_follow_register_semantics() {
+    let mut device = adc();
+    device.bank_mut().register_mut("STATUS").unwrap().set_data(0x0303);
+    device.write(0x2, 0xf0f1).unwrap();
+    assert_eq!(device.bank().register("STATUS").unwrap().get_data(), 0x0302);
+    device.set_value("STATUS.OVR", 1).unwrap();
+    assert_eq!(device.get_value("STATUS.MISSED").unwrap(), 3);
+    assert_eq!(device.get_value("STATUS.MISSED").unwrap(), 0);
+
+    device.write(0x0, 0xa5f1).unwrap();
+    assert_eq!(device.read(0x0).unwrap(), 0x00f1);
+    assert_eq!(device.get_value("CTRL.KEY").unwrap(), 0);
+    assert_eq!(device.bank().register("CTRL").unwrap().get_value("KEY").unwrap(), 0xa5);
+
+    device.set_value("DATA.VALUE", 7).unwrap();
+    assert_eq!(device.get_value("DATA.VALUE").unwrap(), 0);
+    assert_eq!(device.set_value("CTRL.CHANNEL", 16), Err(BitfieldError::ValueOutOfRange { value: 16, max: 15 }));
+}
+
+#[test]
+fn hooks_script_the_device() {
+    let mut device = adc();
+    device
+        .on_change("CTRL.START", |bank, _, new| {
+            if new == 1 {
+                bank.register_mut("DATA").unwrap().set_value("VALUE", 0x123).unwrap();
+                bank.register_mut("STATUS").unwrap().set_value("DONE", 1).unwrap();
+                bank.register_mut("CTRL").unwrap().set_value("START", 0).unwrap();
+            }
+        })
+        .unwrap();
+    let cleared = Rc::new(Cell::new(0));
+    let counter = Rc::clone(&cleared);
+    device.on_change("STATUS.DONE", move |_, old, new| counter.set(counter.get() + old * 10 + new)).unwrap();
+
+    device.set_value("CTRL.START", 1).unwrap();
+    assert_eq!(device.get_value("STATUS.DONE").unwrap(), 1);
+    assert_eq!(device.read(0x4).unwrap(), 0x123);
+    assert_eq!(device.get_value("CTRL.START").unwrap(), 0);
+    assert_eq!(cleared.get(), 0);
+    device.write(0x2, 0x0001).unwrap();
+    assert_eq!(cleared.get(), 10);
+    assert!(matches!(device.on_change("CTRL.STOP", |_, _, _| {}), Err(BitfieldError::KeyNotFound { .. })));
+}
@@ -0,0 +1,69 @@
+use bitflags::{BitStorage, Bitfield};
+
+/**
+ * 对每种存储类型检查按类型命名的创建函数、位数、最高位位域的读写和BitStorage的位模式转换
+ */
+macro_rules! storage_tests {
+    ($($test:ident: $create:ident => $t:ty, $u:ty, $max:expr;)*) => {
+        $(
+            #[test]
+            fn $test() {
+                const BITS: u8 = <$t>::BITS as u8;
+                let mut bitfield = Bitfield::$create("top".to_string(), 0, BITS - 1, 1).unwrap();
+                assert_eq!(bitfield.get_totalbit(), BITS as usize);
+                bitfield.set_value("top", 1).unwrap();
+                assert_eq!(bitfield.get_data().to_raw(), 1u128 << (BITS - 1));
+                assert_eq!(bitfield.get_value("top").unwrap(), 1);
+                bitfield.set_value("top", 0).unwrap();
+                assert_eq!(bitfield.get_data(), 0);
+
+                let generic: Bitfield<$t> = Bitfield::new("low".to_string(), <$t>::MAX, 0, BITS / 2).unwrap();
+                assert_eq!(generic.get_value("low").unwrap().to_raw(), <$t>::mask(BITS / 2));
+                assert!(Bitfield::<$t>::new("bad".to_string(), 0, BITS, 1).is_err());
+
+                assert_eq!(<$t as BitStorage>::BITS, BITS);
+                assert_eq!(<$t>::MAX.to_raw(), $max);
+                assert_eq!(<$t>::from_raw(u128::MAX).to_raw(), <$u>::MAX as u128);
+                assert_eq!(<$t>::from_raw(!<$t>::mask(BITS) | 1), 1);
+                assert_eq!(<$t>::from_raw(1u128 << (BITS - 1)).to_raw(), 1u128 << (BITS - 1));
+                assert_eq!(<$t>::mask(0), 0);
+                assert_eq!(<$t>::mask(1), 1);
+                assert_eq!(<$t>::mask(BITS), <$u>::MAX as u128);
+                assert_eq!(<$t>::from_raw(<$t>::sign_extend(1u128 << (BITS - 1), BITS)), <$t>::from_raw(1u128 << (BITS - 1)));
+                assert_eq!(<$t>::sign_extend(0b10, 2), u128::MAX - 1);
+                assert_eq!(<$t>::sign_extend(0b01, 2), 0b01);
+            }
+        )*
+    };
+}
+
+storage_tests! {
+    storage_u8: create_u8 => u8, u8, u8::MAX as u128;
+    storage_i8: create_i8 => i8, u8, u8::MAX as u128 >> 1;
+    storage_u16: create_u16 => u16, u16, u16::MAX as u128;
+    storage_i16: create_i16 => i16, u16, u16::MAX as u128 >> 1;
+    storage_u32: create_u32 => u32, u32, u32::MAX as u128;
+    storage_i32: create_i32 => i32, u32, u32::MAX as u128 >> 1;
+    storage_u64: create_u64 => u64, u64, u64::MAX as u128;
+    storage_i64: create_i64 => i64, u64, u64::MAX as u128 >> 1;
+    storage_u128: create_u128 => u128, u128, u128::MAX;
+    storage_i128: create_i128 => i128, u128, u128::MAX >> 1;
+}
+
+#[test]
+fn signed_storage_truncates_and_keeps_the_sign_bit() {
+    assert_eq!(i8::from_raw(0x1ff), -1);
+    assert_eq!(i8::from_raw(0x180), i8::MIN);
+    assert_eq!((-1i8).to_raw(), 0xff);
+    assert_eq!(i64::from_raw(0x1_8000_0000_0000_0000), i64::MIN);
+    assert_eq!((-2i64).to_raw(), u64::MAX as u128 - 1);
+    assert_eq!(i128::from_raw(u128::MAX), -1);
+    assert_eq!(i128::MIN.to_raw(), 1u128 << 127);
+
+    let mut bitfield = Bitfield::create_i64("top".to_string(), i64::MIN, 63, 1).unwrap();
+    assert_eq!(bitfield.get_value("top").unwrap(), 1);
+    bitfield.set_value("top", 0).unwrap();
+    assert_eq!(bitfield.get_data(), 0);
+    let bitfield = Bitfield::create_i128("high".to_string(), -1, 64, 64).unwrap();
+    assert_eq!(bitfield.get_value("high").unwrap(), u64::MAX as i128);
+}
@@ -0,0 +1,138 @@
+use bitflags::{Bitfield, BitfieldError, SvdDevice};
+
+const TIMER: &str = include_str!("fixtures/timer.svd");
+const MALFORMED: &str = include_str!("fixtures/malformed.svd");
+
+#[test]
+fn registers_get_layouts_with_their_own_width() {
+    let device = SvdDevice::parse(TIMER).unwrap();
+    assert_eq!(device.name(), "DEMO");
+    let timer = device.peripheral("TIMER0").unwrap();
+    assert_eq!(timer.base_address(), 0x4000_0000);
+
+    let cr = timer.register("CR").unwrap();
+    assert_eq!(cr.size(), 32);
+    assert_eq!(cr.description(), "Control & mode register");
+    let fields: Vec<(&str, u8, u8)> = cr.layout().fields().iter().map(|field| (field.name(), field.start(), field.length())).collect();
+    assert_eq!(fields, [("EN", 0, 1), ("MODE", 1, 3), ("PRESCALE", 8, 8)]);
+    assert_eq!(cr.layout().fields()[0].description(), "Counter enable");
+
+    let sr = timer.register("SR").unwrap();
+    assert_eq!((sr.size(), sr.address_offset(), sr.reset_value()), (16, 4, 0x8000));
+    assert_eq!(sr.access(), Some("read-only"));
+    assert_eq!(sr.field("READY").unwrap().access(), Some("read-only"));
+}
+
+#[test]
+fn rejected_fields_are_reported() {
+    let device = SvdDevice::parse(TIMER).unwrap();
+    let rejected: Vec<(&str, &str, &str)> = device
+        .rejected()
+        .filter(|(peripheral, _, _, _)| *peripheral == "TIMER0")
+        .map(|(peripheral, register, field, _)| (peripheral, register, field))
+        .collect();
+    assert_eq!(rejected, [("TIMER0", "CR", "NOWHERE"), ("TIMER0", "CR", "PRESCALE_LO"), ("TIMER0", "CR", "TRIGGER")]);
+
+    let cr = device.peripheral("TIMER0").unwrap().register("CR").unwrap();
+    let reason = |name: &str| cr.rejected().iter().find(|(field, _)| field == name).map(|(_, err)| err.clone()).unwrap();
+    assert_eq!(reason("PRESCALE_LO"), BitfieldError::Overlap { key: "PRESCALE_LO".to_string(), other: "PRESCALE".to_string(), start: 8, end: 10 });
+    assert_eq!(reason("TRIGGER"), BitfieldError::LengthOverflow { start: 30, length: 4, width: 32 });
+    assert!(matches!(reason("NOWHERE"), BitfieldError::InvalidDocument { .. }));
+}
+
+#[test]
+fn access_and_enumerated_values_are_kept() {
+    let device = SvdDevice::parse(TIMER).unwrap();
+    let cr = device.peripheral("TIMER0").unwrap().register("CR").unwrap();
+    assert_eq!(cr.field("EN").unwrap().access(), Some("read-write"));
+    let values: Vec<(&str, u128)> = cr.field("MODE").unwrap().enumerated_values().iter().map(|value| (value.name(), value.value())).collect();
+    assert_eq!(values, [("OneShot", 1), ("Periodic", 2)]);
+}
+
+#[test]
+fn dim_arrays_and_derived_peripherals_are_expanded() {
+    let device = SvdDevice::parse(TIMER).unwrap();
+    let timer = device.peripheral("TIMER0").unwrap();
+    let ccr: Vec<(&str, u64)> = timer.registers().iter().filter(|register| register.name().starts_with("CCR")).map(|register| (register.name(), register.address_offset())).collect();
+    assert_eq!(ccr, [("CCR0", 0x10), ("CCR1", 0x14)]);
+
+    let derived = device.peripheral("TIMER1").unwrap();
+    assert_eq!(derived.base_address(), 0x4000_1000);
+    assert_eq!(derived.registers(), timer.registers());
+}
+
+#[test]
+fn register_builds_bitfield_from_reset_value() {
+    let device = SvdDevice::parse(TIMER).unwrap();
+    let timer = device.peripheral("TIMER0").unwrap();
+    let cr: Bitfield<u32> = timer.register("CR").unwrap().bitfield().unwrap();
+    assert_eq!(cr.get_value("MODE").unwrap(), 1);
+    assert_eq!(cr.get_value("PRESCALE").unwrap(), 3);
+    let sr: Bitfield<u16> = timer.register("SR").unwrap().bitfield().unwrap();
+    assert_eq!(sr.get_value("READY").unwrap(), 1);
+    assert!(matches!(timer.register("SR").unwrap().bitfield::<u32>(), Err(BitfieldError::WidthMismatch { expected: 16, actual: 32 })));
+}
+
+#[test]
+fn malformed_xml_reports_position() {
+    match SvdDevice::parse(MALFORMED) {
+        Err(BitfieldError::Syntax { line, column, message }) => {
+            assert_eq!((line, column), (7, 5));
+            assert_eq!(message, "expected </peripheral>, found </peripherals>");
+        }
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
+
+#[test]
+fn svd_types_and_errors_are_eq() {
+    fn is_eq<T: Eq>() {}
+    is_eq::<SvdDevice>();
+    is_eq::<bitflags::svd::SvdPeripheral>();
+    is_eq::<bitflags::svd::SvdRegister>();
+    is_eq::<BitfieldError>();
+}
+
+/**
+ * @description: 生成只有一个寄存器的SVD文档
+ * @param {&str} register   寄存器元素的内容
+ * @return {String} SVD文档
+ */
+fn single(register: &str) -> String {
+    format!(
+        "<device><name>D</name><peripherals><peripheral><name>P</name><baseAddress>0</baseAddress><registers><register><name>R%s</name>{}</register></registers></peripheral></peripherals></device>",
+        register
+    )
+}
+
+fn invalid_path(register: &str) -> String {
+    match SvdDevice::parse(&single(register)) {
+        Err(BitfieldError::InvalidDocument { path, .. }) => path,
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
+
+#[test]
+fn oversized_numbers_are_rejected() {
+    assert_eq!(invalid_path("<addressOffset>0x10000000000000000</addressOffset>"), "P.R%s.addressOffset");
+    assert_eq!(invalid_path("<addressOffset>0</addressOffset><size>0x10000000000000020</size>"), "P.R%s.size");
+    let base = "<device><name>D</name><peripherals><peripheral><name>P</name><baseAddress>0x10000000000000000</baseAddress></peripheral></peripherals></device>";
+    assert!(matches!(SvdDevice::parse(base), Err(BitfieldError::InvalidDocument { path, .. }) if path == "P.baseAddress"));
+}
+
+#[test]
+fn dim_address_overflow_is_rejected() {
+    assert_eq!(invalid_path("<dim>2</dim><dimIncrement>0x8000000000000000</dimIncrement><addressOffset>0x8000000000000000</addressOffset>"), "P.R%s.dimIncrement");
+    assert_eq!(invalid_path("<dim>3</dim><dimIncrement>0x8000000000000000</dimIncrement><addressOffset>0</addressOffset>"), "P.R%s.dimIncrement");
+    assert_eq!(invalid_path("<dim>2</dim><dimIncrement>0x10000000000000000</dimIncrement><addressOffset>0</addressOffset>"), "P.R%s.dimIncrement");
+}
+
+#[test]
+fn dim_is_bounded() {
+    assert_eq!(invalid_path("<dim>0x10000000000</dim><dimIncrement>4</dimIncrement><addressOffset>0</addressOffset>"), "P.R%s.dim");
+    assert_eq!(invalid_path("<dim>0</dim><dimIncrement>4</dimIncrement><addressOffset>0</addressOffset>"), "P.R%s.dim");
+    assert_eq!(invalid_path("<dim>2</dim><dimIncrement>4</dimIncrement><dimIndex>0-18446744073709551615</dimIndex><addressOffset>0</addressOffset>"), "P.R%s.dimIndex");
+    let device = SvdDevice::parse(&single("<dim>2</dim><dimIncrement>4</dimIncrement><dimIndex>6-7</dimIndex><addressOffset>0x20</addressOffset>")).unwrap();
+    let registers: Vec<(&str, u64)> = device.peripheral("P").unwrap().registers().iter().map(|register| (register.name(), register.address_offset())).collect();
+    assert_eq!(registers, [("R6", 0x20), ("R7", 0x24)]);
+}